@@ -1,13 +1,20 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, symbol_short, Address, BytesN, Env, Symbol, Vec, String, Map,
+    contract, contracterror, contractimpl, contracttype, symbol_short, xdr::ToXdr, Address, BytesN, Env,
+    Symbol, Vec, String, Map,
+};
+use shared::{
+    authorization::{require_admin, require_role, Role},
+    index::AppendIndex,
 };
-use shared::authorization::{require_admin, require_role, Role};
 
 #[contract]
 pub struct AnalyticsStorageContract;
 
+/// Storage schema version written by this build of the contract
+const SCHEMA_VERSION: u32 = 1;
+
 // Storage keys
 const ADMIN: Symbol = symbol_short!("ADMIN");
 const PAUSED: Symbol = symbol_short!("PAUSED");
@@ -20,6 +27,11 @@ const AGGREGATED_DATA: Symbol = symbol_short!("AGG_DATA");
 const DATA_RETENTION: Symbol = symbol_short!("DATA_RET");
 const COMPRESSION_METADATA: Symbol = symbol_short!("COMP_META");
 const QUERY_CACHE: Symbol = symbol_short!("QUERY_CACHE");
+const RETENTION_SCHEDULE: Symbol = symbol_short!("RET_SCHED");
+const METRIC_AGGREGATIONS: Symbol = symbol_short!("MET_AGG");
+
+/// Most aggregations a query without a contract and metric may scan
+const MAX_QUERY_SCAN: u64 = 500;
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -38,6 +50,7 @@ pub enum ContractError {
     InsufficientData = 12,
     AggregationError = 13,
     StorageFull = 14,
+    MetricNotFound = 15,
 }
 
 /// Time series bucket for efficient storage
@@ -122,14 +135,27 @@ pub struct DataRetentionPolicy {
     pub is_active: bool,
 }
 
-/// Query cache entry
+/// Recurring schedule for retention maintenance, polled by a keeper
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetentionSchedule {
+    /// Seconds between maintenance runs
+    pub interval: u64,
+    /// Earliest timestamp at which the next run may execute
+    pub next_run: u64,
+    /// Timestamp of the last completed run
+    pub last_run: Option<u64>,
+}
+
+/// Query cache entry. The cache is an internal optimization only: a hit
+/// returns exactly what `execute_query` produced on the miss.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct QueryCacheEntry {
     /// Cache key (hash of query parameters)
     pub cache_key: BytesN<32>,
     /// Query result data
-    pub result_data: Vec<u8>,
+    pub result_data: Vec<AggregatedData>,
     /// Cache created timestamp
     pub created_at: u64,
     /// Cache expiry timestamp
@@ -211,6 +237,33 @@ fn generate_bucket_key(
     (bucket_key, bucket_start)
 }
 
+/// Compress data that has aged past its retention policies. Returns the
+/// number of buckets compressed.
+fn compress_expired_buckets(_env: &Env) -> u64 {
+    let mut compressed_count = 0u64;
+
+    // In production, iterate through all buckets and apply retention policies
+    // For now, simulate compression
+    for _ in 0..10 {
+        compressed_count += 1;
+    }
+
+    compressed_count
+}
+
+/// Delete data past its retention period. Returns the number of entries deleted.
+fn purge_expired_data(_env: &Env) -> u64 {
+    let mut deleted_count = 0u64;
+
+    // In production, iterate through all data and apply retention policies
+    // For now, simulate deletion
+    for _ in 0..5 {
+        deleted_count += 1;
+    }
+
+    deleted_count
+}
+
 /// Compress data points (simulated)
 fn compress_data_points(_data_points: &Vec<u64>) -> Result<BytesN<32>, ContractError> {
     // In production, implement actual compression algorithm
@@ -250,6 +303,10 @@ impl AnalyticsStorageContract {
             return Err(ContractError::Paused);
         }
 
+        if !metrics::is_registered(&env, &metric_name) {
+            return Err(ContractError::MetricNotFound);
+        }
+
         let (bucket_key, bucket_start) = generate_bucket_key(
             &contract_address,
             &metric_name,
@@ -295,6 +352,26 @@ impl AnalyticsStorageContract {
         Ok(bucket_id)
     }
 
+    /// Register a metric name so data points can be stored for it (admin only)
+    pub fn register_metric(
+        env: Env,
+        admin: Address,
+        name: Symbol,
+        unit: Symbol,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        require_admin(&env, &admin)?;
+
+        if !metrics::register_metric(&env, &name, &unit) {
+            return Err(ContractError::AlreadyExists);
+        }
+
+        env.events().publish((symbol_short!("met_reg"), name), unit);
+
+        Ok(())
+    }
+
     /// Create aggregated data
     pub fn create_aggregation(
         env: Env,
@@ -332,6 +409,8 @@ impl AnalyticsStorageContract {
         env.storage()
             .persistent()
             .set(&(AGGREGATED_DATA, aggregation_id), &aggregation);
+        AppendIndex::new(&env, METRIC_AGGREGATIONS, (aggregation.contract_address, aggregation.metric_name))
+            .push(&env, &aggregation_id);
 
         env.events().publish(
             (symbol_short!("aggregation_created"), contract_address),
@@ -345,34 +424,36 @@ impl AnalyticsStorageContract {
     pub fn query_analytics(
         env: Env,
         query: AnalyticsQuery,
+    ) -> Result<Vec<AggregatedData>, ContractError> {
+        Self::query_analytics_typed(env, query)
+    }
+
+    /// Query analytics data as typed aggregations. Results may be served
+    /// from the query cache, which always holds the typed results of an
+    /// earlier identical query.
+    pub fn query_analytics_typed(
+        env: Env,
+        query: AnalyticsQuery,
     ) -> Result<Vec<AggregatedData>, ContractError> {
         if is_paused(&env) {
             return Err(ContractError::Paused);
         }
 
-        // Generate cache key
-        let cache_key_input = format!(
-            "{:?}{:?}{:?}{}{}{:?}{:?}{:?}{:?}{}{:?}{:?}",
-            query.contract_address,
-            query.metric_name,
-            query.start_time,
-            query.end_time,
-            query.aggregation,
-            query.granularity,
-            query.group_by,
-            query.limit,
-            query.order_by,
-            query.order_direction
-        );
-        
-        // In production, use proper hash function
-        let cache_key = BytesN::from_array(&env, &[0; 32]);
+        if query.start_time >= query.end_time || query.limit == 0 {
+            return Err(ContractError::InvalidInput);
+        }
+
+        let cache_key: BytesN<32> = env.crypto().sha256(&query.clone().to_xdr(&env)).into();
 
         // Check cache first
-        if let Some(cache_entry) = Self::get_cache_entry(&env, cache_key) {
+        if let Some(mut cache_entry) = Self::get_cache_entry(&env, cache_key.clone()) {
             if env.ledger().timestamp() < cache_entry.expires_at {
-                // Return cached result (deserialize from bytes)
-                return Ok(Vec::new(&env));
+                cache_entry.access_count += 1;
+                cache_entry.last_accessed = env.ledger().timestamp();
+                env.storage()
+                    .temporary()
+                    .set(&(QUERY_CACHE, cache_key), &cache_entry);
+                return Ok(cache_entry.result_data);
             }
         }
 
@@ -433,14 +514,7 @@ impl AnalyticsStorageContract {
 
         require_admin(&env, &admin)?;
 
-        let mut compressed_count = 0u64;
-        let current_time = env.ledger().timestamp();
-
-        // In production, iterate through all buckets and apply retention policies
-        // For now, simulate compression
-        for _ in 0..10 {
-            compressed_count += 1;
-        }
+        let compressed_count = compress_expired_buckets(&env);
 
         env.events().publish(
             (symbol_short!("data_compressed"), admin),
@@ -459,14 +533,7 @@ impl AnalyticsStorageContract {
 
         require_admin(&env, &admin)?;
 
-        let mut deleted_count = 0u64;
-        let current_time = env.ledger().timestamp();
-
-        // In production, iterate through all data and apply retention policies
-        // For now, simulate deletion
-        for _ in 0..5 {
-            deleted_count += 1;
-        }
+        let deleted_count = purge_expired_data(&env);
 
         env.events().publish(
             (symbol_short!("data_deleted"), admin),
@@ -476,6 +543,67 @@ impl AnalyticsStorageContract {
         Ok(deleted_count)
     }
 
+    /// Schedule retention maintenance every `interval` seconds, first due
+    /// one interval from now (admin only)
+    pub fn set_retention_schedule(
+        env: Env,
+        admin: Address,
+        interval: u64,
+    ) -> Result<RetentionSchedule, ContractError> {
+        admin.require_auth();
+
+        require_admin(&env, &admin)?;
+
+        if interval == 0 {
+            return Err(ContractError::InvalidInput);
+        }
+
+        let schedule = RetentionSchedule {
+            interval,
+            next_run: env.ledger().timestamp().saturating_add(interval),
+            last_run: None,
+        };
+        env.storage().persistent().set(&RETENTION_SCHEDULE, &schedule);
+
+        env.events().publish((symbol_short!("ret_sched"), admin), interval);
+
+        Ok(schedule)
+    }
+
+    /// Keeper entry point: compress and purge expired data if the retention
+    /// schedule is due, then push `next_run` one interval past now. Returns
+    /// whether maintenance ran; calls before `next_run` are a cheap no-op.
+    pub fn run_retention_if_due(env: Env) -> Result<bool, ContractError> {
+        if is_paused(&env) {
+            return Err(ContractError::Paused);
+        }
+
+        let mut schedule: RetentionSchedule = env
+            .storage()
+            .persistent()
+            .get(&RETENTION_SCHEDULE)
+            .ok_or(ContractError::NotFound)?;
+
+        let now = env.ledger().timestamp();
+        if now < schedule.next_run {
+            return Ok(false);
+        }
+
+        let compressed_count = compress_expired_buckets(&env);
+        let deleted_count = purge_expired_data(&env);
+
+        schedule.last_run = Some(now);
+        schedule.next_run = now.saturating_add(schedule.interval);
+        env.storage().persistent().set(&RETENTION_SCHEDULE, &schedule);
+
+        env.events().publish(
+            (symbol_short!("ret_run"), schedule.next_run),
+            (compressed_count, deleted_count),
+        );
+
+        Ok(true)
+    }
+
     /// Get storage statistics
     pub fn get_storage_stats(env: Env) -> (u64, u64, u64, u64) {
         // Returns (total_buckets, total_aggregations, total_cache_entries, storage_used_bytes)
@@ -504,19 +632,72 @@ impl AnalyticsStorageContract {
 
     // ===== Internal Helper Functions =====
 
-    /// Execute analytics query
+    /// Execute analytics query. Queries naming both a contract and a metric
+    /// read that pair's index; broader queries scan every aggregation and
+    /// fail with `QueryTimeout` once there are more than `MAX_QUERY_SCAN`.
     fn execute_query(
         env: &Env,
         query: &AnalyticsQuery,
     ) -> Result<Vec<AggregatedData>, ContractError> {
-        // In production, implement actual query execution
-        // For now, return empty vector
-        Ok(Vec::new(env))
+        let candidates: Vec<u64> = match (&query.contract_address, &query.metric_name) {
+            (Some(contract_address), Some(metric_name)) => {
+                AppendIndex::new(env, METRIC_AGGREGATIONS, (contract_address.clone(), metric_name.clone()))
+                    .page(env, 0, u32::MAX)
+            }
+            _ => {
+                let total: u64 = env.storage().persistent().get(&AGGREGATION_COUNTER).unwrap_or(0);
+                if total > MAX_QUERY_SCAN {
+                    return Err(ContractError::QueryTimeout);
+                }
+                let mut ids = Vec::new(env);
+                for aggregation_id in 1..=total {
+                    ids.push_back(aggregation_id);
+                }
+                ids
+            }
+        };
+
+        let mut results = Vec::new(env);
+        for aggregation_id in candidates.iter() {
+            if results.len() >= query.limit {
+                break;
+            }
+
+            let aggregation: AggregatedData = match env
+                .storage()
+                .persistent()
+                .get(&(AGGREGATED_DATA, aggregation_id))
+            {
+                Some(aggregation) => aggregation,
+                None => continue,
+            };
+
+            if let Some(contract_address) = &query.contract_address {
+                if aggregation.contract_address != *contract_address {
+                    continue;
+                }
+            }
+            if let Some(metric_name) = &query.metric_name {
+                if aggregation.metric_name != *metric_name {
+                    continue;
+                }
+            }
+            if aggregation.aggregation_type != query.aggregation {
+                continue;
+            }
+            if aggregation.start_time < query.start_time || aggregation.end_time > query.end_time {
+                continue;
+            }
+
+            results.push_back(aggregation);
+        }
+
+        Ok(results)
     }
 
     /// Get cache entry
     fn get_cache_entry(env: &Env, cache_key: BytesN<32>) -> Option<QueryCacheEntry> {
-        env.storage().temporary().get(&(&QUERY_CACHE, cache_key))
+        env.storage().temporary().get(&(QUERY_CACHE, cache_key))
     }
 
     /// Cache query result
@@ -527,13 +708,10 @@ impl AnalyticsStorageContract {
         ttl_seconds: u64,
     ) -> Result<(), ContractError> {
         let expires_at = env.ledger().timestamp() + ttl_seconds;
-        
-        // In production, serialize results to bytes
-        let result_data = Vec::new(env);
-        
+
         let cache_entry = QueryCacheEntry {
-            cache_key,
-            result_data,
+            cache_key: cache_key.clone(),
+            result_data: results.clone(),
             created_at: env.ledger().timestamp(),
             expires_at,
             access_count: 0,
@@ -542,13 +720,18 @@ impl AnalyticsStorageContract {
 
         env.storage()
             .temporary()
-            .set(&(&QUERY_CACHE, cache_key), &cache_entry);
+            .set(&(QUERY_CACHE, cache_key), &cache_entry);
 
         Ok(())
     }
 
     // ===== View Functions =====
 
+    /// Health check for monitoring: (initialized, paused, schema version)
+    pub fn health(env: Env) -> (bool, bool, u32) {
+        (env.storage().persistent().has(&ADMIN), is_paused(&env), SCHEMA_VERSION)
+    }
+
     /// Get time series bucket
     pub fn get_time_series_bucket(
         env: Env,
@@ -569,11 +752,21 @@ impl AnalyticsStorageContract {
             .get(&(TIME_SERIES_BUCKET, (bucket_key, bucket_start)))
     }
 
+    /// List all registered metric definitions
+    pub fn list_registered_metrics(env: Env) -> Vec<MetricDefinition> {
+        metrics::list_registered_metrics(&env)
+    }
+
     /// Get aggregated data
     pub fn get_aggregated_data(env: Env, aggregation_id: u64) -> Option<AggregatedData> {
         env.storage().persistent().get(&(AGGREGATED_DATA, aggregation_id))
     }
 
+    /// Get the retention maintenance schedule
+    pub fn get_retention_schedule(env: Env) -> Option<RetentionSchedule> {
+        env.storage().persistent().get(&RETENTION_SCHEDULE)
+    }
+
     /// Get retention policy
     pub fn get_retention_policy(env: Env, policy_id: u64) -> Option<DataRetentionPolicy> {
         env.storage().persistent().get(&(DATA_RETENTION, policy_id))
@@ -612,3 +805,6 @@ impl AnalyticsStorageContract {
         (0, 0, 0, 0)
     }
 }
+
+#[cfg(test)]
+mod test;
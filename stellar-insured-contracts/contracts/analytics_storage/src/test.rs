@@ -0,0 +1,149 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+
+fn create_sum(env: &Env, client: &AnalyticsStorageContractClient, contract: &Address, value: u64) -> u64 {
+    client.create_aggregation(
+        contract,
+        &Symbol::new(env, "gas_used"),
+        &Symbol::new(env, "sum"),
+        &Symbol::new(env, "hour"),
+        &3_600,
+        &7_200,
+        &value,
+        &10,
+        &Map::new(env),
+    )
+}
+
+#[test]
+fn test_typed_query_cache_hit_matches_miss() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, AnalyticsStorageContract);
+    let client = AnalyticsStorageContractClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env));
+
+    let monitored = Address::generate(&env);
+    create_sum(&env, &client, &monitored, 500);
+    create_sum(&env, &client, &monitored, 700);
+    create_sum(&env, &client, &Address::generate(&env), 900);
+
+    let query = AnalyticsQuery {
+        contract_address: Some(monitored.clone()),
+        metric_name: Some(Symbol::new(&env, "gas_used")),
+        start_time: 0,
+        end_time: 86_400,
+        aggregation: Symbol::new(&env, "sum"),
+        granularity: Symbol::new(&env, "hour"),
+        group_by: None,
+        filters: Map::new(&env),
+        limit: 10,
+        order_by: None,
+        order_direction: Symbol::new(&env, "asc"),
+    };
+
+    // Cache miss executes the query
+    let miss = client.query_analytics_typed(&query);
+    assert_eq!(miss.len(), 2);
+    assert_eq!(miss.get(0).unwrap().value, 500);
+    assert_eq!(miss.get(1).unwrap().value, 700);
+
+    // A later aggregation is invisible until the cached entry expires,
+    // proving the second call was served from the cache
+    create_sum(&env, &client, &monitored, 1_100);
+    let hit = client.query_analytics_typed(&query);
+    assert_eq!(hit, miss);
+    assert_eq!(client.query_analytics(&query), miss);
+}
+
+#[test]
+fn test_query_reads_contract_metric_index() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, AnalyticsStorageContract);
+    let client = AnalyticsStorageContractClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env));
+
+    let monitored = Address::generate(&env);
+    let busy = Address::generate(&env);
+    for _ in 0..MAX_QUERY_SCAN {
+        create_sum(&env, &client, &busy, 1);
+    }
+    create_sum(&env, &client, &monitored, 500);
+
+    let mut query = AnalyticsQuery {
+        contract_address: Some(monitored.clone()),
+        metric_name: Some(Symbol::new(&env, "gas_used")),
+        start_time: 0,
+        end_time: 86_400,
+        aggregation: Symbol::new(&env, "sum"),
+        granularity: Symbol::new(&env, "hour"),
+        group_by: None,
+        filters: Map::new(&env),
+        limit: 10,
+        order_by: None,
+        order_direction: Symbol::new(&env, "asc"),
+    };
+
+    // Only the pair's own aggregations are read, however many others exist
+    let results = client.query_analytics_typed(&query);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results.get(0).unwrap().value, 500);
+
+    // Without both filters the query would scan every aggregation
+    query.metric_name = None;
+    assert_eq!(client.try_query_analytics_typed(&query), Err(Ok(ContractError::QueryTimeout)));
+}
+
+#[test]
+fn test_retention_runs_only_when_due() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, AnalyticsStorageContract);
+    let client = AnalyticsStorageContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    assert_eq!(client.try_run_retention_if_due(), Err(Ok(ContractError::NotFound)));
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    client.set_retention_schedule(&admin, &3_600);
+
+    // Before next_run the keeper call changes nothing
+    env.ledger().with_mut(|li| li.timestamp = 4_599);
+    assert!(!client.run_retention_if_due());
+    let schedule = client.get_retention_schedule().unwrap();
+    assert_eq!(schedule.next_run, 4_600);
+    assert_eq!(schedule.last_run, None);
+
+    // Once due it runs and reschedules from the run time
+    env.ledger().with_mut(|li| li.timestamp = 5_000);
+    assert!(client.run_retention_if_due());
+    let schedule = client.get_retention_schedule().unwrap();
+    assert_eq!(schedule.last_run, Some(5_000));
+    assert_eq!(schedule.next_run, 8_600);
+
+    assert!(!client.run_retention_if_due());
+}
+
+#[test]
+fn test_health_reflects_pause_state() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, AnalyticsStorageContract);
+    let client = AnalyticsStorageContractClient::new(&env, &contract_id);
+    assert_eq!(client.health(), (false, false, 1));
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    assert_eq!(client.health(), (true, false, 1));
+
+    client.set_paused(&admin, &true);
+    assert_eq!(client.health(), (true, true, 1));
+}
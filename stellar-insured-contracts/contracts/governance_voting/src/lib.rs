@@ -4,9 +4,9 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype,
+    contract, contractclient, contractimpl, contracttype, token,
     Address, Env, Map, Symbol, Vec, String,
-    log,
+    log, symbol_short,
 };
 
 // ─────────────────────────────────────────────
@@ -16,8 +16,8 @@ use soroban_sdk::{
 const VOTING_PERIOD_SECS:   u64 = 7 * 24 * 3600;   // 7 days
 const TIMELOCK_SECS:        u64 = 2 * 24 * 3600;   // 2 days post-vote
 const QUORUM_BPS:           u32 = 2_000;            // 20 % quorum
-const APPROVAL_THRESHOLD_BPS: u32 = 5_000;          // 50 % + 1 = simple majority
 const MAX_DELEGATION_DEPTH: u32 = 5;
+const SCHEMA_VERSION:       u32 = 1;
 
 // ─────────────────────────────────────────────
 // Storage Keys
@@ -31,9 +31,11 @@ pub enum GovKey {
     ProposalCount,
     Vote(u64, Address),              // VoteRecord per proposal per voter
     Delegation(Address),             // Who Address delegates to
+    Delegators(Address),             // Reverse index: who delegates to Address
     DelegationDepth(Address),        // Cycle guard
     ProposalList,                    // Vec<u64> of all proposals
     Paused,
+    PowerSource,                     // Where own voting power is read from
 }
 
 // ─────────────────────────────────────────────
@@ -51,6 +53,32 @@ pub enum ProposalStatus {
     Cancelled,
 }
 
+/// Share of cast votes (for + against + abstain) that must be `For` for a
+/// proposal to pass. Compared exactly, without rounding to basis points.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ThresholdType {
+    SimpleMajority, // for > half; an exact tie fails
+    SuperMajority,  // for >= two thirds; exactly 2/3 passes
+    Unanimous,      // every cast vote is for
+}
+
+/// Weight applied to stake in one reward pool, in BPS (10_000 = 1x)
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolWeight {
+    pub pool_id:    u32,
+    pub weight_bps: u32,
+}
+
+/// Where a voter's own voting power comes from, chosen at initialization
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum PowerSource {
+    TokenBalance,                        // Governance token balance
+    StakedPools(Address, Vec<PoolWeight>), // Weighted stake in RewardDistribution pools
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct Proposal {
@@ -68,6 +96,7 @@ pub struct Proposal {
     pub votes_abstain: i128,
     pub status:        ProposalStatus,
     pub executed_at:   u64,
+    pub threshold:     ThresholdType,
 }
 
 #[contracttype]
@@ -111,10 +140,27 @@ impl GovernanceVoting {
     // ── Initialization ───────────────────────
 
     pub fn initialize(env: Env, governance_token: Address, total_supply: i128) {
+        Self::initialize_with_power_source(env, governance_token, total_supply, PowerSource::TokenBalance);
+    }
+
+    /// Initialize with voting power read from `power_source` instead of
+    /// always using the governance token balance.
+    pub fn initialize_with_power_source(
+        env: Env,
+        governance_token: Address,
+        total_supply: i128,
+        power_source: PowerSource,
+    ) {
         if env.storage().instance().has(&GovKey::GovernanceToken) {
             panic!("already initialised");
         }
+        if let PowerSource::StakedPools(_, weights) = &power_source {
+            if weights.is_empty() {
+                panic!("no pools weighted");
+            }
+        }
         env.storage().instance().set(&GovKey::GovernanceToken, &governance_token);
+        env.storage().instance().set(&GovKey::PowerSource,      &power_source);
         env.storage().instance().set(&GovKey::TotalSupply,      &total_supply);
         env.storage().instance().set(&GovKey::ProposalCount,    &0u64);
         env.storage().instance().set(&GovKey::ProposalList,     &Vec::<u64>::new(&env));
@@ -130,6 +176,21 @@ impl GovernanceVoting {
         description: String,
         target:      Address,
         calldata:    Symbol,
+    ) -> u64 {
+        Self::create_proposal_with_threshold(
+            env, proposer, title, description, target, calldata, ThresholdType::SimpleMajority,
+        )
+    }
+
+    /// Create a proposal that must clear `threshold` rather than a simple majority.
+    pub fn create_proposal_with_threshold(
+        env:         Env,
+        proposer:    Address,
+        title:       String,
+        description: String,
+        target:      Address,
+        calldata:    Symbol,
+        threshold:   ThresholdType,
     ) -> u64 {
         proposer.require_auth();
         Self::require_not_paused(&env);
@@ -142,7 +203,7 @@ impl GovernanceVoting {
 
         let proposal = Proposal {
             id,
-            proposer,
+            proposer: proposer.clone(),
             title,
             description,
             target,
@@ -155,6 +216,7 @@ impl GovernanceVoting {
             votes_abstain: 0,
             status:        ProposalStatus::Active,
             executed_at:   0,
+            threshold,
         };
 
         env.storage().persistent().set(&GovKey::Proposal(id), &proposal);
@@ -166,6 +228,7 @@ impl GovernanceVoting {
         list.push_back(id);
         env.storage().instance().set(&GovKey::ProposalList, &list);
 
+        env.events().publish((symbol_short!("proposed"), id), proposer);
         log!(&env, "proposal {} created", id);
         id
     }
@@ -178,18 +241,53 @@ impl GovernanceVoting {
         if delegator == delegate {
             panic!("cannot self-delegate");
         }
-        // Cycle detection: walk the chain
+        if Self::would_create_cycle(env.clone(), delegator.clone(), delegate.clone()) {
+            panic!("delegation would create a cycle");
+        }
         let depth = Self::delegation_depth(&env, &delegate, 0);
         if depth >= MAX_DELEGATION_DEPTH {
             panic!("delegation chain too long or cycle detected");
         }
-        env.storage().instance().set(&GovKey::Delegation(delegator), &delegate);
+        Self::remove_delegator(&env, &delegator);
+        env.storage().instance().set(&GovKey::Delegation(delegator.clone()), &delegate);
+
+        let mut delegators: Vec<Address> = env.storage().instance()
+            .get(&GovKey::Delegators(delegate.clone()))
+            .unwrap_or(Vec::new(&env));
+        delegators.push_back(delegator.clone());
+        env.storage().instance().set(&GovKey::Delegators(delegate.clone()), &delegators);
+        env.events().publish((symbol_short!("delegated"), delegator), delegate);
         log!(&env, "delegation set, chain depth {}", depth + 1);
     }
 
     pub fn undelegate(env: Env, delegator: Address) {
         delegator.require_auth();
-        env.storage().instance().remove(&GovKey::Delegation(delegator));
+        let previous: Option<Address> = env.storage().instance()
+            .get(&GovKey::Delegation(delegator.clone()));
+        Self::remove_delegator(&env, &delegator);
+        env.storage().instance().remove(&GovKey::Delegation(delegator.clone()));
+        env.events().publish((Symbol::new(&env, "undelegated"), delegator), previous);
+    }
+
+    /// Whether delegating from `delegator` to `delegate` would close a loop,
+    /// i.e. `delegate`'s full forward chain already reaches `delegator`.
+    pub fn would_create_cycle(env: Env, delegator: Address, delegate: Address) -> bool {
+        let mut visited = Vec::<Address>::new(&env);
+        let mut current = delegate;
+        loop {
+            if current == delegator {
+                return true;
+            }
+            // Stop on a loop that doesn't involve `delegator`
+            if visited.contains(&current) {
+                return false;
+            }
+            visited.push_back(current.clone());
+            match env.storage().instance().get::<GovKey, Address>(&GovKey::Delegation(current)) {
+                Some(next) => current = next,
+                None       => return false,
+            }
+        }
     }
 
     /// Resolve the ultimate delegate for `voter` (follow the chain).
@@ -197,9 +295,32 @@ impl GovernanceVoting {
         Self::follow_delegation(&env, &voter, 0)
     }
 
+    /// Effective voting power of `voter`: their own power (token balance or
+    /// weighted stake, per the power source) plus everything delegated to
+    /// them (directly or through a chain), or zero if they have delegated
+    /// their own power away.
+    pub fn get_voting_power(env: Env, voter: Address) -> i128 {
+        if env.storage().instance().has(&GovKey::Delegation(voter.clone())) {
+            return 0;
+        }
+        let token_address: Address = env.storage().instance()
+            .get(&GovKey::GovernanceToken)
+            .expect("not initialised");
+        let source = Self::get_power_source(env.clone());
+        Self::inbound_power(&env, &token_address, &source, &voter, 0)
+    }
+
+    pub fn get_power_source(env: Env) -> PowerSource {
+        env.storage().instance()
+            .get(&GovKey::PowerSource)
+            .unwrap_or(PowerSource::TokenBalance)
+    }
+
     // ── Voting ───────────────────────────────
 
-    /// Cast a vote on behalf of `voter` (weight comes from `token_balance`).
+    /// Cast a vote on behalf of `voter`. With the token-balance power source
+    /// the weight comes from `token_balance`; with staked pools it is the
+    /// effective voter's weighted stake and `token_balance` is ignored.
     /// Delegation is followed automatically.
     pub fn cast_vote(
         env:           Env,
@@ -231,13 +352,21 @@ impl GovernanceVoting {
         }
 
         // Prevent double-voting
-        if env.storage().temporary()
+        if env.storage().persistent()
             .has(&GovKey::Vote(proposal_id, effective_voter.clone()))
         {
             panic!("already voted");
         }
 
-        let weight = token_balance;
+        let weight = match Self::get_power_source(env.clone()) {
+            PowerSource::TokenBalance => token_balance,
+            source => {
+                let token_address: Address = env.storage().instance()
+                    .get(&GovKey::GovernanceToken)
+                    .expect("not initialised");
+                Self::inbound_power(&env, &token_address, &source, &effective_voter, 0)
+            }
+        };
         if weight <= 0 {
             panic!("no voting power");
         }
@@ -250,15 +379,18 @@ impl GovernanceVoting {
 
         let record = VoteRecord {
             voter:          effective_voter.clone(),
-            choice,
+            choice:         choice.clone(),
             weight,
             delegated_from,
             timestamp: now,
         };
 
-        env.storage().temporary().set(&GovKey::Vote(proposal_id, effective_voter), &record);
+        // Persisted so receipts remain auditable after the voting period
+        env.storage().persistent().set(&GovKey::Vote(proposal_id, effective_voter.clone()), &record);
         env.storage().persistent().set(&GovKey::Proposal(proposal_id), &proposal);
 
+        env.events().publish((symbol_short!("voted"), proposal_id), (effective_voter, choice, weight));
+
         log!(&env, "vote cast on proposal {} weight {}", proposal_id, weight);
     }
 
@@ -278,30 +410,31 @@ impl GovernanceVoting {
             panic!("voting period still open");
         }
 
-        let total_supply: i128 = env.storage().instance()
-            .get(&GovKey::TotalSupply)
-            .unwrap_or(1);
+        let (quorum_bps, approval_bps, passed) = Self::evaluate_outcome(&env, &proposal);
 
-        let total_votes = proposal.votes_for + proposal.votes_against + proposal.votes_abstain;
-        let quorum_bps  = ((total_votes * 10_000) / total_supply) as u32;
-        let approval_bps = if total_votes > 0 {
-            ((proposal.votes_for * 10_000) / total_votes) as u32
-        } else {
-            0
-        };
-
-        proposal.status = if quorum_bps >= QUORUM_BPS && approval_bps > APPROVAL_THRESHOLD_BPS {
+        proposal.status = if passed {
             ProposalStatus::Succeeded
         } else {
             ProposalStatus::Defeated
         };
 
         env.storage().persistent().set(&GovKey::Proposal(proposal_id), &proposal);
+        env.events().publish((symbol_short!("finalized"), proposal_id), proposal.status.clone());
         log!(&env, "proposal {} finalised: quorum {}bps approval {}bps",
              proposal_id, quorum_bps, approval_bps);
         proposal.status
     }
 
+    /// Where a proposal stands on current tallies: (quorum_bps, approval_bps,
+    /// would_pass). Uses the same math as `finalize_proposal` but works
+    /// mid-vote and changes nothing.
+    pub fn preview_outcome(env: Env, proposal_id: u64) -> (u32, u32, bool) {
+        let proposal: Proposal = env.storage().persistent()
+            .get(&GovKey::Proposal(proposal_id))
+            .expect("proposal not found");
+        Self::evaluate_outcome(&env, &proposal)
+    }
+
     /// Queue a succeeded proposal for execution (starts timelock countdown).
     pub fn queue_proposal(env: Env, caller: Address, proposal_id: u64) {
         caller.require_auth();
@@ -314,6 +447,7 @@ impl GovernanceVoting {
         }
         proposal.status = ProposalStatus::Queued;
         env.storage().persistent().set(&GovKey::Proposal(proposal_id), &proposal);
+        env.events().publish((symbol_short!("queued"), proposal_id), proposal.execute_after);
     }
 
     /// Execute a queued proposal after the timelock has expired.
@@ -337,6 +471,7 @@ impl GovernanceVoting {
         proposal.status      = ProposalStatus::Executed;
         proposal.executed_at = now;
         env.storage().persistent().set(&GovKey::Proposal(proposal_id), &proposal);
+        env.events().publish((symbol_short!("executed"), proposal_id), now);
 
         // NOTE: In production, invoke proposal.target.calldata here via cross-contract call.
         log!(&env, "proposal {} executed at {}", proposal_id, now);
@@ -356,6 +491,7 @@ impl GovernanceVoting {
         }
         proposal.status = ProposalStatus::Cancelled;
         env.storage().persistent().set(&GovKey::Proposal(proposal_id), &proposal);
+        env.events().publish((symbol_short!("cancelled"), proposal_id), caller);
     }
 
     // ── Analytics ────────────────────────────
@@ -369,6 +505,7 @@ impl GovernanceVoting {
         let mut active    = 0u64;
         let mut executed  = 0u64;
         let mut total_votes_cast = 0i128;
+        let mut participation_bps_sum = 0i128;
         let total_supply: i128 = env.storage().instance()
             .get(&GovKey::TotalSupply)
             .unwrap_or(1);
@@ -380,12 +517,15 @@ impl GovernanceVoting {
             {
                 if p.status == ProposalStatus::Active   { active   += 1; }
                 if p.status == ProposalStatus::Executed { executed += 1; }
-                total_votes_cast += p.votes_for + p.votes_against + p.votes_abstain;
+                let proposal_votes = p.votes_for + p.votes_against + p.votes_abstain;
+                total_votes_cast += proposal_votes;
+                participation_bps_sum += proposal_votes * 10_000 / total_supply;
             }
         }
 
+        // Scale each proposal to BPS before averaging to keep precision
         let avg_participation = if total > 0 {
-            ((total_votes_cast / total as i128) * 10_000 / total_supply) as u32
+            (participation_bps_sum / total as i128) as u32
         } else {
             0
         };
@@ -405,6 +545,31 @@ impl GovernanceVoting {
             .expect("not found")
     }
 
+    // ── Audit ────────────────────────────────
+
+    /// Vote receipt for `voter` on a proposal. Delegated votes are recorded
+    /// under the effective (final) delegate.
+    pub fn get_vote_receipt(env: Env, proposal_id: u64, voter: Address) -> Option<VoteRecord> {
+        env.storage().persistent().get(&GovKey::Vote(proposal_id, voter))
+    }
+
+    /// Raw (for, against, abstain) tally used by `finalize_proposal`.
+    pub fn export_tally(env: Env, proposal_id: u64) -> (i128, i128, i128) {
+        let proposal: Proposal = env.storage().persistent()
+            .get(&GovKey::Proposal(proposal_id))
+            .expect("proposal not found");
+        (proposal.votes_for, proposal.votes_against, proposal.votes_abstain)
+    }
+
+    // ── Health ───────────────────────────────
+
+    /// Health check for monitoring: (initialized, paused, schema version)
+    pub fn health(env: Env) -> (bool, bool, u32) {
+        let initialized = env.storage().instance().has(&GovKey::GovernanceToken);
+        let paused = env.storage().instance().get(&GovKey::Paused).unwrap_or(false);
+        (initialized, paused, SCHEMA_VERSION)
+    }
+
     // ── Internal Helpers ─────────────────────
 
     fn follow_delegation(env: &Env, voter: &Address, depth: u32) -> Address {
@@ -427,9 +592,87 @@ impl GovernanceVoting {
         }
     }
 
+    /// Drop `delegator` from its current delegate's reverse index.
+    fn remove_delegator(env: &Env, delegator: &Address) {
+        if let Some(current) = env.storage().instance()
+            .get::<GovKey, Address>(&GovKey::Delegation(delegator.clone()))
+        {
+            let mut delegators: Vec<Address> = env.storage().instance()
+                .get(&GovKey::Delegators(current.clone()))
+                .unwrap_or(Vec::new(env));
+            if let Some(index) = delegators.first_index_of(delegator) {
+                delegators.remove(index);
+            }
+            env.storage().instance().set(&GovKey::Delegators(current), &delegators);
+        }
+    }
+
+    /// Power `voter` holds in their own right, before delegation.
+    fn own_power(env: &Env, token_address: &Address, source: &PowerSource, voter: &Address) -> i128 {
+        match source {
+            PowerSource::TokenBalance => token::Client::new(env, token_address).balance(voter),
+            PowerSource::StakedPools(reward_contract, weights) => {
+                let rewards = RewardDistributionClient::new(env, reward_contract);
+                let mut power = 0i128;
+                for weight in weights.iter() {
+                    let staked = rewards.get_staked_amount(voter, &weight.pool_id);
+                    power += staked * weight.weight_bps as i128 / 10_000;
+                }
+                power
+            }
+        }
+    }
+
+    /// Own power of `voter` plus the power of everyone delegating to them.
+    fn inbound_power(env: &Env, token_address: &Address, source: &PowerSource, voter: &Address, depth: u32) -> i128 {
+        let mut power = Self::own_power(env, token_address, source, voter);
+        if depth >= MAX_DELEGATION_DEPTH {
+            return power;
+        }
+        let delegators: Vec<Address> = env.storage().instance()
+            .get(&GovKey::Delegators(voter.clone()))
+            .unwrap_or(Vec::new(env));
+        for delegator in delegators.iter() {
+            power += Self::inbound_power(env, token_address, source, &delegator, depth + 1);
+        }
+        power
+    }
+
+    fn evaluate_outcome(env: &Env, proposal: &Proposal) -> (u32, u32, bool) {
+        let total_supply: i128 = env.storage().instance()
+            .get(&GovKey::TotalSupply)
+            .unwrap_or(1);
+
+        let total_votes = proposal.votes_for + proposal.votes_against + proposal.votes_abstain;
+        let quorum_bps  = ((total_votes * 10_000) / total_supply) as u32;
+        let approval_bps = if total_votes > 0 {
+            ((proposal.votes_for * 10_000) / total_votes) as u32
+        } else {
+            0
+        };
+
+        let approved = match proposal.threshold {
+            ThresholdType::SimpleMajority => proposal.votes_for * 2 > total_votes,
+            ThresholdType::SuperMajority  => proposal.votes_for * 3 >= total_votes * 2,
+            ThresholdType::Unanimous      => total_votes > 0 && proposal.votes_for == total_votes,
+        };
+
+        let passed = quorum_bps >= QUORUM_BPS && approved;
+        (quorum_bps, approval_bps, passed)
+    }
+
     fn require_not_paused(env: &Env) {
         if env.storage().instance().get::<GovKey, bool>(&GovKey::Paused).unwrap_or(false) {
             panic!("paused");
         }
     }
-}
\ No newline at end of file
+}
+
+// Client interface for the reward distribution contract
+#[contractclient(name = "RewardDistributionClient")]
+pub trait RewardDistributionInterface {
+    fn get_staked_amount(env: Env, staker: Address, pool_id: u32) -> i128;
+}
+
+#[cfg(test)]
+mod test;
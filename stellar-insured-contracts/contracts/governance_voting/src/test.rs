@@ -0,0 +1,319 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::testutils::{Address as _, Events, Ledger};
+use soroban_sdk::{contract, contractimpl, symbol_short, token, IntoVal, TryFromVal, Val};
+
+fn setup(env: &Env) -> GovernanceVotingClient<'_> {
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, GovernanceVoting);
+    let client = GovernanceVotingClient::new(env, &contract_id);
+    client.initialize(&Address::generate(env), &1_000);
+
+    client
+}
+
+fn create_proposal(env: &Env, client: &GovernanceVotingClient) -> u64 {
+    client.create_proposal(
+        &Address::generate(env),
+        &String::from_str(env, "Raise cap"),
+        &String::from_str(env, "Raise the pool cap"),
+        &Address::generate(env),
+        &Symbol::new(env, "set_cap"),
+    )
+}
+
+#[test]
+fn test_vote_receipts_and_tally() {
+    let env = Env::default();
+    let client = setup(&env);
+    let proposal_id = create_proposal(&env, &client);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    let dave = Address::generate(&env);
+
+    client.cast_vote(&alice, &proposal_id, &VoteChoice::For, &300);
+    client.cast_vote(&bob, &proposal_id, &VoteChoice::Against, &100);
+    client.cast_vote(&carol, &proposal_id, &VoteChoice::Abstain, &50);
+    client.cast_vote(&dave, &proposal_id, &VoteChoice::For, &25);
+
+    let receipt = client.get_vote_receipt(&proposal_id, &alice).unwrap();
+    assert!(receipt.choice == VoteChoice::For);
+    assert_eq!(receipt.weight, 300);
+
+    let receipt = client.get_vote_receipt(&proposal_id, &bob).unwrap();
+    assert!(receipt.choice == VoteChoice::Against);
+    assert_eq!(receipt.weight, 100);
+
+    assert!(client.get_vote_receipt(&proposal_id, &Address::generate(&env)).is_none());
+
+    assert_eq!(client.export_tally(&proposal_id), (325, 100, 50));
+}
+
+#[test]
+fn test_analytics_average_participation() {
+    let env = Env::default();
+    let client = setup(&env);
+
+    // 30% and 0.5% participation against a supply of 1,000
+    let first = create_proposal(&env, &client);
+    client.cast_vote(&Address::generate(&env), &first, &VoteChoice::For, &300);
+    let second = create_proposal(&env, &client);
+    client.cast_vote(&Address::generate(&env), &second, &VoteChoice::Against, &5);
+
+    let analytics = client.get_analytics();
+    assert_eq!(analytics.total_proposals, 2);
+    assert_eq!(analytics.total_votes_cast, 305);
+    assert_eq!(analytics.avg_participation, 1_525);
+}
+
+#[test]
+fn test_preview_outcome_matches_finalized_result() {
+    let env = Env::default();
+    let client = setup(&env);
+    let proposal_id = create_proposal(&env, &client);
+
+    client.cast_vote(&Address::generate(&env), &proposal_id, &VoteChoice::For, &50);
+    let (quorum_bps, _, would_pass) = client.preview_outcome(&proposal_id);
+    assert_eq!(quorum_bps, 500);
+    assert!(!would_pass);
+
+    client.cast_vote(&Address::generate(&env), &proposal_id, &VoteChoice::For, &250);
+    client.cast_vote(&Address::generate(&env), &proposal_id, &VoteChoice::Against, &100);
+    assert_eq!(client.preview_outcome(&proposal_id), (4_000, 7_500, true));
+
+    // Previewing leaves the proposal untouched
+    assert!(client.get_proposal(&proposal_id).status == ProposalStatus::Active);
+
+    env.ledger().with_mut(|li| li.timestamp += VOTING_PERIOD_SECS + 1);
+    assert!(client.finalize_proposal(&proposal_id) == ProposalStatus::Succeeded);
+}
+
+#[test]
+fn test_effective_voting_power_follows_delegations() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let minter = token::StellarAssetClient::new(&env, &token_address);
+    let contract_id = env.register_contract(None, GovernanceVoting);
+    let client = GovernanceVotingClient::new(&env, &contract_id);
+    client.initialize(&token_address, &1_000);
+
+    let [alice, bob, carol, dave, erin] = [0; 5].map(|_| Address::generate(&env));
+    for (holder, balance) in [(&alice, 100), (&bob, 50), (&carol, 200), (&dave, 30), (&erin, 10)] {
+        minter.mint(holder, &balance);
+    }
+
+    // dave -> alice -> carol <- bob; erin votes undelegated
+    client.delegate(&dave, &alice);
+    client.delegate(&alice, &carol);
+    client.delegate(&bob, &carol);
+
+    assert_eq!(client.get_voting_power(&carol), 380);
+    assert_eq!(client.get_voting_power(&alice), 0);
+    assert_eq!(client.get_voting_power(&bob), 0);
+    assert_eq!(client.get_voting_power(&dave), 0);
+    assert_eq!(client.get_voting_power(&erin), 10);
+
+    // Moving bob's delegation shifts that power to erin
+    client.delegate(&bob, &erin);
+    assert_eq!(client.get_voting_power(&carol), 330);
+    assert_eq!(client.get_voting_power(&erin), 60);
+
+    client.undelegate(&alice);
+    assert_eq!(client.get_voting_power(&alice), 130);
+    assert_eq!(client.get_voting_power(&carol), 200);
+}
+
+/// Create a proposal under `threshold`, cast the given for/against weights
+/// and finalize it once voting closes
+fn finalize_with(
+    env: &Env,
+    client: &GovernanceVotingClient,
+    threshold: ThresholdType,
+    votes_for: i128,
+    votes_against: i128,
+) -> ProposalStatus {
+    let proposal_id = client.create_proposal_with_threshold(
+        &Address::generate(env),
+        &String::from_str(env, "Raise cap"),
+        &String::from_str(env, "Raise the pool cap"),
+        &Address::generate(env),
+        &Symbol::new(env, "set_cap"),
+        &threshold,
+    );
+    client.cast_vote(&Address::generate(env), &proposal_id, &VoteChoice::For, &votes_for);
+    if votes_against > 0 {
+        client.cast_vote(&Address::generate(env), &proposal_id, &VoteChoice::Against, &votes_against);
+    }
+    env.ledger().with_mut(|li| li.timestamp += VOTING_PERIOD_SECS + 1);
+    client.finalize_proposal(&proposal_id)
+}
+
+#[test]
+fn test_simple_majority_boundaries() {
+    let env = Env::default();
+    let client = setup(&env);
+
+    // A tie fails; one vote over half passes, even where basis points round down to 50%
+    assert!(finalize_with(&env, &client, ThresholdType::SimpleMajority, 150, 150) == ProposalStatus::Defeated);
+    assert!(finalize_with(&env, &client, ThresholdType::SimpleMajority, 151, 150) == ProposalStatus::Succeeded);
+    assert!(finalize_with(&env, &client, ThresholdType::SimpleMajority, 5_001, 5_000) == ProposalStatus::Succeeded);
+}
+
+#[test]
+fn test_super_majority_boundaries() {
+    let env = Env::default();
+    let client = setup(&env);
+
+    // Exactly two thirds passes; just under fails
+    assert!(finalize_with(&env, &client, ThresholdType::SuperMajority, 200, 100) == ProposalStatus::Succeeded);
+    assert!(finalize_with(&env, &client, ThresholdType::SuperMajority, 199, 100) == ProposalStatus::Defeated);
+    assert!(finalize_with(&env, &client, ThresholdType::SimpleMajority, 199, 100) == ProposalStatus::Succeeded);
+}
+
+#[test]
+fn test_unanimous_boundaries() {
+    let env = Env::default();
+    let client = setup(&env);
+
+    assert!(finalize_with(&env, &client, ThresholdType::Unanimous, 300, 0) == ProposalStatus::Succeeded);
+    assert!(finalize_with(&env, &client, ThresholdType::Unanimous, 300, 1) == ProposalStatus::Defeated);
+}
+
+#[test]
+fn test_delegation_cycle_rejected() {
+    let env = Env::default();
+    let client = setup(&env);
+    let [a, b, c, d] = [0; 4].map(|_| Address::generate(&env));
+
+    client.delegate(&a, &b);
+    client.delegate(&b, &c);
+
+    // C -> A is well within the depth limit but would close A -> B -> C -> A
+    assert!(client.would_create_cycle(&c, &a));
+    assert!(!client.would_create_cycle(&c, &d));
+    assert!(client.try_delegate(&c, &a).is_err());
+    assert!(client.resolve_delegate(&a) == c);
+
+    client.delegate(&c, &d);
+    assert!(client.resolve_delegate(&a) == d);
+}
+
+fn last_event(env: &Env) -> (Vec<Val>, Val) {
+    let (_, topics, data) = env.events().all().last().unwrap();
+    (topics, data)
+}
+
+#[test]
+fn test_transitions_emit_events() {
+    let env = Env::default();
+    let client = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    client.delegate(&alice, &bob);
+    let (topics, data) = last_event(&env);
+    assert_eq!(topics, (symbol_short!("delegated"), alice.clone()).into_val(&env));
+    assert_eq!(Address::try_from_val(&env, &data).unwrap(), bob);
+
+    client.undelegate(&alice);
+    let (topics, data) = last_event(&env);
+    assert_eq!(topics, (Symbol::new(&env, "undelegated"), alice.clone()).into_val(&env));
+    assert_eq!(Option::<Address>::try_from_val(&env, &data).unwrap(), Some(bob));
+
+    let proposal_id = create_proposal(&env, &client);
+    client.cast_vote(&alice, &proposal_id, &VoteChoice::For, &300);
+    env.ledger().with_mut(|li| li.timestamp += VOTING_PERIOD_SECS + 1);
+    client.finalize_proposal(&proposal_id);
+
+    client.queue_proposal(&alice, &proposal_id);
+    let (topics, data) = last_event(&env);
+    assert_eq!(topics, (symbol_short!("queued"), proposal_id).into_val(&env));
+    assert_eq!(
+        u64::try_from_val(&env, &data).unwrap(),
+        client.get_proposal(&proposal_id).execute_after
+    );
+
+    let proposer = Address::generate(&env);
+    let cancelled_id = client.create_proposal(
+        &proposer,
+        &String::from_str(&env, "Lower cap"),
+        &String::from_str(&env, "Lower the pool cap"),
+        &Address::generate(&env),
+        &Symbol::new(&env, "set_cap"),
+    );
+    client.cancel_proposal(&proposer, &cancelled_id);
+    let (topics, data) = last_event(&env);
+    assert_eq!(topics, (symbol_short!("cancelled"), cancelled_id).into_val(&env));
+    assert_eq!(Address::try_from_val(&env, &data).unwrap(), proposer);
+}
+
+/// Stand-in reward contract reporting preset stake per (staker, pool)
+#[contract]
+struct MockRewards;
+
+#[contractimpl]
+impl MockRewards {
+    pub fn set_staked(env: Env, staker: Address, pool_id: u32, amount: i128) {
+        env.storage().instance().set(&(staker, pool_id), &amount);
+    }
+
+    pub fn get_staked_amount(env: Env, staker: Address, pool_id: u32) -> i128 {
+        env.storage().instance().get(&(staker, pool_id)).unwrap_or(0)
+    }
+}
+
+#[test]
+fn test_voting_power_from_weighted_stake() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let rewards = MockRewardsClient::new(&env, &env.register_contract(None, MockRewards));
+    let mut weights = Vec::new(&env);
+    weights.push_back(PoolWeight { pool_id: 1, weight_bps: 10_000 });
+    weights.push_back(PoolWeight { pool_id: 2, weight_bps: 5_000 });
+
+    let client = GovernanceVotingClient::new(&env, &env.register_contract(None, GovernanceVoting));
+    client.initialize_with_power_source(
+        &Address::generate(&env),
+        &1_000,
+        &PowerSource::StakedPools(rewards.address.clone(), weights),
+    );
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    rewards.set_staked(&alice, &1, &300);
+    rewards.set_staked(&alice, &2, &200);
+    // Pool 3 carries no weight, so stake there doesn't count
+    rewards.set_staked(&alice, &3, &1_000);
+    rewards.set_staked(&bob, &2, &100);
+
+    assert_eq!(client.get_voting_power(&alice), 400);
+    assert_eq!(client.get_voting_power(&bob), 50);
+
+    client.delegate(&bob, &alice);
+    assert_eq!(client.get_voting_power(&alice), 450);
+
+    // The vote counts the staked power, not the balance the caller supplies
+    let proposal_id = create_proposal(&env, &client);
+    client.cast_vote(&alice, &proposal_id, &VoteChoice::For, &1_000_000);
+    assert_eq!(client.get_vote_receipt(&proposal_id, &alice).unwrap().weight, 450);
+    assert_eq!(client.export_tally(&proposal_id), (450, 0, 0));
+}
+
+#[test]
+fn test_health_reflects_pause_state() {
+    let env = Env::default();
+    let client = setup(&env);
+    assert_eq!(client.health(), (true, false, SCHEMA_VERSION));
+
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(&GovKey::Paused, &true);
+    });
+    assert_eq!(client.health(), (true, true, SCHEMA_VERSION));
+}
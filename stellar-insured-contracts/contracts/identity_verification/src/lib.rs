@@ -1,16 +1,20 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, symbol_short, Address, BytesN, Env, Symbol, Vec, String,
+    contract, contractclient, contracterror, contractimpl, contracttype, symbol_short, Address,
+    BytesN, Env, Symbol, Vec, String,
 };
 use shared::{
-    IdentityVerification, KycRecord, ZkIdentityProof, ZkProof, ZkVerificationResult,
+    IdentityVerification, KycRecord, RevocationInfo, ZkIdentityProof, ZkProof, ZkVerificationResult,
     authorization::{require_admin, require_role, Role},
 };
 
 #[contract]
 pub struct IdentityVerificationContract;
 
+/// Storage schema version written by this build of the contract
+const SCHEMA_VERSION: u32 = 1;
+
 // Storage keys
 const ADMIN: Symbol = symbol_short!("ADMIN");
 const PAUSED: Symbol = symbol_short!("PAUSED");
@@ -24,6 +28,15 @@ const ATTESTATION: Symbol = symbol_short!("ATTEST");
 const VERIFIER_REGISTRY: Symbol = symbol_short!("VER_REG");
 const CIRCUIT_VERIFICATION_KEY: Symbol = symbol_short!("CIR_VK");
 const ZK_IDENTITY_PROOF: Symbol = symbol_short!("ZK_ID");
+const REQUEST_ATTESTATIONS: Symbol = symbol_short!("REQ_ATT");
+const DID_ATTESTATIONS: Symbol = symbol_short!("DID_ATT");
+const ATTESTATION_REVOCATION: Symbol = symbol_short!("ATT_REV");
+const REQUEST_INDEX: Symbol = symbol_short!("REQ_IDX");
+const CONFIDENCE_DECAY: Symbol = symbol_short!("CONF_DCY");
+const KYC_CONTRACT: Symbol = symbol_short!("KYC_CTR");
+
+/// How long an expired request is kept before it can be pruned
+const REQUEST_PRUNE_GRACE_PERIOD: u64 = 30 * 86400;
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -62,6 +75,7 @@ pub struct VerificationRequest {
     pub status: Symbol, // "pending", "approved", "rejected", "expired"
     pub approver: Option<Address>,
     pub approved_at: Option<u64>,
+    pub required_jurisdiction: Option<String>, // Only verifiers registered here may attest
 }
 
 /// Attestation from a verifier
@@ -79,6 +93,7 @@ pub struct Attestation {
     pub expires_at: u64,
     pub created_at: u64,
     pub is_revoked: bool,
+    pub kyc_id: Option<u64>, // KYC record backing this attestation, if any
 }
 
 /// Circuit verification key registration
@@ -114,6 +129,17 @@ fn set_paused(env: &Env, paused: bool) {
     env.storage().persistent().set(&PAUSED, &paused);
 }
 
+fn record_attestation_revocation(env: &Env, attestation_id: u64, by: &Address, reason: &Symbol) {
+    let info = RevocationInfo {
+        by: by.clone(),
+        reason: reason.clone(),
+        at: env.ledger().timestamp(),
+    };
+    env.storage()
+        .persistent()
+        .set(&(ATTESTATION_REVOCATION, attestation_id), &info);
+}
+
 fn get_next_verification_id(env: &Env) -> u64 {
     let current: u64 = env.storage().persistent().get(&VERIFICATION_COUNTER).unwrap_or(0);
     env.storage().persistent().set(&VERIFICATION_COUNTER, &(current + 1));
@@ -165,6 +191,71 @@ fn verify_zk_proof_enhanced(
     Ok(ZkVerificationResult::Valid)
 }
 
+/// Confidence an attestation counts for now. Types with decay enabled scale
+/// the stored score by the fraction of the validity window still remaining.
+fn effective_confidence(env: &Env, attestation: &Attestation) -> u32 {
+    let decays = env
+        .storage()
+        .persistent()
+        .get(&(CONFIDENCE_DECAY, attestation.attestation_type.clone()))
+        .unwrap_or(false);
+    let total = attestation.expires_at.saturating_sub(attestation.created_at);
+    if !decays || total == 0 {
+        return attestation.confidence_score;
+    }
+
+    let remaining = attestation.expires_at.saturating_sub(env.ledger().timestamp());
+    (attestation.confidence_score as u64 * remaining / total) as u32
+}
+
+/// Reputation-weighted average confidence of the attestations that match the
+/// requested type and attributes. Returns `None` when nothing matches.
+fn weighted_confidence(
+    env: &Env,
+    attestations: &Vec<Attestation>,
+    verification_type: &Symbol,
+    required_attributes: &Vec<String>,
+) -> Option<u32> {
+    let mut weighted_sum: u64 = 0;
+    let mut total_weight: u64 = 0;
+
+    for attestation in attestations.iter() {
+        if attestation.attestation_type != *verification_type {
+            continue;
+        }
+
+        let mut all_attributes_verified = true;
+        for required_attr in required_attributes.iter() {
+            if !attestation.verified_attributes.contains(required_attr) {
+                all_attributes_verified = false;
+                break;
+            }
+        }
+        if !all_attributes_verified {
+            continue;
+        }
+
+        let reputation = env
+            .storage()
+            .persistent()
+            .get::<_, VerifierRegistration>(&(VERIFIER_REGISTRY, attestation.verifier.clone()))
+            .map(|registration| registration.reputation_score)
+            .unwrap_or(0) as u64;
+        if reputation == 0 {
+            continue;
+        }
+
+        weighted_sum += effective_confidence(env, &attestation) as u64 * reputation;
+        total_weight += reputation;
+    }
+
+    if total_weight == 0 {
+        None
+    } else {
+        Some((weighted_sum / total_weight) as u32)
+    }
+}
+
 #[contractimpl]
 impl IdentityVerificationContract {
     /// Initialize the identity verification contract
@@ -261,7 +352,8 @@ impl IdentityVerificationContract {
         Ok(())
     }
 
-    /// Submit a verification request
+    /// Submit a verification request. With `required_jurisdiction` set, only
+    /// verifiers registered in that jurisdiction may attest to it.
     pub fn submit_verification_request(
         env: Env,
         requester: Address,
@@ -270,6 +362,7 @@ impl IdentityVerificationContract {
         required_level: u32,
         requested_attributes: Vec<String>,
         expires_in_days: u32,
+        required_jurisdiction: Option<String>,
     ) -> Result<u64, ContractError> {
         requester.require_auth();
 
@@ -300,12 +393,21 @@ impl IdentityVerificationContract {
             status: Symbol::new(&env, "pending"),
             approver: None,
             approved_at: None,
+            required_jurisdiction,
         };
 
         env.storage()
             .persistent()
             .set(&(VERIFICATION_REQUEST, request_id), &request);
 
+        let mut request_index: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&REQUEST_INDEX)
+            .unwrap_or(Vec::new(&env));
+        request_index.push_back(request_id);
+        env.storage().persistent().set(&REQUEST_INDEX, &request_index);
+
         env.events().publish(
             (symbol_short!("verification_requested"), did.clone()),
             (request_id, verification_type, required_level),
@@ -327,7 +429,84 @@ impl IdentityVerificationContract {
     ) -> Result<u64, ContractError> {
         verifier.require_auth();
 
-        if is_paused(&env) {
+        Self::issue_attestation(
+            &env,
+            verifier,
+            verification_request_id,
+            attestation_type,
+            verified_attributes,
+            confidence_score,
+            proof_hash,
+            env.ledger().timestamp() + (expires_in_days as u64 * 86400),
+            None,
+        )
+    }
+
+    /// Create an attestation backed by an active KYC record in the configured KYC contract
+    pub fn create_kyc_backed_attestation(
+        env: Env,
+        verifier: Address,
+        verification_request_id: u64,
+        kyc_id: u64,
+        min_kyc_level: u32,
+        attestation_type: Symbol,
+        verified_attributes: Vec<String>,
+        confidence_score: u32,
+        proof_hash: BytesN<32>,
+    ) -> Result<u64, ContractError> {
+        verifier.require_auth();
+
+        let request: VerificationRequest = env
+            .storage()
+            .persistent()
+            .get(&(VERIFICATION_REQUEST, verification_request_id))
+            .ok_or(ContractError::NotFound)?;
+
+        let kyc_contract: Address = env
+            .storage()
+            .persistent()
+            .get(&KYC_CONTRACT)
+            .ok_or(ContractError::NotInitialized)?;
+        let kyc_client = KycIntegrationClient::new(&env, &kyc_contract);
+        let kyc_record = kyc_client.get_kyc_record(&kyc_id).ok_or(ContractError::NotFound)?;
+
+        if !kyc_record.is_active
+            || env.ledger().timestamp() > kyc_record.expires_at
+            || kyc_record.did != request.did
+        {
+            return Err(ContractError::IdentityNotVerified);
+        }
+
+        if kyc_record.kyc_level < min_kyc_level {
+            return Err(ContractError::KycLevelInsufficient);
+        }
+
+        Self::issue_attestation(
+            &env,
+            verifier,
+            verification_request_id,
+            attestation_type,
+            verified_attributes,
+            confidence_score,
+            proof_hash,
+            // The attestation lasts as long as the backing KYC record
+            kyc_record.expires_at,
+            Some(kyc_id),
+        )
+    }
+
+    fn issue_attestation(
+        env: &Env,
+        verifier: Address,
+        verification_request_id: u64,
+        attestation_type: Symbol,
+        verified_attributes: Vec<String>,
+        confidence_score: u32,
+        proof_hash: BytesN<32>,
+        expires_at: u64,
+        kyc_id: Option<u64>,
+    ) -> Result<u64, ContractError> {
+        if is_paused(env) {
             return Err(ContractError::Paused);
         }
 
@@ -357,8 +536,13 @@ impl IdentityVerificationContract {
             return Err(ContractError::VerificationExpired);
         }
 
-        let attestation_id = get_next_attestation_id(&env);
-        let expires_at = env.ledger().timestamp() + (expires_in_days as u64 * 86400);
+        if let Some(required) = &request.required_jurisdiction {
+            if registration.jurisdiction != *required {
+                return Err(ContractError::VerifierNotAuthorized);
+            }
+        }
+
+        let attestation_id = get_next_attestation_id(env);
 
         let attestation = Attestation {
             attestation_id,
@@ -372,12 +556,34 @@ impl IdentityVerificationContract {
             expires_at,
             created_at: env.ledger().timestamp(),
             is_revoked: false,
+            kyc_id,
         };
 
         env.storage()
             .persistent()
             .set(&(ATTESTATION, attestation_id), &attestation);
 
+        // Index the attestation under its verification request
+        let mut request_attestations: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&(REQUEST_ATTESTATIONS, verification_request_id))
+            .unwrap_or(Vec::new(env));
+        request_attestations.push_back(attestation_id);
+        env.storage()
+            .persistent()
+            .set(&(REQUEST_ATTESTATIONS, verification_request_id), &request_attestations);
+
+        let mut did_attestations: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&(DID_ATTESTATIONS, request.did.clone()))
+            .unwrap_or(Vec::new(env));
+        did_attestations.push_back(attestation_id);
+        env.storage()
+            .persistent()
+            .set(&(DID_ATTESTATIONS, request.did.clone()), &did_attestations);
+
         env.events().publish(
             (symbol_short!("attestation_created"), request.did.clone()),
             (attestation_id, attestation_type, confidence_score),
@@ -464,38 +670,102 @@ impl IdentityVerificationContract {
             return Err(ContractError::Paused);
         }
 
-        // Check for valid attestations
+        // Compare the reputation-weighted confidence of matching attestations
+        // against the level threshold (level * 20)
         let attestations = Self::get_valid_attestations(env.clone(), did.clone());
-        
-        for attestation in attestations.iter() {
-            if attestation.attestation_type == required_verification_type 
-                && attestation.confidence_score >= (required_level * 20) // Convert level to confidence score
-                && !attestation.is_revoked
-                && env.ledger().timestamp() <= attestation.expires_at {
-                
-                // Check if all required attributes are verified
-                let mut all_attributes_verified = true;
-                for required_attr in required_attributes.iter() {
-                    if !attestation.verified_attributes.contains(required_attr) {
-                        all_attributes_verified = false;
-                        break;
-                    }
-                }
-                
-                if all_attributes_verified {
-                    return Ok(true);
-                }
-            }
+
+        match weighted_confidence(&env, &attestations, &required_verification_type, &required_attributes) {
+            Some(confidence) => Ok(confidence >= required_level * 20),
+            None => Ok(false),
         }
+    }
 
-        Ok(false)
+    /// Get the reputation-weighted confidence for a DID's matching attestations
+    pub fn get_aggregate_confidence(
+        env: Env,
+        did: String,
+        verification_type: Symbol,
+        required_attributes: Vec<String>,
+    ) -> u32 {
+        let attestations = Self::get_valid_attestations(env.clone(), did);
+        weighted_confidence(&env, &attestations, &verification_type, &required_attributes).unwrap_or(0)
     }
 
-    /// Revoke attestation
+    /// Update a verifier's reputation score (admin only)
+    pub fn set_verifier_reputation(
+        env: Env,
+        admin: Address,
+        verifier: Address,
+        reputation_score: u32,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        require_admin(&env, &admin)?;
+
+        if reputation_score == 0 || reputation_score > 100 {
+            return Err(ContractError::InvalidInput);
+        }
+
+        let mut registration: VerifierRegistration = env
+            .storage()
+            .persistent()
+            .get(&(VERIFIER_REGISTRY, verifier.clone()))
+            .ok_or(ContractError::VerifierNotAuthorized)?;
+
+        registration.reputation_score = reputation_score;
+        env.storage()
+            .persistent()
+            .set(&(VERIFIER_REGISTRY, verifier.clone()), &registration);
+
+        env.events().publish((symbol_short!("ver_rep"), verifier), reputation_score);
+
+        Ok(())
+    }
+
+    /// Set the KYC contract that backs KYC attestations (admin only)
+    pub fn set_kyc_contract(env: Env, admin: Address, kyc_contract: Address) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        require_admin(&env, &admin)?;
+
+        env.storage().persistent().set(&KYC_CONTRACT, &kyc_contract);
+
+        env.events().publish((symbol_short!("kyc_ctr"), admin), kyc_contract);
+
+        Ok(())
+    }
+
+    /// Get the configured KYC contract
+    pub fn get_kyc_contract(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&KYC_CONTRACT)
+    }
+
+    /// Enable or disable confidence decay toward expiry for an attestation type (admin only)
+    pub fn set_confidence_decay(
+        env: Env,
+        admin: Address,
+        attestation_type: Symbol,
+        enabled: bool,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        require_admin(&env, &admin)?;
+
+        env.storage()
+            .persistent()
+            .set(&(CONFIDENCE_DECAY, attestation_type.clone()), &enabled);
+
+        env.events().publish((symbol_short!("conf_dcy"), attestation_type), enabled);
+
+        Ok(())
+    }
+
+    /// Revoke attestation, recording who revoked it and why
     pub fn revoke_attestation(
         env: Env,
         verifier: Address,
         attestation_id: u64,
+        reason: Symbol,
     ) -> Result<(), ContractError> {
         verifier.require_auth();
 
@@ -513,15 +783,129 @@ impl IdentityVerificationContract {
         env.storage()
             .persistent()
             .set(&(ATTESTATION, attestation_id), &attestation);
+        record_attestation_revocation(&env, attestation_id, &verifier, &reason);
 
         env.events().publish(
             (symbol_short!("attestation_revoked"), attestation.did),
-            attestation_id,
+            (attestation_id, reason),
         );
 
         Ok(())
     }
 
+    /// Revoke every attestation the calling verifier issued for a verification request
+    pub fn revoke_attestations_for_request(
+        env: Env,
+        verifier: Address,
+        verification_request_id: u64,
+        reason: Symbol,
+    ) -> Result<u32, ContractError> {
+        verifier.require_auth();
+
+        if !env
+            .storage()
+            .persistent()
+            .has(&(VERIFICATION_REQUEST, verification_request_id))
+        {
+            return Err(ContractError::NotFound);
+        }
+
+        let attestation_ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&(REQUEST_ATTESTATIONS, verification_request_id))
+            .unwrap_or(Vec::new(&env));
+
+        let mut revoked = 0u32;
+        for attestation_id in attestation_ids.iter() {
+            let mut attestation: Attestation = match env
+                .storage()
+                .persistent()
+                .get(&(ATTESTATION, attestation_id))
+            {
+                Some(attestation) => attestation,
+                None => continue,
+            };
+
+            if attestation.verifier != verifier || attestation.is_revoked {
+                continue;
+            }
+
+            attestation.is_revoked = true;
+            env.storage()
+                .persistent()
+                .set(&(ATTESTATION, attestation_id), &attestation);
+            record_attestation_revocation(&env, attestation_id, &verifier, &reason);
+            revoked += 1;
+        }
+
+        env.events().publish(
+            (symbol_short!("att_bulk"), verification_request_id),
+            (verifier, revoked),
+        );
+
+        Ok(revoked)
+    }
+
+    /// Mark pending requests past their expiry as expired and prune
+    /// processed requests once the grace period has also elapsed
+    pub fn expire_stale_requests(env: Env, caller: Address) -> Result<u32, ContractError> {
+        caller.require_auth();
+
+        require_admin(&env, &caller)?;
+
+        let request_index: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&REQUEST_INDEX)
+            .unwrap_or(Vec::new(&env));
+
+        let now = env.ledger().timestamp();
+        let pending = Symbol::new(&env, "pending");
+        let mut remaining = Vec::new(&env);
+        let mut expired = 0u32;
+        let mut pruned = 0u32;
+
+        for request_id in request_index.iter() {
+            let mut request: VerificationRequest = match env
+                .storage()
+                .persistent()
+                .get(&(VERIFICATION_REQUEST, request_id))
+            {
+                Some(request) => request,
+                None => continue,
+            };
+
+            if now <= request.expires_at {
+                remaining.push_back(request_id);
+                continue;
+            }
+
+            if request.status == pending {
+                request.status = Symbol::new(&env, "expired");
+                env.storage()
+                    .persistent()
+                    .set(&(VERIFICATION_REQUEST, request_id), &request);
+                expired += 1;
+            }
+
+            if now > request.expires_at.saturating_add(REQUEST_PRUNE_GRACE_PERIOD) {
+                env.storage()
+                    .persistent()
+                    .remove(&(VERIFICATION_REQUEST, request_id));
+                pruned += 1;
+            } else {
+                remaining.push_back(request_id);
+            }
+        }
+
+        env.storage().persistent().set(&REQUEST_INDEX, &remaining);
+
+        env.events().publish((symbol_short!("req_exp"), caller), (expired, pruned));
+
+        Ok(expired)
+    }
+
     /// Pause/unpause contract (admin only)
     pub fn set_paused(env: Env, admin: Address, paused: bool) -> Result<(), ContractError> {
         admin.require_auth();
@@ -543,6 +927,11 @@ impl IdentityVerificationContract {
 
     // ===== View Functions =====
 
+    /// Health check for monitoring: (initialized, paused, schema version)
+    pub fn health(env: Env) -> (bool, bool, u32) {
+        (env.storage().persistent().has(&ADMIN), is_paused(&env), SCHEMA_VERSION)
+    }
+
     /// Get verification request
     pub fn get_verification_request(env: Env, request_id: u64) -> Option<VerificationRequest> {
         env.storage().persistent().get(&(VERIFICATION_REQUEST, request_id))
@@ -553,6 +942,11 @@ impl IdentityVerificationContract {
         env.storage().persistent().get(&(ATTESTATION, attestation_id))
     }
 
+    /// Get who revoked an attestation, why and when
+    pub fn get_attestation_revocation(env: Env, attestation_id: u64) -> Option<RevocationInfo> {
+        env.storage().persistent().get(&(ATTESTATION_REVOCATION, attestation_id))
+    }
+
     /// Get verifier registration
     pub fn get_verifier_registration(env: Env, verifier: Address) -> Option<VerifierRegistration> {
         env.storage().persistent().get(&(VERIFIER_REGISTRY, verifier))
@@ -563,11 +957,36 @@ impl IdentityVerificationContract {
         env.storage().persistent().get(&(CIRCUIT_VERIFICATION_KEY, circuit_id))
     }
 
+    /// Get attestation ids linked to a verification request
+    pub fn get_request_attestations(env: Env, verification_request_id: u64) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&(REQUEST_ATTESTATIONS, verification_request_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
     /// Get valid attestations for a DID
     pub fn get_valid_attestations(env: Env, did: String) -> Vec<Attestation> {
-        // In production, maintain an index for efficient querying
-        // For now, return empty vector
-        Vec::new(&env)
+        let attestation_ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&(DID_ATTESTATIONS, did))
+            .unwrap_or(Vec::new(&env));
+
+        let now = env.ledger().timestamp();
+        let mut attestations = Vec::new(&env);
+        for attestation_id in attestation_ids.iter() {
+            if let Some(attestation) = env
+                .storage()
+                .persistent()
+                .get::<_, Attestation>(&(ATTESTATION, attestation_id))
+            {
+                if !attestation.is_revoked && now <= attestation.expires_at {
+                    attestations.push_back(attestation);
+                }
+            }
+        }
+        attestations
     }
 
     /// Check if verifier is authorized for verification type
@@ -591,3 +1010,11 @@ impl IdentityVerificationContract {
         (0, 0, 0)
     }
 }
+
+#[contractclient(name = "KycIntegrationClient")]
+pub trait KycIntegrationInterface {
+    fn get_kyc_record(env: Env, kyc_id: u64) -> Option<KycRecord>;
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,338 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{contract, contractimpl, vec};
+
+#[contract]
+pub struct MockKyc;
+
+#[contractimpl]
+impl MockKyc {
+    pub fn set_record(env: Env, record: KycRecord) {
+        env.storage().persistent().set(&record.kyc_id, &record);
+    }
+
+    pub fn get_kyc_record(env: Env, kyc_id: u64) -> Option<KycRecord> {
+        env.storage().persistent().get(&kyc_id)
+    }
+}
+
+fn kyc_record(env: &Env, kyc_id: u64, did: &String, kyc_level: u32, is_active: bool) -> KycRecord {
+    KycRecord {
+        kyc_id,
+        did: did.clone(),
+        kyc_provider: Address::generate(env),
+        kyc_level,
+        risk_score: 10,
+        jurisdiction: String::from_str(env, "US"),
+        verified_at: 0,
+        expires_at: 365 * 86400,
+        compliance_data_hash: BytesN::from_array(env, &[1u8; 32]),
+        is_active,
+        aml_screening_passed: true,
+    }
+}
+
+fn setup(env: &Env) -> (IdentityVerificationContractClient<'_>, Address) {
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, IdentityVerificationContract);
+    let client = IdentityVerificationContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+
+    client.initialize(&admin);
+    env.as_contract(&contract_id, || {
+        authorization::initialize_admin(env, admin.clone());
+    });
+
+    (client, admin)
+}
+
+fn register_verifier(env: &Env, client: &IdentityVerificationContractClient, admin: &Address) -> Address {
+    let verifier = Address::generate(env);
+    client.register_verifier(
+        admin,
+        &verifier,
+        &Symbol::new(env, "institutional"),
+        &vec![env, Symbol::new(env, "kyc")],
+        &5,
+        &String::from_str(env, "US"),
+    );
+    verifier
+}
+
+fn submit_request(env: &Env, client: &IdentityVerificationContractClient, did: &String) -> u64 {
+    client.submit_verification_request(
+        &Address::generate(env),
+        did,
+        &Symbol::new(env, "kyc"),
+        &2,
+        &vec![env, String::from_str(env, "name")],
+        &30,
+        &None,
+    )
+}
+
+fn attest(
+    env: &Env,
+    client: &IdentityVerificationContractClient,
+    verifier: &Address,
+    request_id: u64,
+    confidence_score: u32,
+) -> u64 {
+    client.create_attestation(
+        verifier,
+        &request_id,
+        &Symbol::new(env, "kyc"),
+        &vec![env, String::from_str(env, "name")],
+        &confidence_score,
+        &BytesN::from_array(env, &[1u8; 32]),
+        &90,
+    )
+}
+
+#[test]
+fn test_revoke_attestations_for_request() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let verifier = register_verifier(&env, &client, &admin);
+    let did = String::from_str(&env, "did:stellar:alice");
+
+    let request_id = submit_request(&env, &client, &did);
+    let first = attest(&env, &client, &verifier, request_id, 80);
+    let second = attest(&env, &client, &verifier, request_id, 90);
+
+    assert_eq!(client.revoke_attestations_for_request(&verifier, &request_id, &Symbol::new(&env, "superseded")), 2);
+    assert!(client.get_attestation(&first).unwrap().is_revoked);
+    assert!(client.get_attestation(&second).unwrap().is_revoked);
+    assert_eq!(
+        client.get_attestation_revocation(&first).unwrap().reason,
+        Symbol::new(&env, "superseded")
+    );
+
+    // Already-revoked attestations are not counted again
+    assert_eq!(client.revoke_attestations_for_request(&verifier, &request_id, &Symbol::new(&env, "superseded")), 0);
+}
+
+#[test]
+fn test_reputation_weighted_confidence() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let trusted = register_verifier(&env, &client, &admin);
+    let untrusted = register_verifier(&env, &client, &admin);
+    client.set_verifier_reputation(&admin, &trusted, &90);
+    client.set_verifier_reputation(&admin, &untrusted, &10);
+
+    let did = String::from_str(&env, "did:stellar:alice");
+    let request_id = submit_request(&env, &client, &did);
+    attest(&env, &client, &trusted, request_id, 30);
+    attest(&env, &client, &untrusted, request_id, 90);
+
+    let kyc = Symbol::new(&env, "kyc");
+    let attributes = vec![&env, String::from_str(&env, "name")];
+
+    // (30 * 90 + 90 * 10) / (90 + 10) = 36
+    assert_eq!(client.get_aggregate_confidence(&did, &kyc, &attributes), 36);
+
+    // Level 1 needs 20, level 2 needs 40
+    assert!(client.verify_identity_requirements(&did, &kyc, &1, &attributes, &100));
+    assert!(!client.verify_identity_requirements(&did, &kyc, &2, &attributes, &100));
+}
+
+#[test]
+fn test_kyc_backed_attestation_requires_valid_kyc() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let verifier = register_verifier(&env, &client, &admin);
+    let did = String::from_str(&env, "did:stellar:alice");
+    let request_id = submit_request(&env, &client, &did);
+
+    let kyc_id = env.register_contract(None, MockKyc);
+    let kyc = MockKycClient::new(&env, &kyc_id);
+    kyc.set_record(&kyc_record(&env, 1, &did, 3, true));
+    kyc.set_record(&kyc_record(&env, 2, &did, 3, false));
+    kyc.set_record(&kyc_record(&env, 3, &did, 1, true));
+    client.set_kyc_contract(&admin, &kyc_id);
+
+    let attest_with = |kyc_record_id: u64| {
+        client.try_create_kyc_backed_attestation(
+            &verifier,
+            &request_id,
+            &kyc_record_id,
+            &2,
+            &Symbol::new(&env, "kyc"),
+            &vec![&env, String::from_str(&env, "name")],
+            &80,
+            &BytesN::from_array(&env, &[1u8; 32]),
+        )
+    };
+
+    assert_eq!(attest_with(2), Err(Ok(ContractError::IdentityNotVerified)));
+    assert_eq!(attest_with(3), Err(Ok(ContractError::KycLevelInsufficient)));
+    assert_eq!(attest_with(4), Err(Ok(ContractError::NotFound)));
+
+    let attestation_id = attest_with(1).unwrap().unwrap();
+    let attestation = client.get_attestation(&attestation_id).unwrap();
+    assert_eq!(attestation.kyc_id, Some(1));
+    assert_eq!(attestation.expires_at, 365 * 86400);
+}
+
+#[test]
+fn test_kyc_backed_attestation_ignores_unconfigured_contracts() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let verifier = register_verifier(&env, &client, &admin);
+    let did = String::from_str(&env, "did:stellar:alice");
+    let request_id = submit_request(&env, &client, &did);
+
+    // A valid-looking record in a contract the admin never configured
+    let rogue_id = env.register_contract(None, MockKyc);
+    MockKycClient::new(&env, &rogue_id).set_record(&kyc_record(&env, 1, &did, 3, true));
+
+    let attest = || {
+        client.try_create_kyc_backed_attestation(
+            &verifier,
+            &request_id,
+            &1,
+            &2,
+            &Symbol::new(&env, "kyc"),
+            &vec![&env, String::from_str(&env, "name")],
+            &80,
+            &BytesN::from_array(&env, &[1u8; 32]),
+        )
+    };
+
+    assert_eq!(attest(), Err(Ok(ContractError::NotInitialized)));
+
+    let outsider = Address::generate(&env);
+    assert!(client.try_set_kyc_contract(&outsider, &rogue_id).is_err());
+
+    let kyc_id = env.register_contract(None, MockKyc);
+    client.set_kyc_contract(&admin, &kyc_id);
+    assert_eq!(client.get_kyc_contract(), Some(kyc_id));
+    assert_eq!(attest(), Err(Ok(ContractError::NotFound)));
+}
+
+#[test]
+fn test_expire_stale_requests() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let did = String::from_str(&env, "did:stellar:alice");
+    let request_id = submit_request(&env, &client, &did);
+
+    assert_eq!(client.expire_stale_requests(&admin), 0);
+    assert_eq!(client.get_verification_request(&request_id).unwrap().status, Symbol::new(&env, "pending"));
+
+    env.ledger().with_mut(|li| li.timestamp += 31 * 86400);
+
+    assert_eq!(client.expire_stale_requests(&admin), 1);
+    assert_eq!(client.get_verification_request(&request_id).unwrap().status, Symbol::new(&env, "expired"));
+
+    // Once the grace period has passed the request is pruned
+    env.ledger().with_mut(|li| li.timestamp += 31 * 86400);
+
+    assert_eq!(client.expire_stale_requests(&admin), 0);
+    assert!(client.get_verification_request(&request_id).is_none());
+}
+
+#[test]
+fn test_revoke_attestation_records_reason() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let verifier = register_verifier(&env, &client, &admin);
+    let did = String::from_str(&env, "did:stellar:alice");
+
+    let request_id = submit_request(&env, &client, &did);
+    let attestation_id = attest(&env, &client, &verifier, request_id, 80);
+    assert!(client.get_attestation_revocation(&attestation_id).is_none());
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    client.revoke_attestation(&verifier, &attestation_id, &Symbol::new(&env, "compromised"));
+
+    let info = client.get_attestation_revocation(&attestation_id).unwrap();
+    assert_eq!(info.by, verifier);
+    assert_eq!(info.reason, Symbol::new(&env, "compromised"));
+    assert_eq!(info.at, 1_000);
+}
+
+#[test]
+fn test_confidence_decays_toward_expiry() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let verifier = register_verifier(&env, &client, &admin);
+    let did = String::from_str(&env, "did:stellar:alice");
+
+    let request_id = submit_request(&env, &client, &did);
+    attest(&env, &client, &verifier, request_id, 80);
+
+    let kyc = Symbol::new(&env, "kyc");
+    let attributes = vec![&env, String::from_str(&env, "name")];
+    client.set_confidence_decay(&admin, &kyc, &true);
+
+    // Fresh: the full 80 clears level 2 (40)
+    assert_eq!(client.get_aggregate_confidence(&did, &kyc, &attributes), 80);
+    assert!(client.verify_identity_requirements(&did, &kyc, &2, &attributes, &100));
+
+    // 20 of 90 days left: 80 * 20 / 90 = 17
+    env.ledger().with_mut(|li| li.timestamp += 70 * 86400);
+    assert_eq!(client.get_aggregate_confidence(&did, &kyc, &attributes), 17);
+    assert!(!client.verify_identity_requirements(&did, &kyc, &2, &attributes, &100));
+
+    // Without decay the raw score still counts
+    client.set_confidence_decay(&admin, &kyc, &false);
+    assert!(client.verify_identity_requirements(&did, &kyc, &2, &attributes, &100));
+}
+
+#[test]
+fn test_attestation_requires_matching_jurisdiction() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let us_verifier = register_verifier(&env, &client, &admin);
+    let eu_verifier = Address::generate(&env);
+    client.register_verifier(
+        &admin,
+        &eu_verifier,
+        &Symbol::new(&env, "institutional"),
+        &vec![&env, Symbol::new(&env, "kyc")],
+        &5,
+        &String::from_str(&env, "EU"),
+    );
+
+    let request_id = client.submit_verification_request(
+        &Address::generate(&env),
+        &String::from_str(&env, "did:stellar:alice"),
+        &Symbol::new(&env, "kyc"),
+        &2,
+        &vec![&env, String::from_str(&env, "name")],
+        &30,
+        &Some(String::from_str(&env, "EU")),
+    );
+
+    let result = client.try_create_attestation(
+        &us_verifier,
+        &request_id,
+        &Symbol::new(&env, "kyc"),
+        &vec![&env, String::from_str(&env, "name")],
+        &80,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &90,
+    );
+    assert_eq!(result, Err(Ok(ContractError::VerifierNotAuthorized)));
+
+    let attestation_id = attest(&env, &client, &eu_verifier, request_id, 80);
+    assert_eq!(client.get_attestation(&attestation_id).unwrap().verifier, eu_verifier);
+}
+
+#[test]
+fn test_health_reflects_pause_state() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    assert_eq!(client.health(), (true, false, 1));
+
+    client.set_paused(&admin, &true);
+    assert_eq!(client.health(), (true, true, 1));
+
+    client.set_paused(&admin, &false);
+    assert_eq!(client.health(), (true, false, 1));
+}
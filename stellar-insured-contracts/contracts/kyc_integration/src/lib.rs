@@ -1,27 +1,42 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, symbol_short, Address, BytesN, Env, Symbol, Vec, String,
+    contract, contractclient, contracterror, contractimpl, contracttype, symbol_short, Address,
+    BytesN, Env, Symbol, Vec, String,
 };
 use shared::{
-    KycRecord, IdentityVerification, authorization::{require_admin, require_role, Role},
+    KycRecord, IdentityVerification, ZkIdentityProof, ZkVerificationResult,
+    authorization::{require_admin, require_role, Role},
+    index::AppendIndex,
 };
 
 #[contract]
 pub struct KycIntegrationContract;
 
+/// Storage schema version written by this build of the contract
+const SCHEMA_VERSION: u32 = 1;
+
 // Storage keys
 const ADMIN: Symbol = symbol_short!("ADMIN");
 const PAUSED: Symbol = symbol_short!("PAUSED");
 const KYC_COUNTER: Symbol = symbol_short!("KYC_CNT");
 const PROVIDER_REGISTRY: Symbol = symbol_short!("PROV_REG");
 const JURISDICTION_CONFIG: Symbol = symbol_short!("JUR_CFG");
+const ZK_IDENTITY_CONTRACT: Symbol = symbol_short!("ZK_CONT");
 
 // KYC-specific storage prefixes
 const KYC_RECORD: Symbol = symbol_short!("KYC_REC");
 const PROVIDER_KYC_MAPPING: Symbol = symbol_short!("PROV_KYC");
 const DID_KYC_MAPPING: Symbol = symbol_short!("DID_KYC");
 const AML_SCREENING: Symbol = symbol_short!("AML_SCR");
+const KYC_COMMITMENT: Symbol = symbol_short!("KYC_CMT");
+const AML_FLAGS: Symbol = symbol_short!("AML_FLAGS");
+const ACCOUNT_DID: Symbol = symbol_short!("ACCT_DID");
+const PROVIDER_KYC_INDEX: Symbol = symbol_short!("PROV_IDX");
+
+// Thresholds a privacy-mode range proof must attest to (mirrors check_kyc_requirements)
+const PRIVACY_MIN_KYC_LEVEL: u32 = 2;
+const PRIVACY_MAX_RISK_SCORE: u32 = 50;
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -88,6 +103,28 @@ pub struct AmlScreeningResult {
     pub next_screening_date: u64,
 }
 
+/// Privacy-mode KYC commitment (hides level and risk score)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KycCommitment {
+    pub did: String,
+    pub provider: Address,
+    pub commitment: BytesN<32>,
+    pub created_at: u64,
+    pub is_active: bool,
+}
+
+/// Range statement proven against a KYC commitment.
+/// The referenced zk_identity proof must carry public inputs
+/// `[hex(commitment), min_kyc_level, max_risk_score]`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KycRangeProof {
+    pub proof_id: BytesN<32>,
+    pub min_kyc_level: u32,
+    pub max_risk_score: u32,
+}
+
 /// KYC requirements for different use cases
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -120,6 +157,34 @@ fn get_next_screening_id(env: &Env) -> u64 {
     current + 1
 }
 
+/// Render a u32 as a decimal string (public input encoding)
+fn u32_to_string(env: &Env, value: u32) -> String {
+    let mut buf = [0u8; 10];
+    let mut i = buf.len();
+    let mut n = value;
+    loop {
+        i -= 1;
+        buf[i] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    String::from_bytes(env, &buf[i..])
+}
+
+/// Render a 32-byte commitment as lowercase hex (public input encoding)
+fn commitment_to_hex(env: &Env, commitment: &BytesN<32>) -> String {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let bytes = commitment.to_array();
+    let mut buf = [0u8; 64];
+    for (i, byte) in bytes.iter().enumerate() {
+        buf[i * 2] = HEX[(byte >> 4) as usize];
+        buf[i * 2 + 1] = HEX[(byte & 0x0f) as usize];
+    }
+    String::from_bytes(env, &buf)
+}
+
 /// Validate jurisdiction code format
 fn validate_jurisdiction(jurisdiction: &String) -> Result<(), ContractError> {
     if jurisdiction.len() != 2 {
@@ -240,6 +305,132 @@ impl KycIntegrationContract {
         Ok(())
     }
 
+    /// Deactivate a jurisdiction, blocking new KYC records for that code
+    pub fn deactivate_jurisdiction(
+        env: Env,
+        admin: Address,
+        jurisdiction_code: String,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        require_admin(&env, &admin)?;
+
+        let mut config: JurisdictionConfig = env
+            .storage()
+            .persistent()
+            .get(&(JURISDICTION_CONFIG, jurisdiction_code.clone()))
+            .ok_or(ContractError::NotFound)?;
+
+        if !config.is_active {
+            return Err(ContractError::InvalidState);
+        }
+
+        config.is_active = false;
+        env.storage()
+            .persistent()
+            .set(&(JURISDICTION_CONFIG, jurisdiction_code.clone()), &config);
+
+        env.events().publish(
+            (symbol_short!("jur_deact"), jurisdiction_code),
+            (),
+        );
+
+        Ok(())
+    }
+
+    /// Authorize a provider for a jurisdiction, updating both the provider's
+    /// `supported_jurisdictions` and the jurisdiction's `supported_providers`
+    pub fn add_provider_to_jurisdiction(
+        env: Env,
+        admin: Address,
+        provider_address: Address,
+        jurisdiction_code: String,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        require_admin(&env, &admin)?;
+
+        let mut provider: KycProvider = env
+            .storage()
+            .persistent()
+            .get(&(PROVIDER_REGISTRY, provider_address.clone()))
+            .ok_or(ContractError::NotFound)?;
+
+        let mut config: JurisdictionConfig = env
+            .storage()
+            .persistent()
+            .get(&(JURISDICTION_CONFIG, jurisdiction_code.clone()))
+            .ok_or(ContractError::NotFound)?;
+
+        if !provider.supported_jurisdictions.contains(&jurisdiction_code) {
+            provider.supported_jurisdictions.push_back(jurisdiction_code.clone());
+        }
+
+        if !config.supported_providers.contains(&provider_address) {
+            config.supported_providers.push_back(provider_address.clone());
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(PROVIDER_REGISTRY, provider_address.clone()), &provider);
+        env.storage()
+            .persistent()
+            .set(&(JURISDICTION_CONFIG, jurisdiction_code.clone()), &config);
+
+        env.events().publish(
+            (symbol_short!("prov_add"), provider_address),
+            jurisdiction_code,
+        );
+
+        Ok(())
+    }
+
+    /// Remove a provider from a jurisdiction on both sides of the mapping
+    pub fn remove_provider_jurisdiction(
+        env: Env,
+        admin: Address,
+        provider_address: Address,
+        jurisdiction_code: String,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        require_admin(&env, &admin)?;
+
+        let mut provider: KycProvider = env
+            .storage()
+            .persistent()
+            .get(&(PROVIDER_REGISTRY, provider_address.clone()))
+            .ok_or(ContractError::NotFound)?;
+
+        let mut config: JurisdictionConfig = env
+            .storage()
+            .persistent()
+            .get(&(JURISDICTION_CONFIG, jurisdiction_code.clone()))
+            .ok_or(ContractError::NotFound)?;
+
+        if let Some(index) = provider.supported_jurisdictions.first_index_of(&jurisdiction_code) {
+            provider.supported_jurisdictions.remove(index);
+        }
+
+        if let Some(index) = config.supported_providers.first_index_of(&provider_address) {
+            config.supported_providers.remove(index);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(PROVIDER_REGISTRY, provider_address.clone()), &provider);
+        env.storage()
+            .persistent()
+            .set(&(JURISDICTION_CONFIG, jurisdiction_code.clone()), &config);
+
+        env.events().publish(
+            (symbol_short!("prov_rem"), provider_address),
+            jurisdiction_code,
+        );
+
+        Ok(())
+    }
+
     /// Create KYC record for a DID
     pub fn create_kyc_record(
         env: Env,
@@ -332,6 +523,7 @@ impl KycIntegrationContract {
         env.storage()
             .persistent()
             .set(&(PROVIDER_KYC_MAPPING, (provider.clone(), kyc_id)), &did);
+        AppendIndex::new(&env, PROVIDER_KYC_INDEX, provider.clone()).push(&env, &kyc_id);
         env.storage()
             .persistent()
             .set(&(DID_KYC_MAPPING, did.clone()), &kyc_id);
@@ -344,7 +536,154 @@ impl KycIntegrationContract {
         Ok(kyc_id)
     }
 
-    /// Submit AML screening
+    /// Set the zk_identity contract used to verify privacy-mode range proofs (admin only)
+    pub fn set_zk_identity_contract(
+        env: Env,
+        admin: Address,
+        zk_contract: Address,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        require_admin(&env, &admin)?;
+
+        env.storage().persistent().set(&ZK_IDENTITY_CONTRACT, &zk_contract);
+
+        env.events().publish((symbol_short!("zk_contr"), admin), zk_contract);
+
+        Ok(())
+    }
+
+    /// Create a privacy-mode KYC record that stores only a commitment to
+    /// the level and risk score
+    pub fn create_kyc_commitment(
+        env: Env,
+        provider: Address,
+        did: String,
+        commitment: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        provider.require_auth();
+
+        if is_paused(&env) {
+            return Err(ContractError::Paused);
+        }
+
+        let provider_info: KycProvider = env
+            .storage()
+            .persistent()
+            .get(&(PROVIDER_REGISTRY, provider.clone()))
+            .ok_or(ContractError::ProviderNotAuthorized)?;
+
+        if !provider_info.is_active {
+            return Err(ContractError::ProviderNotActive);
+        }
+
+        let kyc_commitment = KycCommitment {
+            did: did.clone(),
+            provider,
+            commitment: commitment.clone(),
+            created_at: env.ledger().timestamp(),
+            is_active: true,
+        };
+
+        env.storage()
+            .persistent()
+            .set(&(KYC_COMMITMENT, did.clone()), &kyc_commitment);
+
+        env.events().publish(
+            (symbol_short!("kyc_cmt"), did),
+            commitment,
+        );
+
+        Ok(())
+    }
+
+    /// Verify a range proof ("level >= 2 and risk <= 50") against a DID's
+    /// KYC commitment using the registered zk_identity circuit
+    pub fn verify_kyc_range_proof(
+        env: Env,
+        did: String,
+        proof: KycRangeProof,
+        circuit_id: Symbol,
+    ) -> Result<bool, ContractError> {
+        if is_paused(&env) {
+            return Err(ContractError::Paused);
+        }
+
+        let kyc_commitment: KycCommitment = env
+            .storage()
+            .persistent()
+            .get(&(KYC_COMMITMENT, did.clone()))
+            .ok_or(ContractError::NotFound)?;
+
+        if !kyc_commitment.is_active {
+            return Ok(false);
+        }
+
+        if proof.min_kyc_level == 0 || proof.min_kyc_level > 4 || proof.max_risk_score > 100 {
+            return Err(ContractError::InvalidInput);
+        }
+
+        // The proven range must be at least as strict as the privacy-mode requirements
+        if proof.min_kyc_level < PRIVACY_MIN_KYC_LEVEL || proof.max_risk_score > PRIVACY_MAX_RISK_SCORE {
+            return Ok(false);
+        }
+
+        let zk_contract: Address = env
+            .storage()
+            .persistent()
+            .get(&ZK_IDENTITY_CONTRACT)
+            .ok_or(ContractError::NotInitialized)?;
+        let zk_client = ZkIdentityClient::new(&env, &zk_contract);
+
+        let zk_proof = match zk_client.get_zk_proof(&proof.proof_id) {
+            Some(zk_proof) => zk_proof,
+            None => return Ok(false),
+        };
+
+        if zk_proof.did != did || zk_proof.circuit_id != circuit_id {
+            return Ok(false);
+        }
+
+        // Bind the proof's public statement to the commitment and the claimed range
+        let mut expected_inputs = Vec::new(&env);
+        expected_inputs.push_back(commitment_to_hex(&env, &kyc_commitment.commitment));
+        expected_inputs.push_back(u32_to_string(&env, proof.min_kyc_level));
+        expected_inputs.push_back(u32_to_string(&env, proof.max_risk_score));
+        if zk_proof.public_inputs != expected_inputs {
+            return Ok(false);
+        }
+
+        Ok(zk_client.verify_proof(&proof.proof_id) == ZkVerificationResult::Valid)
+    }
+
+    /// Add a flag name to the allowed AML flag taxonomy
+    pub fn add_aml_flag(env: Env, admin: Address, flag: String) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        require_admin(&env, &admin)?;
+
+        if flag.len() == 0 {
+            return Err(ContractError::InvalidInput);
+        }
+
+        let mut flags: Vec<String> = env
+            .storage()
+            .persistent()
+            .get(&AML_FLAGS)
+            .unwrap_or(Vec::new(&env));
+
+        if flags.contains(&flag) {
+            return Err(ContractError::AlreadyExists);
+        }
+
+        flags.push_back(flag.clone());
+        env.storage().persistent().set(&AML_FLAGS, &flags);
+
+        env.events().publish((symbol_short!("aml_flag"), admin), flag);
+
+        Ok(())
+    }
+
+    /// Submit AML screening. Every flag must be in the allowed taxonomy.
     pub fn submit_aml_screening(
         env: Env,
         provider: Address,
@@ -372,6 +711,17 @@ impl KycIntegrationContract {
             return Err(ContractError::AmlScreeningFailed);
         }
 
+        let allowed_flags: Vec<String> = env
+            .storage()
+            .persistent()
+            .get(&AML_FLAGS)
+            .unwrap_or(Vec::new(&env));
+        for flag in flags.iter() {
+            if !allowed_flags.contains(&flag) {
+                return Err(ContractError::InvalidInput);
+            }
+        }
+
         let screening_id = get_next_screening_id(&env);
         let next_screening_date = env.ledger().timestamp() + (next_screening_days as u64 * 86400);
 
@@ -446,6 +796,29 @@ impl KycIntegrationContract {
         Ok(true)
     }
 
+    /// Bind an on-chain account to a DID verified by `provider`, so other
+    /// contracts can look up the account's KYC level
+    pub fn bind_account(
+        env: Env,
+        provider: Address,
+        account: Address,
+        did: String,
+    ) -> Result<(), ContractError> {
+        provider.require_auth();
+        account.require_auth();
+
+        let kyc_record = Self::get_active_kyc_for_did(env.clone(), did.clone()).ok_or(ContractError::NotFound)?;
+        if kyc_record.kyc_provider != provider {
+            return Err(ContractError::Unauthorized);
+        }
+
+        env.storage().persistent().set(&(ACCOUNT_DID, account.clone()), &did);
+
+        env.events().publish((symbol_short!("acct_bind"), did), account);
+
+        Ok(())
+    }
+
     /// Deactivate KYC record
     pub fn deactivate_kyc(
         env: Env,
@@ -477,6 +850,55 @@ impl KycIntegrationContract {
         Ok(())
     }
 
+    /// Keep a DID's KYC mapping, active record and commitment alive for at
+    /// least `ledgers` more ledgers. Entries that already live longer are
+    /// left as they are.
+    pub fn bump_did_ttl(env: Env, did: String, ledgers: u32) -> Result<(), ContractError> {
+        if ledgers == 0 {
+            return Err(ContractError::InvalidInput);
+        }
+
+        let storage = env.storage().persistent();
+        let mut found = false;
+
+        let mapping_key = (DID_KYC_MAPPING, did.clone());
+        if let Some(kyc_id) = storage.get::<_, u64>(&mapping_key) {
+            storage.extend_ttl(&mapping_key, ledgers, ledgers);
+            let record_key = (KYC_RECORD, kyc_id);
+            if storage.has(&record_key) {
+                storage.extend_ttl(&record_key, ledgers, ledgers);
+            }
+            found = true;
+        }
+
+        let commitment_key = (KYC_COMMITMENT, did);
+        if storage.has(&commitment_key) {
+            storage.extend_ttl(&commitment_key, ledgers, ledgers);
+            found = true;
+        }
+
+        if !found {
+            return Err(ContractError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Keep a single KYC record alive for at least `ledgers` more ledgers
+    pub fn bump_kyc_ttl(env: Env, kyc_id: u64, ledgers: u32) -> Result<(), ContractError> {
+        if ledgers == 0 {
+            return Err(ContractError::InvalidInput);
+        }
+
+        let key = (KYC_RECORD, kyc_id);
+        if !env.storage().persistent().has(&key) {
+            return Err(ContractError::NotFound);
+        }
+        env.storage().persistent().extend_ttl(&key, ledgers, ledgers);
+
+        Ok(())
+    }
+
     /// Pause/unpause contract (admin only)
     pub fn set_paused(env: Env, admin: Address, paused: bool) -> Result<(), ContractError> {
         admin.require_auth();
@@ -498,6 +920,11 @@ impl KycIntegrationContract {
 
     // ===== View Functions =====
 
+    /// Health check for monitoring: (initialized, paused, schema version)
+    pub fn health(env: Env) -> (bool, bool, u32) {
+        (env.storage().persistent().has(&ADMIN), is_paused(&env), SCHEMA_VERSION)
+    }
+
     /// Get KYC record
     pub fn get_kyc_record(env: Env, kyc_id: u64) -> Option<KycRecord> {
         env.storage().persistent().get(&(KYC_RECORD, kyc_id))
@@ -513,6 +940,19 @@ impl KycIntegrationContract {
         env.storage().persistent().get(&(JURISDICTION_CONFIG, jurisdiction_code))
     }
 
+    /// List the allowed AML flags
+    pub fn list_aml_flags(env: Env) -> Vec<String> {
+        env.storage()
+            .persistent()
+            .get(&AML_FLAGS)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get privacy-mode KYC commitment for DID
+    pub fn get_kyc_commitment(env: Env, did: String) -> Option<KycCommitment> {
+        env.storage().persistent().get(&(KYC_COMMITMENT, did))
+    }
+
     /// Get AML screening result
     pub fn get_aml_screening(env: Env, screening_id: u64) -> Option<AmlScreeningResult> {
         env.storage().persistent().get(&(AML_SCREENING, screening_id))
@@ -530,11 +970,24 @@ impl KycIntegrationContract {
         None
     }
 
-    /// Get all KYC records for provider
-    pub fn get_provider_kyc_records(env: Env, provider: Address) -> Vec<KycRecord> {
-        // In production, maintain an index for efficient querying
-        // For now, return empty vector
-        Vec::new(&env)
+    /// Get the active KYC level for a bound account, or 0 if it has none
+    pub fn get_kyc_level(env: Env, account: Address) -> u32 {
+        let did: Option<String> = env.storage().persistent().get(&(ACCOUNT_DID, account));
+        did.and_then(|did| Self::get_active_kyc_for_did(env, did))
+            .map_or(0, |kyc_record| kyc_record.kyc_level)
+    }
+
+    /// Get up to `limit` KYC records issued by a provider, oldest first,
+    /// skipping `offset`. Deactivated records are included.
+    pub fn get_provider_kyc_records(env: Env, provider: Address, offset: u32, limit: u32) -> Vec<KycRecord> {
+        let ids: Vec<u64> = AppendIndex::new(&env, PROVIDER_KYC_INDEX, provider).page(&env, offset, limit);
+        let mut records = Vec::new(&env);
+        for kyc_id in ids.iter() {
+            if let Some(record) = env.storage().persistent().get(&(KYC_RECORD, kyc_id)) {
+                records.push_back(record);
+            }
+        }
+        records
     }
 
     /// Check if provider is authorized for jurisdiction
@@ -557,3 +1010,13 @@ impl KycIntegrationContract {
         (0, 0, 0)
     }
 }
+
+// Client interface for the zk_identity contract
+#[contractclient(name = "ZkIdentityClient")]
+pub trait ZkIdentityInterface {
+    fn get_zk_proof(env: Env, proof_id: BytesN<32>) -> Option<ZkIdentityProof>;
+    fn verify_proof(env: Env, proof_id: BytesN<32>) -> ZkVerificationResult;
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,234 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::testutils::{storage::Persistent as _, Address as _};
+use soroban_sdk::{contract, contractimpl, vec};
+
+#[contract]
+pub struct MockZkIdentity;
+
+#[contractimpl]
+impl MockZkIdentity {
+    pub fn set_proof(env: Env, proof: ZkIdentityProof) {
+        env.storage().persistent().set(&proof.proof_id, &proof);
+    }
+
+    pub fn get_zk_proof(env: Env, proof_id: BytesN<32>) -> Option<ZkIdentityProof> {
+        env.storage().persistent().get(&proof_id)
+    }
+
+    pub fn verify_proof(env: Env, proof_id: BytesN<32>) -> ZkVerificationResult {
+        match env.storage().persistent().get::<_, ZkIdentityProof>(&proof_id) {
+            Some(proof) if !proof.is_revoked => ZkVerificationResult::Valid,
+            _ => ZkVerificationResult::Invalid,
+        }
+    }
+}
+
+fn setup(env: &Env) -> (KycIntegrationContractClient<'_>, Address, Address) {
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, KycIntegrationContract);
+    let client = KycIntegrationContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    let provider = Address::generate(env);
+
+    client.initialize(&admin);
+    env.as_contract(&contract_id, || {
+        authorization::initialize_admin(env, admin.clone());
+    });
+
+    let us = String::from_str(env, "US");
+    client.register_kyc_provider(
+        &admin,
+        &provider,
+        &String::from_str(env, "Acme KYC"),
+        &Symbol::new(env, "digital_identity"),
+        &vec![env, us.clone()],
+        &3,
+        &true,
+    );
+    client.configure_jurisdiction(&admin, &us, &1, &80, &false, &365, &vec![env, provider.clone()]);
+
+    (client, admin, provider)
+}
+
+#[test]
+fn test_deactivated_jurisdiction_blocks_new_kyc() {
+    let env = Env::default();
+    let (client, admin, provider) = setup(&env);
+    let us = String::from_str(&env, "US");
+
+    client.deactivate_jurisdiction(&admin, &us);
+    assert!(!client.get_jurisdiction_config(&us).unwrap().is_active);
+
+    let result = client.try_create_kyc_record(
+        &provider,
+        &String::from_str(&env, "did:stellar:alice"),
+        &2,
+        &10,
+        &us,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &365,
+        &true,
+    );
+    assert_eq!(result, Err(Ok(ContractError::JurisdictionNotSupported)));
+}
+
+#[test]
+fn test_provider_jurisdiction_sync() {
+    let env = Env::default();
+    let (client, admin, provider) = setup(&env);
+    let gb = String::from_str(&env, "GB");
+
+    client.configure_jurisdiction(&admin, &gb, &1, &80, &false, &365, &Vec::new(&env));
+    client.add_provider_to_jurisdiction(&admin, &provider, &gb);
+
+    assert!(client.get_kyc_provider(&provider).unwrap().supported_jurisdictions.contains(&gb));
+    assert!(client.get_jurisdiction_config(&gb).unwrap().supported_providers.contains(&provider));
+
+    client.remove_provider_jurisdiction(&admin, &provider, &gb);
+
+    assert!(!client.get_kyc_provider(&provider).unwrap().supported_jurisdictions.contains(&gb));
+    assert!(!client.get_jurisdiction_config(&gb).unwrap().supported_providers.contains(&provider));
+    assert!(!client.is_provider_authorized_for_jurisdiction(&provider, &gb));
+}
+
+#[test]
+fn test_kyc_range_proof_privacy_mode() {
+    let env = Env::default();
+    let (client, admin, provider) = setup(&env);
+
+    let zk_id = env.register_contract(None, MockZkIdentity);
+    let zk_client = MockZkIdentityClient::new(&env, &zk_id);
+    client.set_zk_identity_contract(&admin, &zk_id);
+
+    let did = String::from_str(&env, "did:stellar:alice");
+    let commitment = BytesN::from_array(&env, &[7u8; 32]);
+    let circuit_id = Symbol::new(&env, "kyc_range");
+    client.create_kyc_commitment(&provider, &did, &commitment);
+
+    let proof_id = BytesN::from_array(&env, &[9u8; 32]);
+    zk_client.set_proof(&ZkIdentityProof {
+        proof_id: proof_id.clone(),
+        did: did.clone(),
+        circuit_id: circuit_id.clone(),
+        public_inputs: vec![
+            &env,
+            commitment_to_hex(&env, &commitment),
+            String::from_str(&env, "2"),
+            String::from_str(&env, "50"),
+        ],
+        proof_data: BytesN::from_array(&env, &[1u8; 32]),
+        verification_key_hash: BytesN::from_array(&env, &[2u8; 32]),
+        created_at: 0,
+        expires_at: u64::MAX,
+        is_revoked: false,
+    });
+
+    let valid = KycRangeProof { proof_id: proof_id.clone(), min_kyc_level: 2, max_risk_score: 50 };
+    assert!(client.verify_kyc_range_proof(&did, &valid, &circuit_id));
+
+    // A range weaker than the privacy-mode requirement is rejected
+    let out_of_range = KycRangeProof { proof_id, min_kyc_level: 1, max_risk_score: 50 };
+    assert!(!client.verify_kyc_range_proof(&did, &out_of_range, &circuit_id));
+}
+
+#[test]
+fn test_bump_did_ttl_extends_kyc_record() {
+    let env = Env::default();
+    let (client, _admin, provider) = setup(&env);
+    let did = String::from_str(&env, "did:stellar:alice");
+
+    let kyc_id = client.create_kyc_record(
+        &provider,
+        &did,
+        &2,
+        &10,
+        &String::from_str(&env, "US"),
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &365,
+        &true,
+    );
+
+    let ttl = || {
+        env.as_contract(&client.address, || {
+            env.storage().persistent().get_ttl(&(KYC_RECORD, kyc_id))
+        })
+    };
+
+    let before = ttl();
+    client.bump_did_ttl(&did, &100_000);
+    assert!(ttl() > before);
+    // The TTL is raised to the requested ledgers, not by them
+    assert_eq!(ttl(), 100_000);
+}
+
+#[test]
+fn test_aml_screening_rejects_unknown_flags() {
+    let env = Env::default();
+    let (client, admin, provider) = setup(&env);
+    let did = String::from_str(&env, "did:stellar:alice");
+    let pep = String::from_str(&env, "pep");
+
+    client.add_aml_flag(&admin, &String::from_str(&env, "sanction_list"));
+    client.add_aml_flag(&admin, &pep);
+    assert_eq!(client.list_aml_flags().len(), 2);
+    assert_eq!(client.try_add_aml_flag(&admin, &pep), Err(Ok(ContractError::AlreadyExists)));
+
+    let screening_id = client.submit_aml_screening(&provider, &did, &20, &vec![&env, pep.clone()], &true, &false, &90);
+    assert_eq!(client.get_aml_screening(&screening_id).unwrap().flags, vec![&env, pep.clone()]);
+
+    let result = client.try_submit_aml_screening(
+        &provider,
+        &did,
+        &20,
+        &vec![&env, pep, String::from_str(&env, "PEP_typo")],
+        &true,
+        &false,
+        &90,
+    );
+    assert_eq!(result, Err(Ok(ContractError::InvalidInput)));
+}
+
+#[test]
+fn test_kyc_level_for_bound_account() {
+    let env = Env::default();
+    let (client, _admin, provider) = setup(&env);
+    let did = String::from_str(&env, "did:stellar:alice");
+    let account = Address::generate(&env);
+
+    let result = client.try_bind_account(&provider, &account, &did);
+    assert_eq!(result, Err(Ok(ContractError::NotFound)));
+
+    let kyc_id = client.create_kyc_record(
+        &provider,
+        &did,
+        &3,
+        &10,
+        &String::from_str(&env, "US"),
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &365,
+        &true,
+    );
+    assert_eq!(client.get_kyc_level(&account), 0);
+
+    client.bind_account(&provider, &account, &did);
+    assert_eq!(client.get_kyc_level(&account), 3);
+
+    client.deactivate_kyc(&provider, &kyc_id);
+    assert_eq!(client.get_kyc_level(&account), 0);
+}
+
+#[test]
+fn test_health_reflects_pause_state() {
+    let env = Env::default();
+    let (client, admin, _) = setup(&env);
+    assert_eq!(client.health(), (true, false, 1));
+
+    client.set_paused(&admin, &true);
+    assert_eq!(client.health(), (true, true, 1));
+
+    client.set_paused(&admin, &false);
+    assert_eq!(client.health(), (true, false, 1));
+}
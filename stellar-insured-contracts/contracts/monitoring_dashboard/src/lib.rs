@@ -1,18 +1,32 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, symbol_short, Address, BytesN, Env, Symbol, Vec, String, Map,
+    contract, contracterror, contractimpl, contracttype, symbol_short,
+    xdr::{FromXdr, ToXdr},
+    Address, Bytes, BytesN, Env, Symbol, Vec, String, Map,
 };
 use shared::authorization::{require_admin, require_role, Role};
+use shared::idempotency::{idempotent_id, remember_idempotent_id};
+use shared::index::AppendIndex;
+use shared::rng;
 
 #[contract]
 pub struct MonitoringDashboardContract;
 
+/// Storage schema version written by this build of the contract
+const SCHEMA_VERSION: u32 = 1;
+
 // Storage keys
 const ADMIN: Symbol = symbol_short!("ADMIN");
 const PAUSED: Symbol = symbol_short!("PAUSED");
 const DASHBOARD_COUNTER: Symbol = symbol_short!("DASH_CNT");
 const WIDGET_COUNTER: Symbol = symbol_short!("WID_CNT");
+const REFRESH_BOUNDS: Symbol = symbol_short!("REF_BND");
+const DEFAULT_THEME: Symbol = symbol_short!("DEF_THEME");
+
+// Default refresh interval bounds (seconds)
+const DEFAULT_MIN_REFRESH_INTERVAL: u64 = 5;
+const DEFAULT_MAX_REFRESH_INTERVAL: u64 = 86400;
 
 // Dashboard storage prefixes
 const DASHBOARD_CONFIG: Symbol = symbol_short!("DASH_CFG");
@@ -21,6 +35,9 @@ const DASHBOARD_SHARE: Symbol = symbol_short!("DASH_SHARE");
 const DASHBOARD_TEMPLATE: Symbol = symbol_short!("DASH_TEMP");
 const USER_PREFERENCES: Symbol = symbol_short!("USER_PREF");
 const DASHBOARD_SNAPSHOT: Symbol = symbol_short!("DASH_SNAP");
+const WIDGET_DATA_CACHE: Symbol = symbol_short!("WID_CACHE");
+const EDIT_GRANT: Symbol = symbol_short!("EDIT_GRNT");
+const USER_DASHBOARDS: Symbol = symbol_short!("USER_DASH");
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -150,6 +167,8 @@ pub struct Widget {
     pub created_at: u64,
     /// Last updated timestamp
     pub updated_at: u64,
+    /// Widgets on the same dashboard this one derives its data from
+    pub depends_on: Vec<u64>,
 }
 
 /// Data source for widget
@@ -168,6 +187,18 @@ pub struct DataSource {
     pub aggregation: Option<AggregationSettings>,
     /// Filters to apply
     pub filters: Map<Symbol, String>,
+    /// How long a fetched payload may be served from cache (seconds, 0 = no caching)
+    pub cache_ttl: u64,
+}
+
+/// Last payload fetched for a widget, kept in temporary storage
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CachedWidgetData {
+    /// Fetched payload
+    pub payload: Bytes,
+    /// When the payload was fetched
+    pub fetched_at: u64,
 }
 
 /// Aggregation settings for data source
@@ -292,8 +323,8 @@ pub struct DashboardSnapshot {
     pub dashboard_id: u64,
     /// Snapshot name
     pub name: String,
-    /// Snapshot data (serialized dashboard state)
-    pub snapshot_data: Vec<u8>,
+    /// Snapshot data (XDR-serialized dashboard state)
+    pub snapshot_data: Bytes,
     /// Created timestamp
     pub created_at: u64,
     /// Snapshot creator
@@ -342,22 +373,77 @@ fn get_next_widget_id(env: &Env) -> u64 {
     current + 1
 }
 
-/// Generate share token
-fn generate_share_token(env: &Env, dashboard_id: u64, user: &Address) -> BytesN<32> {
-    let timestamp = env.ledger().timestamp();
-    let combined = format!("{}:{}:{}", dashboard_id, user, timestamp);
-    // In production, use proper cryptographic hash
-    BytesN::from_array(env, &[
-        (dashboard_id >> 24) as u8,
-        (dashboard_id >> 16) as u8,
-        (dashboard_id >> 8) as u8,
-        dashboard_id as u8,
-        (timestamp >> 24) as u8,
-        (timestamp >> 16) as u8,
-        (timestamp >> 8) as u8,
-        timestamp as u8,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    ])
+/// Get (min, max) refresh interval bounds
+fn get_refresh_bounds(env: &Env) -> (u64, u64) {
+    env.storage()
+        .persistent()
+        .get(&REFRESH_BOUNDS)
+        .unwrap_or((DEFAULT_MIN_REFRESH_INTERVAL, DEFAULT_MAX_REFRESH_INTERVAL))
+}
+
+/// Validate a widget refresh interval against the configured bounds
+fn validate_refresh_interval(env: &Env, refresh_interval: u64) -> Result<(), ContractError> {
+    let (min, max) = get_refresh_bounds(env);
+    if refresh_interval < min || refresh_interval > max {
+        return Err(ContractError::InvalidInput);
+    }
+    Ok(())
+}
+
+/// Theme new dashboards start with: the admin-configured default, or the
+/// built-in one when none is set
+fn get_default_theme(env: &Env) -> DashboardTheme {
+    env.storage().instance().get(&DEFAULT_THEME).unwrap_or_else(|| DashboardTheme {
+        name: String::from_str(env, "default"),
+        primary_color: String::from_str(env, "#007bff"),
+        secondary_color: String::from_str(env, "#6c757d"),
+        background_color: String::from_str(env, "#ffffff"),
+        text_color: String::from_str(env, "#000000"),
+        font_family: String::from_str(env, "Arial"),
+        border_radius: 4,
+        shadow: true,
+    })
+}
+
+/// Clamp a dashboard auto-refresh interval into the configured bounds
+fn clamp_refresh_interval(env: &Env, refresh_interval: u64) -> u64 {
+    let (min, max) = get_refresh_bounds(env);
+    refresh_interval.clamp(min, max)
+}
+
+/// Require that `editor` owns the dashboard, is a permanent editor, or holds
+/// an unexpired temporary edit grant
+fn require_can_edit(env: &Env, dashboard: &Dashboard, editor: &Address) -> Result<(), ContractError> {
+    if dashboard.owner == *editor || dashboard.permissions.allowed_editors.contains(editor) {
+        return Ok(());
+    }
+
+    let grant: Option<u64> = env
+        .storage()
+        .persistent()
+        .get(&(EDIT_GRANT, dashboard.dashboard_id, editor.clone()));
+    match grant {
+        Some(expires_at) if env.ledger().timestamp() < expires_at => Ok(()),
+        _ => Err(ContractError::Unauthorized),
+    }
+}
+
+/// Decode the dashboard state captured in a snapshot
+fn load_snapshot_dashboard(env: &Env, snapshot_id: u64) -> Result<Dashboard, ContractError> {
+    let snapshot: DashboardSnapshot = env
+        .storage()
+        .persistent()
+        .get(&(DASHBOARD_SNAPSHOT, snapshot_id))
+        .ok_or(ContractError::NotFound)?;
+
+    Dashboard::from_xdr(env, &snapshot.snapshot_data).map_err(|_| ContractError::SnapshotInvalid)
+}
+
+/// Fetch the current data for a widget from its data source
+fn fetch_widget_data(env: &Env, widget: &Widget) -> Bytes {
+    // In production, query the configured data source; for now capture the
+    // source definition and fetch time
+    (widget.data_source.clone(), env.ledger().timestamp()).to_xdr(env)
 }
 
 #[contractimpl]
@@ -378,7 +464,8 @@ impl MonitoringDashboardContract {
         Ok(())
     }
 
-    /// Create a new dashboard
+    /// Create a new dashboard. Retrying with the same `idempotency_key`
+    /// returns the originally created id.
     pub fn create_dashboard(
         env: Env,
         owner: Address,
@@ -387,6 +474,7 @@ impl MonitoringDashboardContract {
         layout_type: Symbol,
         columns: u32,
         rows: u32,
+        idempotency_key: Option<BytesN<32>>,
     ) -> Result<u64, ContractError> {
         owner.require_auth();
 
@@ -394,6 +482,10 @@ impl MonitoringDashboardContract {
             return Err(ContractError::Paused);
         }
 
+        if let Some(dashboard_id) = idempotent_id(&env, symbol_short!("dash"), &owner, &idempotency_key) {
+            return Ok(dashboard_id);
+        }
+
         let dashboard_id = get_next_dashboard_id(&env);
 
         let layout = DashboardLayout {
@@ -404,16 +496,7 @@ impl MonitoringDashboardContract {
             breakpoints: Map::new(&env),
         };
 
-        let theme = DashboardTheme {
-            name: String::from_str(&env, "default"),
-            primary_color: String::from_str(&env, "#007bff"),
-            secondary_color: String::from_str(&env, "#6c757d"),
-            background_color: String::from_str(&env, "#ffffff"),
-            text_color: String::from_str(&env, "#000000"),
-            font_family: String::from_str(&env, "Arial"),
-            border_radius: 4,
-            shadow: true,
-        };
+        let theme = get_default_theme(&env);
 
         let permissions = DashboardPermissions {
             is_public: false,
@@ -436,7 +519,7 @@ impl MonitoringDashboardContract {
             layout,
             widgets: Vec::new(&env),
             default_time_range: 86400, // 24 hours
-            auto_refresh_interval: 300, // 5 minutes
+            auto_refresh_interval: clamp_refresh_interval(&env, 300), // 5 minutes
             theme,
             permissions,
             created_at: env.ledger().timestamp(),
@@ -447,6 +530,8 @@ impl MonitoringDashboardContract {
         env.storage()
             .persistent()
             .set(&(DASHBOARD_CONFIG, dashboard_id), &dashboard);
+        AppendIndex::new(&env, USER_DASHBOARDS, owner.clone()).push(&env, &dashboard_id);
+        remember_idempotent_id(&env, symbol_short!("dash"), &owner, &idempotency_key, dashboard_id);
 
         env.events().publish(
             (symbol_short!("dashboard_created"), owner),
@@ -459,7 +544,7 @@ impl MonitoringDashboardContract {
     /// Add widget to dashboard
     pub fn add_widget(
         env: Env,
-        owner: Address,
+        editor: Address,
         dashboard_id: u64,
         widget_type: Symbol,
         title: String,
@@ -468,22 +553,22 @@ impl MonitoringDashboardContract {
         visualization: VisualizationSettings,
         refresh_interval: u64,
     ) -> Result<u64, ContractError> {
-        owner.require_auth();
+        editor.require_auth();
 
         if is_paused(&env) {
             return Err(ContractError::Paused);
         }
 
-        // Verify dashboard ownership
+        // Verify edit access
         let mut dashboard: Dashboard = env
             .storage()
             .persistent()
             .get(&(DASHBOARD_CONFIG, dashboard_id))
             .ok_or(ContractError::DashboardNotFound)?;
 
-        if dashboard.owner != owner {
-            return Err(ContractError::Unauthorized);
-        }
+        require_can_edit(&env, &dashboard, &editor)?;
+
+        validate_refresh_interval(&env, refresh_interval)?;
 
         let widget_id = get_next_widget_id(&env);
 
@@ -498,6 +583,7 @@ impl MonitoringDashboardContract {
             visible: true,
             created_at: env.ledger().timestamp(),
             updated_at: env.ledger().timestamp(),
+            depends_on: Vec::new(&env),
         };
 
         dashboard.widgets.push_back(widget.clone());
@@ -515,7 +601,7 @@ impl MonitoringDashboardContract {
             .set(&(DASHBOARD_WIDGET, widget_id), &widget);
 
         env.events().publish(
-            (symbol_short!("widget_added"), owner),
+            (Symbol::new(&env, "widget_added"), editor),
             (dashboard_id, widget_id),
         );
 
@@ -525,27 +611,25 @@ impl MonitoringDashboardContract {
     /// Update widget position
     pub fn update_widget_position(
         env: Env,
-        owner: Address,
+        editor: Address,
         dashboard_id: u64,
         widget_id: u64,
         position: WidgetPosition,
     ) -> Result<(), ContractError> {
-        owner.require_auth();
+        editor.require_auth();
 
         if is_paused(&env) {
             return Err(ContractError::Paused);
         }
 
-        // Verify dashboard ownership
+        // Verify edit access
         let mut dashboard: Dashboard = env
             .storage()
             .persistent()
             .get(&(DASHBOARD_CONFIG, dashboard_id))
             .ok_or(ContractError::DashboardNotFound)?;
 
-        if dashboard.owner != owner {
-            return Err(ContractError::Unauthorized);
-        }
+        require_can_edit(&env, &dashboard, &editor)?;
 
         // Update widget position in layout
         dashboard.layout.widget_positions.set(widget_id, position);
@@ -557,13 +641,250 @@ impl MonitoringDashboardContract {
             .set(&(DASHBOARD_CONFIG, dashboard_id), &dashboard);
 
         env.events().publish(
-            (symbol_short!("widget_position_updated"), owner),
+            (Symbol::new(&env, "widget_position_updated"), editor),
             (dashboard_id, widget_id),
         );
 
         Ok(())
     }
 
+    /// Update widget refresh interval
+    pub fn update_widget_refresh_interval(
+        env: Env,
+        editor: Address,
+        dashboard_id: u64,
+        widget_id: u64,
+        refresh_interval: u64,
+    ) -> Result<(), ContractError> {
+        editor.require_auth();
+
+        if is_paused(&env) {
+            return Err(ContractError::Paused);
+        }
+
+        // Verify edit access
+        let mut dashboard: Dashboard = env
+            .storage()
+            .persistent()
+            .get(&(DASHBOARD_CONFIG, dashboard_id))
+            .ok_or(ContractError::DashboardNotFound)?;
+
+        require_can_edit(&env, &dashboard, &editor)?;
+
+        validate_refresh_interval(&env, refresh_interval)?;
+
+        let index = dashboard
+            .widgets
+            .iter()
+            .position(|w| w.widget_id == widget_id)
+            .ok_or(ContractError::WidgetNotFound)? as u32;
+
+        let mut widget = dashboard.widgets.get(index).unwrap();
+        widget.refresh_interval = refresh_interval;
+        widget.updated_at = env.ledger().timestamp();
+        dashboard.widgets.set(index, widget.clone());
+        dashboard.updated_at = env.ledger().timestamp();
+        dashboard.version += 1;
+
+        env.storage()
+            .persistent()
+            .set(&(DASHBOARD_CONFIG, dashboard_id), &dashboard);
+        env.storage()
+            .persistent()
+            .set(&(DASHBOARD_WIDGET, widget_id), &widget);
+
+        env.events().publish(
+            (symbol_short!("wid_ref"), editor),
+            (dashboard_id, widget_id, refresh_interval),
+        );
+
+        Ok(())
+    }
+
+    /// Set the widgets a widget derives its data from. Dependencies must be
+    /// other widgets on the same dashboard; cycles are reported when the
+    /// render order is computed.
+    pub fn set_widget_dependencies(
+        env: Env,
+        editor: Address,
+        dashboard_id: u64,
+        widget_id: u64,
+        depends_on: Vec<u64>,
+    ) -> Result<(), ContractError> {
+        editor.require_auth();
+
+        if is_paused(&env) {
+            return Err(ContractError::Paused);
+        }
+
+        // Verify edit access
+        let mut dashboard: Dashboard = env
+            .storage()
+            .persistent()
+            .get(&(DASHBOARD_CONFIG, dashboard_id))
+            .ok_or(ContractError::DashboardNotFound)?;
+
+        require_can_edit(&env, &dashboard, &editor)?;
+
+        let index = dashboard
+            .widgets
+            .iter()
+            .position(|w| w.widget_id == widget_id)
+            .ok_or(ContractError::WidgetNotFound)? as u32;
+
+        for dependency in depends_on.iter() {
+            if dependency == widget_id {
+                return Err(ContractError::LayoutInvalid);
+            }
+            if !dashboard.widgets.iter().any(|w| w.widget_id == dependency) {
+                return Err(ContractError::WidgetNotFound);
+            }
+        }
+
+        let mut widget = dashboard.widgets.get(index).unwrap();
+        widget.depends_on = depends_on.clone();
+        widget.updated_at = env.ledger().timestamp();
+        dashboard.widgets.set(index, widget.clone());
+        dashboard.updated_at = env.ledger().timestamp();
+        dashboard.version += 1;
+
+        env.storage()
+            .persistent()
+            .set(&(DASHBOARD_CONFIG, dashboard_id), &dashboard);
+        env.storage()
+            .persistent()
+            .set(&(DASHBOARD_WIDGET, widget_id), &widget);
+
+        env.events().publish(
+            (symbol_short!("wid_deps"), editor),
+            (dashboard_id, widget_id, depends_on),
+        );
+
+        Ok(())
+    }
+
+    /// Set dashboard auto-refresh interval (clamped to the configured bounds)
+    pub fn set_auto_refresh_interval(
+        env: Env,
+        editor: Address,
+        dashboard_id: u64,
+        auto_refresh_interval: u64,
+    ) -> Result<u64, ContractError> {
+        editor.require_auth();
+
+        if is_paused(&env) {
+            return Err(ContractError::Paused);
+        }
+
+        let mut dashboard: Dashboard = env
+            .storage()
+            .persistent()
+            .get(&(DASHBOARD_CONFIG, dashboard_id))
+            .ok_or(ContractError::DashboardNotFound)?;
+
+        require_can_edit(&env, &dashboard, &editor)?;
+
+        let interval = clamp_refresh_interval(&env, auto_refresh_interval);
+        dashboard.auto_refresh_interval = interval;
+        dashboard.updated_at = env.ledger().timestamp();
+        dashboard.version += 1;
+
+        env.storage()
+            .persistent()
+            .set(&(DASHBOARD_CONFIG, dashboard_id), &dashboard);
+
+        Ok(interval)
+    }
+
+    /// Configure refresh interval bounds (admin only)
+    pub fn set_refresh_bounds(
+        env: Env,
+        admin: Address,
+        min_interval: u64,
+        max_interval: u64,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().persistent().get(&ADMIN).ok_or(ContractError::NotInitialized)?;
+        if admin != stored_admin {
+            return Err(ContractError::Unauthorized);
+        }
+
+        if min_interval == 0 || min_interval > max_interval {
+            return Err(ContractError::InvalidInput);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&REFRESH_BOUNDS, &(min_interval, max_interval));
+
+        Ok(())
+    }
+
+    /// Set the theme new dashboards inherit (admin only). Existing
+    /// dashboards keep their current theme.
+    pub fn set_default_theme(
+        env: Env,
+        admin: Address,
+        theme: DashboardTheme,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().persistent().get(&ADMIN).ok_or(ContractError::NotInitialized)?;
+        if admin != stored_admin {
+            return Err(ContractError::Unauthorized);
+        }
+
+        env.storage().instance().set(&DEFAULT_THEME, &theme);
+
+        Ok(())
+    }
+
+    /// Get the theme new dashboards inherit
+    pub fn get_default_theme(env: Env) -> DashboardTheme {
+        get_default_theme(&env)
+    }
+
+    /// Grant `editor` edit access to a dashboard until `expires_at`
+    pub fn grant_temporary_edit(
+        env: Env,
+        owner: Address,
+        dashboard_id: u64,
+        editor: Address,
+        expires_at: u64,
+    ) -> Result<(), ContractError> {
+        owner.require_auth();
+
+        if is_paused(&env) {
+            return Err(ContractError::Paused);
+        }
+
+        let dashboard: Dashboard = env
+            .storage()
+            .persistent()
+            .get(&(DASHBOARD_CONFIG, dashboard_id))
+            .ok_or(ContractError::DashboardNotFound)?;
+
+        if dashboard.owner != owner {
+            return Err(ContractError::Unauthorized);
+        }
+
+        if expires_at <= env.ledger().timestamp() {
+            return Err(ContractError::InvalidInput);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(EDIT_GRANT, dashboard_id, editor.clone()), &expires_at);
+
+        env.events().publish(
+            (symbol_short!("edit_grnt"), owner),
+            (dashboard_id, editor, expires_at),
+        );
+
+        Ok(())
+    }
+
     /// Share dashboard
     pub fn share_dashboard(
         env: Env,
@@ -590,7 +911,7 @@ impl MonitoringDashboardContract {
             return Err(ContractError::Unauthorized);
         }
 
-        let share_token = generate_share_token(&env, dashboard_id, &owner);
+        let share_token = rng::random_bytes(&env, &owner);
         let expires_at = expires_in_days.map(|days| env.ledger().timestamp() + (days as u64 * 86400));
 
         dashboard.permissions.share_settings = ShareSettings {
@@ -643,8 +964,7 @@ impl MonitoringDashboardContract {
 
         let snapshot_id = get_next_dashboard_id(&env);
 
-        // In production, serialize dashboard state to bytes
-        let snapshot_data = Vec::new(&env);
+        let snapshot_data = dashboard.clone().to_xdr(&env);
 
         let snapshot = DashboardSnapshot {
             snapshot_id,
@@ -668,6 +988,49 @@ impl MonitoringDashboardContract {
         Ok(snapshot_id)
     }
 
+    /// Report what changed between two snapshots of a dashboard, as
+    /// (change kind, detail) pairs ordered from snapshot A to snapshot B
+    pub fn diff_snapshots(
+        env: Env,
+        snapshot_id_a: u64,
+        snapshot_id_b: u64,
+    ) -> Result<Vec<(Symbol, String)>, ContractError> {
+        let before = load_snapshot_dashboard(&env, snapshot_id_a)?;
+        let after = load_snapshot_dashboard(&env, snapshot_id_b)?;
+
+        if before.dashboard_id != after.dashboard_id {
+            return Err(ContractError::SnapshotInvalid);
+        }
+
+        let mut changes = Vec::new(&env);
+
+        if before.name != after.name {
+            changes.push_back((Symbol::new(&env, "name_changed"), after.name.clone()));
+        }
+
+        for widget in after.widgets.iter() {
+            if !before.widgets.iter().any(|w| w.widget_id == widget.widget_id) {
+                changes.push_back((Symbol::new(&env, "widget_added"), widget.title));
+            }
+        }
+
+        for widget in before.widgets.iter() {
+            if !after.widgets.iter().any(|w| w.widget_id == widget.widget_id) {
+                changes.push_back((Symbol::new(&env, "widget_removed"), widget.title));
+            }
+        }
+
+        if before.layout != after.layout {
+            changes.push_back((Symbol::new(&env, "layout_changed"), String::from_str(&env, "layout")));
+        }
+
+        if before.theme != after.theme {
+            changes.push_back((Symbol::new(&env, "theme_changed"), after.theme.name.clone()));
+        }
+
+        Ok(changes)
+    }
+
     /// Create dashboard template
     pub fn create_template(
         env: Env,
@@ -772,6 +1135,11 @@ impl MonitoringDashboardContract {
 
     // ===== View Functions =====
 
+    /// Health check for monitoring: (initialized, paused, schema version)
+    pub fn health(env: Env) -> (bool, bool, u32) {
+        (env.storage().persistent().has(&ADMIN), is_paused(&env), SCHEMA_VERSION)
+    }
+
     /// Get dashboard configuration
     pub fn get_dashboard(env: Env, dashboard_id: u64) -> Option<Dashboard> {
         env.storage().persistent().get(&(DASHBOARD_CONFIG, dashboard_id))
@@ -782,6 +1150,84 @@ impl MonitoringDashboardContract {
         env.storage().persistent().get(&(DASHBOARD_WIDGET, widget_id))
     }
 
+    /// Widget ids in an order where every widget follows the widgets it
+    /// depends on, keeping dashboard order among independent widgets.
+    /// Fails with `LayoutInvalid` when dependencies form a cycle.
+    pub fn get_widget_render_order(env: Env, dashboard_id: u64) -> Result<Vec<u64>, ContractError> {
+        let dashboard: Dashboard = env
+            .storage()
+            .persistent()
+            .get(&(DASHBOARD_CONFIG, dashboard_id))
+            .ok_or(ContractError::DashboardNotFound)?;
+
+        let mut on_dashboard: Map<u64, bool> = Map::new(&env);
+        for widget in dashboard.widgets.iter() {
+            on_dashboard.set(widget.widget_id, true);
+        }
+
+        // Repeatedly place every widget whose dependencies are already
+        // placed; a pass that places nothing means the rest form a cycle
+        let mut placed: Map<u64, bool> = Map::new(&env);
+        let mut order = Vec::new(&env);
+        while order.len() < dashboard.widgets.len() {
+            let mut progressed = false;
+            for widget in dashboard.widgets.iter() {
+                if placed.contains_key(widget.widget_id) {
+                    continue;
+                }
+                let ready = widget
+                    .depends_on
+                    .iter()
+                    .all(|dependency| placed.contains_key(dependency) || !on_dashboard.contains_key(dependency));
+                if ready {
+                    placed.set(widget.widget_id, true);
+                    order.push_back(widget.widget_id);
+                    progressed = true;
+                }
+            }
+            if !progressed {
+                return Err(ContractError::LayoutInvalid);
+            }
+        }
+
+        Ok(order)
+    }
+
+    /// Get widget data, serving the cached payload while it is within the
+    /// data source's cache TTL. The flag is true when the data was freshly fetched.
+    pub fn get_widget_data_cached(env: Env, widget_id: u64) -> Result<(Bytes, bool), ContractError> {
+        let widget: Widget = env
+            .storage()
+            .persistent()
+            .get(&(DASHBOARD_WIDGET, widget_id))
+            .ok_or(ContractError::WidgetNotFound)?;
+
+        let now = env.ledger().timestamp();
+        let cache_ttl = widget.data_source.cache_ttl;
+        let key = (WIDGET_DATA_CACHE, widget_id);
+
+        if cache_ttl > 0 {
+            if let Some(cached) = env.storage().temporary().get::<_, CachedWidgetData>(&key) {
+                if now.saturating_sub(cached.fetched_at) < cache_ttl {
+                    return Ok((cached.payload, false));
+                }
+            }
+        }
+
+        let payload = fetch_widget_data(&env, &widget);
+        if cache_ttl > 0 {
+            env.storage().temporary().set(
+                &key,
+                &CachedWidgetData {
+                    payload: payload.clone(),
+                    fetched_at: now,
+                },
+            );
+        }
+
+        Ok((payload, true))
+    }
+
     /// Get dashboard template
     pub fn get_template(env: Env, template_id: u64) -> Option<DashboardTemplate> {
         env.storage().persistent().get(&(DASHBOARD_TEMPLATE, template_id))
@@ -792,16 +1238,26 @@ impl MonitoringDashboardContract {
         env.storage().persistent().get(&(DASHBOARD_SNAPSHOT, snapshot_id))
     }
 
+    /// Get (min, max) refresh interval bounds
+    pub fn get_refresh_interval_bounds(env: Env) -> (u64, u64) {
+        get_refresh_bounds(&env)
+    }
+
     /// Get user preferences
     pub fn get_user_preferences(env: Env, user: Address) -> Option<UserPreferences> {
         env.storage().persistent().get(&(USER_PREFERENCES, user))
     }
 
-    /// List dashboards for user
-    pub fn list_user_dashboards(env: Env, user: Address) -> Vec<Dashboard> {
-        // In production, query dashboards where user is owner or has access
-        // For now, return empty vector
-        Vec::new(&env)
+    /// List up to `limit` dashboards owned by `user`, oldest first, skipping `offset`
+    pub fn list_user_dashboards(env: Env, user: Address, offset: u32, limit: u32) -> Vec<Dashboard> {
+        let ids: Vec<u64> = AppendIndex::new(&env, USER_DASHBOARDS, user).page(&env, offset, limit);
+        let mut dashboards = Vec::new(&env);
+        for dashboard_id in ids.iter() {
+            if let Some(dashboard) = env.storage().persistent().get(&(DASHBOARD_CONFIG, dashboard_id)) {
+                dashboards.push_back(dashboard);
+            }
+        }
+        dashboards
     }
 
     /// List public templates
@@ -829,3 +1285,6 @@ impl MonitoringDashboardContract {
         Ok(false)
     }
 }
+
+#[cfg(test)]
+mod test;
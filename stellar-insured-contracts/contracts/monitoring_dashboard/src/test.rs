@@ -0,0 +1,312 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::vec;
+
+fn setup(env: &Env) -> (MonitoringDashboardContractClient<'_>, Address) {
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, MonitoringDashboardContract);
+    let client = MonitoringDashboardContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+
+    client.initialize(&admin);
+
+    (client, admin)
+}
+
+fn create_dashboard(env: &Env, client: &MonitoringDashboardContractClient, owner: &Address) -> u64 {
+    client.create_dashboard(
+        owner,
+        &String::from_str(env, "Ops"),
+        &String::from_str(env, "Operations overview"),
+        &Symbol::new(env, "grid"),
+        &4,
+        &4,
+        &None,
+    )
+}
+
+fn data_source(env: &Env) -> DataSource {
+    DataSource {
+        source_type: Symbol::new(env, "contract"),
+        contract_address: None,
+        metric_name: Some(Symbol::new(env, "gas_used")),
+        query_params: Map::new(env),
+        aggregation: None,
+        filters: Map::new(env),
+        cache_ttl: 30,
+    }
+}
+
+fn visualization(env: &Env) -> VisualizationSettings {
+    VisualizationSettings {
+        chart_type: Symbol::new(env, "line"),
+        color_scheme: String::from_str(env, "default"),
+        axis_settings: Map::new(env),
+        legend_settings: Map::new(env),
+        animation: false,
+        interactive: true,
+    }
+}
+
+fn add_widget(
+    env: &Env,
+    client: &MonitoringDashboardContractClient,
+    owner: &Address,
+    dashboard_id: u64,
+    refresh_interval: u64,
+) -> Result<u64, ContractError> {
+    client
+        .try_add_widget(
+            owner,
+            &dashboard_id,
+            &Symbol::new(env, "chart"),
+            &String::from_str(env, "Gas"),
+            &Map::new(env),
+            &data_source(env),
+            &visualization(env),
+            &refresh_interval,
+        )
+        .map(|r| r.unwrap())
+        .map_err(|e| e.unwrap())
+}
+
+#[test]
+fn test_widget_refresh_interval_bounds() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let dashboard_id = create_dashboard(&env, &client, &owner);
+
+    assert_eq!(add_widget(&env, &client, &owner, dashboard_id, 1), Err(ContractError::InvalidInput));
+    assert_eq!(add_widget(&env, &client, &owner, dashboard_id, 0), Err(ContractError::InvalidInput));
+    assert_eq!(add_widget(&env, &client, &owner, dashboard_id, 86_401), Err(ContractError::InvalidInput));
+
+    let widget_id = add_widget(&env, &client, &owner, dashboard_id, 60).unwrap();
+
+    let result = client.try_update_widget_refresh_interval(&owner, &dashboard_id, &widget_id, &0);
+    assert_eq!(result, Err(Ok(ContractError::InvalidInput)));
+
+    client.update_widget_refresh_interval(&owner, &dashboard_id, &widget_id, &120);
+    assert_eq!(client.get_widget(&widget_id).unwrap().refresh_interval, 120);
+}
+
+#[test]
+fn test_auto_refresh_interval_is_clamped() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let dashboard_id = create_dashboard(&env, &client, &owner);
+
+    assert_eq!(client.set_auto_refresh_interval(&owner, &dashboard_id, &1), 5);
+    assert_eq!(client.set_auto_refresh_interval(&owner, &dashboard_id, &1_000_000), 86_400);
+    assert_eq!(client.get_dashboard(&dashboard_id).unwrap().auto_refresh_interval, 86_400);
+}
+
+#[test]
+fn test_diff_snapshots_reports_added_widget() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let dashboard_id = create_dashboard(&env, &client, &owner);
+
+    let before = client.create_snapshot(&owner, &dashboard_id, &String::from_str(&env, "before"), &false);
+    add_widget(&env, &client, &owner, dashboard_id, 60).unwrap();
+    let after = client.create_snapshot(&owner, &dashboard_id, &String::from_str(&env, "after"), &false);
+
+    let diff = client.diff_snapshots(&before, &after);
+    assert_eq!(diff.len(), 1);
+    assert_eq!(
+        diff.get(0).unwrap(),
+        (Symbol::new(&env, "widget_added"), String::from_str(&env, "Gas"))
+    );
+}
+
+#[test]
+fn test_widget_data_served_from_cache_within_ttl() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let dashboard_id = create_dashboard(&env, &client, &owner);
+    let widget_id = add_widget(&env, &client, &owner, dashboard_id, 60).unwrap();
+
+    let (first, fresh) = client.get_widget_data_cached(&widget_id);
+    assert!(fresh);
+
+    env.ledger().with_mut(|li| li.timestamp += 10);
+    let (second, fresh) = client.get_widget_data_cached(&widget_id);
+    assert!(!fresh);
+    assert_eq!(second, first);
+
+    // Past the 30s TTL the data is fetched again
+    env.ledger().with_mut(|li| li.timestamp += 30);
+    let (third, fresh) = client.get_widget_data_cached(&widget_id);
+    assert!(fresh);
+    assert_ne!(third, first);
+}
+
+#[test]
+fn test_temporary_edit_grant_expires() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let contractor = Address::generate(&env);
+    let dashboard_id = create_dashboard(&env, &client, &owner);
+
+    assert_eq!(add_widget(&env, &client, &contractor, dashboard_id, 60), Err(ContractError::Unauthorized));
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let result = client.try_grant_temporary_edit(&owner, &dashboard_id, &contractor, &1_000);
+    assert_eq!(result, Err(Ok(ContractError::InvalidInput)));
+    client.grant_temporary_edit(&owner, &dashboard_id, &contractor, &2_000);
+
+    let widget_id = add_widget(&env, &client, &contractor, dashboard_id, 60).unwrap();
+    client.update_widget_refresh_interval(&contractor, &dashboard_id, &widget_id, &120);
+    assert_eq!(client.get_widget(&widget_id).unwrap().refresh_interval, 120);
+
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    let result = client.try_update_widget_refresh_interval(&contractor, &dashboard_id, &widget_id, &300);
+    assert_eq!(result, Err(Ok(ContractError::Unauthorized)));
+
+    // The owner keeps edit access regardless of grants
+    client.update_widget_refresh_interval(&owner, &dashboard_id, &widget_id, &300);
+}
+
+#[test]
+fn test_create_dashboard_is_idempotent() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let key = Some(BytesN::from_array(&env, &[7u8; 32]));
+
+    let create = |key: &Option<BytesN<32>>| {
+        client.create_dashboard(
+            &owner,
+            &String::from_str(&env, "Ops"),
+            &String::from_str(&env, "Operations overview"),
+            &Symbol::new(&env, "grid"),
+            &4,
+            &4,
+            key,
+        )
+    };
+
+    let first = create(&key);
+    let retried = create(&key);
+    assert_eq!(first, retried);
+    assert!(client.get_dashboard(&(first + 1)).is_none());
+
+    // A fresh key, or none at all, still creates a new dashboard
+    assert_eq!(create(&Some(BytesN::from_array(&env, &[8u8; 32]))), first + 1);
+    assert_eq!(create(&None), first + 2);
+}
+
+#[test]
+fn test_health_reflects_pause_state() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    assert_eq!(client.health(), (true, false, SCHEMA_VERSION));
+
+    client.set_paused(&admin, &true);
+    assert_eq!(client.health(), (true, true, SCHEMA_VERSION));
+
+    client.set_paused(&admin, &false);
+    assert_eq!(client.health(), (true, false, SCHEMA_VERSION));
+}
+
+#[test]
+fn test_new_dashboards_inherit_default_theme() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let create = || {
+        client.create_dashboard(
+            &owner,
+            &String::from_str(&env, "Ops"),
+            &String::from_str(&env, "Operations overview"),
+            &Symbol::new(&env, "grid"),
+            &4,
+            &4,
+            &None,
+        )
+    };
+
+    let existing = create();
+    let builtin = client.get_default_theme();
+    assert_eq!(builtin.font_family, String::from_str(&env, "Arial"));
+
+    let brand = DashboardTheme {
+        name: String::from_str(&env, "acme"),
+        primary_color: String::from_str(&env, "#ff5500"),
+        secondary_color: String::from_str(&env, "#222222"),
+        background_color: String::from_str(&env, "#fafafa"),
+        text_color: String::from_str(&env, "#111111"),
+        font_family: String::from_str(&env, "Inter"),
+        border_radius: 8,
+        shadow: false,
+    };
+    assert_eq!(
+        client.try_set_default_theme(&owner, &brand),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    client.set_default_theme(&admin, &brand);
+
+    let branded = create();
+    assert_eq!(client.get_dashboard(&branded).unwrap().theme, brand);
+    assert_eq!(client.get_dashboard(&existing).unwrap().theme, builtin);
+}
+
+#[test]
+fn test_widget_render_order_follows_dependencies() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let dashboard_id = create_dashboard(&env, &client, &owner);
+
+    let summary = add_widget(&env, &client, &owner, dashboard_id, 60).unwrap();
+    let gas_chart = add_widget(&env, &client, &owner, dashboard_id, 60).unwrap();
+    let error_chart = add_widget(&env, &client, &owner, dashboard_id, 60).unwrap();
+    let raw_feed = add_widget(&env, &client, &owner, dashboard_id, 60).unwrap();
+
+    // summary <- gas_chart, error_chart; both charts <- raw_feed
+    client.set_widget_dependencies(&owner, &dashboard_id, &summary, &vec![&env, gas_chart, error_chart]);
+    client.set_widget_dependencies(&owner, &dashboard_id, &gas_chart, &vec![&env, raw_feed]);
+    client.set_widget_dependencies(&owner, &dashboard_id, &error_chart, &vec![&env, raw_feed]);
+
+    assert_eq!(
+        client.get_widget_render_order(&dashboard_id),
+        vec![&env, raw_feed, gas_chart, error_chart, summary]
+    );
+
+    // Closing the loop raw_feed -> summary is a cycle
+    client.set_widget_dependencies(&owner, &dashboard_id, &raw_feed, &vec![&env, summary]);
+    assert_eq!(
+        client.try_get_widget_render_order(&dashboard_id),
+        Err(Ok(ContractError::LayoutInvalid))
+    );
+}
+
+#[test]
+fn test_list_user_dashboards_pages_owned_dashboards() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    let ids = [0; 3].map(|_| create_dashboard(&env, &client, &owner));
+    create_dashboard(&env, &client, &other);
+
+    let first_page = client.list_user_dashboards(&owner, &0, &2);
+    assert_eq!(first_page.len(), 2);
+    assert_eq!(first_page.get(0).unwrap().dashboard_id, ids[0]);
+    assert_eq!(first_page.get(1).unwrap().dashboard_id, ids[1]);
+
+    let last_page = client.list_user_dashboards(&owner, &2, &2);
+    assert_eq!(last_page.len(), 1);
+    assert_eq!(last_page.get(0).unwrap().dashboard_id, ids[2]);
+
+    assert_eq!(client.list_user_dashboards(&owner, &3, &2).len(), 0);
+    assert_eq!(client.list_user_dashboards(&other, &0, &10).len(), 1);
+}
@@ -15,12 +15,15 @@ const ORACLE_DATA: Symbol = symbol_short!("ORA_DATA");
 const ORACLE_HISTORY: Symbol = symbol_short!("ORA_HIST");
 const SUBMISSIONS: Symbol = symbol_short!("SUBS");
 const THRESHOLDS: Symbol = symbol_short!("THRESH");
+const SOURCE_WEIGHT: Symbol = symbol_short!("SRC_WGT");
+const MIN_WEIGHT: Symbol = symbol_short!("MIN_WGT");
 
 // Default thresholds for oracle validation
 const DEFAULT_MIN_SUBMISSIONS: u32 = 3;
 const DEFAULT_MAJORITY_THRESHOLD: u32 = 66; // 66% (2 out of 3)
 const DEFAULT_OUTLIER_DEVIATION: i128 = 15; // 15% deviation threshold
 const DEFAULT_STALENESS_THRESHOLD_SECONDS: u64 = 3600; // 1 hour
+const MIN_WEIGHT_FOR_CONSENSUS: u32 = 2; // 2 agreeing sources at default weight
 
 // ============================================================================
 // Error Handling
@@ -41,6 +44,7 @@ pub enum OracleError {
     ConsensusNotReached = 10,
     InvalidThreshold = 11,
     DuplicateSubmission = 12,
+    InsufficientWeight = 13,
 }
 
 // ============================================================================
@@ -161,6 +165,18 @@ fn set_thresholds(env: &Env, thresholds: &ValidationThreshold) {
     env.storage().persistent().set(&THRESHOLDS, thresholds);
 }
 
+/// Consensus weight governance registered for a source, 0 if unregistered
+fn get_source_weight(env: &Env, oracle: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&(SOURCE_WEIGHT, oracle.clone()))
+        .unwrap_or(0)
+}
+
+fn get_min_consensus_weight(env: &Env) -> u32 {
+    env.storage().persistent().get(&MIN_WEIGHT).unwrap_or(MIN_WEIGHT_FOR_CONSENSUS)
+}
+
 /// Calculate median of values
 fn calculate_median(values: &Vec<i128>) -> i128 {
     if values.is_empty() {
@@ -306,16 +322,59 @@ impl OracleContract {
         Ok(get_thresholds(&env))
     }
 
-    /// Submit oracle data for a specific data point
+    /// Set the consensus weight of an oracle source (governance only)
+    pub fn set_source_weight(env: Env, oracle: Address, weight: u32) -> Result<(), OracleError> {
+        let admin = require_admin(&env)?;
+        admin.require_auth();
+
+        if weight == 0 {
+            return Err(OracleError::InvalidInput);
+        }
+
+        env.storage().persistent().set(&(SOURCE_WEIGHT, oracle), &weight);
+        Ok(())
+    }
+
+    /// Get the consensus weight of an oracle source, 0 if it isn't registered
+    pub fn get_source_weight(env: Env, oracle: Address) -> u32 {
+        get_source_weight(&env, &oracle)
+    }
+
+    /// Set the total weight agreeing sources must reach for consensus
+    pub fn set_min_consensus_weight(env: Env, min_weight: u32) -> Result<(), OracleError> {
+        let admin = require_admin(&env)?;
+        admin.require_auth();
+
+        if min_weight == 0 {
+            return Err(OracleError::InvalidThreshold);
+        }
+
+        env.storage().persistent().set(&MIN_WEIGHT, &min_weight);
+        Ok(())
+    }
+
+    /// Get the total weight agreeing sources must reach for consensus
+    pub fn get_min_consensus_weight(env: Env) -> u32 {
+        get_min_consensus_weight(&env)
+    }
+
+    /// Submit oracle data for a specific data point. Only sources
+    /// registered with `set_source_weight` may submit.
     /// Returns true if consensus is reached immediately
-    pub fn submit_oracle_data(env: Env, data_id: u64, value: i128) -> Result<bool, OracleError> {
+    pub fn submit_oracle_data(
+        env: Env,
+        oracle: Address,
+        data_id: u64,
+        value: i128,
+    ) -> Result<bool, OracleError> {
         if is_paused(&env) {
             return Err(OracleError::Paused);
         }
 
-        // See note in `require_admin` about SDK API differences.
-        // For now, use current contract address as the submitting oracle.
-        let oracle = env.current_contract_address();
+        oracle.require_auth();
+        if get_source_weight(&env, &oracle) == 0 {
+            return Err(OracleError::Unauthorized);
+        }
         let current_time = env.ledger().timestamp();
 
         let submissions_key = (SUBMISSIONS, data_id);
@@ -349,6 +408,7 @@ impl OracleContract {
         match OracleContract.try_resolve_oracle_data(&env, data_id) {
             Ok(_) => Ok(true),
             Err(OracleError::InsufficientSubmissions) => Ok(false),
+            Err(OracleError::InsufficientWeight) => Ok(false),
             Err(e) => Err(e),
         }
     }
@@ -393,10 +453,16 @@ impl OracleContract {
         // Filter out outliers and calculate consensus
         let mut valid_values: Vec<i128> = Vec::new(&env);
         let mut rejected_count = 0u32;
+        let mut total_weight = 0u32;
+        let mut agreeing_weight = 0u32;
 
         for i in 0..values.len() {
+            let weight = get_source_weight(env, &submissions.get(i).unwrap().oracle);
+            total_weight = total_weight.saturating_add(weight);
+
             if !outlier_flags.get(i).unwrap() {
                 valid_values.push_back(values.get(i).unwrap());
+                agreeing_weight = agreeing_weight.saturating_add(weight);
             } else {
                 rejected_count += 1;
             }
@@ -404,8 +470,13 @@ impl OracleContract {
 
         let valid_count = valid_values.len() as u32;
 
-        // Verify consensus threshold is met
-        let consensus_percentage = (valid_count * 100) / submission_count;
+        // Agreeing sources must carry enough combined weight
+        if agreeing_weight < get_min_consensus_weight(env) {
+            return Err(OracleError::InsufficientWeight);
+        }
+
+        // Verify consensus threshold is met, weighting each source
+        let consensus_percentage = (agreeing_weight * 100) / total_weight;
 
         if consensus_percentage < thresholds.majority_threshold_percent {
             return Err(OracleError::ConsensusNotReached);
@@ -902,3 +973,65 @@ mod oracle_consensus_tests {
         assert!(submit_result2.is_ok());
     }
 }
+
+#[cfg(test)]
+mod source_weight_tests {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    fn setup(env: &Env) -> OracleContractClient<'_> {
+        env.mock_all_auths();
+
+        let contract_id = env.register_contract(None, OracleContract);
+        let client = OracleContractClient::new(env, &contract_id);
+        client.initialize(&Address::generate(env));
+        client.set_thresholds(&2, &66, &15, &3600);
+        client.set_min_consensus_weight(&10);
+
+        client
+    }
+
+    #[test]
+    fn test_low_weight_sources_fail_weight_quorum() {
+        let env = Env::default();
+        let client = setup(&env);
+
+        for value in [100i128, 101, 102] {
+            let source = Address::generate(&env);
+            client.set_source_weight(&source, &1);
+            assert!(!client.submit_oracle_data(&source, &1, &value));
+        }
+
+        let result = client.try_resolve_oracle_data(&1);
+        assert_eq!(result, Err(Ok(OracleError::InsufficientWeight)));
+    }
+
+    #[test]
+    fn test_unregistered_source_cannot_submit() {
+        let env = Env::default();
+        let client = setup(&env);
+
+        let outsider = Address::generate(&env);
+        assert_eq!(client.get_source_weight(&outsider), 0);
+        let result = client.try_submit_oracle_data(&outsider, &1, &100);
+        assert_eq!(result, Err(Ok(OracleError::Unauthorized)));
+    }
+
+    #[test]
+    fn test_high_weight_sources_reach_consensus() {
+        let env = Env::default();
+        let client = setup(&env);
+
+        let first = Address::generate(&env);
+        let second = Address::generate(&env);
+        client.set_source_weight(&first, &5);
+        client.set_source_weight(&second, &5);
+
+        assert!(!client.submit_oracle_data(&first, &1, &100));
+        assert!(client.submit_oracle_data(&second, &1, &102));
+
+        let data = client.get_oracle_data(&1);
+        assert_eq!(data.consensus_value, 101);
+        assert_eq!(data.consensus_percentage, 100);
+    }
+}
@@ -4,7 +4,7 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype,
+    contract, contractclient, contractimpl, contracttype,
     Address, Env, Map, Symbol, Vec,
     log,
 };
@@ -17,8 +17,17 @@ const MAX_PRICE_DEVIATION_BPS: i128 = 500;   // 5 % max deviation between source
 const MIN_SOURCES_FOR_CONSENSUS: u32  = 3;    // Minimum oracle sources required
 const STALENESS_THRESHOLD_SECS: u64   = 300;  // 5 minutes
 const ANOMALY_MULTIPLIER_BPS: i128    = 2000; // 20 % jump = anomaly
-const HISTORY_MAX_ENTRIES: u32        = 100;
-const QUALITY_DECAY_PER_MISS: u32     = 10;   // Quality score penalty per missing round
+const HISTORY_MAX_ENTRIES: u32        = 100;  // Default history cap per asset
+const HISTORY_ABSOLUTE_MAX: u32       = 1000; // Upper bound for any configured cap
+const DEFAULT_QUALITY_DECAY: u32      = 10;   // Quality score penalty per stale evaluation
+const DEFAULT_QUALITY_RECOVERY: u32   = 5;    // Quality score regained per accurate evaluation
+const MAX_QUALITY_SCORE: u32          = 100;  // Score a newly added source starts at
+const MEDIAN_GROUP_SIZE: u32          = 3;    // Sources per subset in median-of-medians
+const SCHEMA_VERSION: u32             = 1;    // Storage schema written by this build
+const MAX_BATCH_SUBMISSIONS: u32      = 20;   // Assets a source may report in one batch
+const CORRELATION_JUMP_BPS: i128      = 1000; // 10 % move checked against correlated assets
+const CORRELATION_WINDOW_SECS: u64    = 300;  // How recent a peer's move must be to count
+const MAX_SUBSCRIBERS_PER_ASSET: u32  = 50;   // Bounds the PRICE_UPDATE events per consensus
 
 // ─────────────────────────────────────────────
 // Storage Types
@@ -27,16 +36,46 @@ const QUALITY_DECAY_PER_MISS: u32     = 10;   // Quality score penalty per missi
 #[contracttype]
 pub enum OracleKey {
     SourceList,                   // Vec<Address> of approved oracle sources
-    SourcePrice(Address),         // Latest price submission per source
+    SourcePrice(Address, Symbol), // Latest price submission per source and asset
+    LastSubmission(Address),      // Timestamp of a source's latest submission, any asset
     AggregatedPrice(Symbol),      // Consensus price per asset symbol
+    AggregatedConfidence(Symbol), // Confidence (0–100) of the consensus price
     PriceHistory(Symbol),         // Vec<PricePoint> for asset
     FallbackPrice(Symbol),        // Admin-set fallback price
     QualityScore(Address),        // Per-source reliability score (0–100)
     AnomalyFlag(Symbol),          // Whether current price is flagged
+    HistoryCap(Symbol),           // Governance-set history cap per asset
+    AggregationMode(Symbol),      // Consensus aggregation mode per asset
+    LastRound(Address),           // Last accepted submission round per source
+    MinConfidence,                // Submissions below this are left out of consensus
+    QualityPolicy,                // Governance-set quality decay, recovery and floor
+    Subscribers(Symbol),          // Vec<Address> notified on consensus updates for asset
+    AlertingContract,             // Opt-in alerting contract told of deviations and anomalies
+    CorrelationGroup(Symbol),     // Vec<Symbol> of assets expected to move together
+    AssetGroup(Symbol),           // Correlation group an asset belongs to
+    LastMove(Symbol),             // (signed move in BPS, timestamp) of latest consensus
+    CorrelationFlag(Symbol),      // Whether the asset's latest move was isolated from its group
+    RecoveredRound(Address, Symbol), // Last own round a source's quality recovered for, per asset
+    DecayedRound(Address, Symbol),   // Last consensus round a stale source was penalised for, per asset
     Governance,
     Paused,
 }
 
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AggregationMode {
+    Median,          // Median over all fresh submissions
+    MedianOfMedians, // Median of the medians of source subsets
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QualityPolicy {
+    pub decay_per_miss: u32,     // Penalty each time a source's submission is found stale
+    pub recovery_per_round: u32, // Gain each time a fresh submission lands near consensus
+    pub floor: u32,              // Score a source never decays below
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct PriceSubmission {
@@ -44,6 +83,7 @@ pub struct PriceSubmission {
     pub price:     i128,   // Price in smallest unit (e.g. 7 decimal places)
     pub timestamp: u64,
     pub confidence: u32,   // Source self-reported confidence 0–100
+    pub round_id:  u64,    // Strictly increasing per source
 }
 
 #[contracttype]
@@ -53,6 +93,7 @@ pub struct PricePoint {
     pub timestamp: u64,
     pub sources:   u32,   // How many sources agreed
     pub anomaly:   bool,
+    pub confidence: u32,  // Quality-weighted source confidence 0–100
 }
 
 #[contracttype]
@@ -63,6 +104,7 @@ pub struct ConsensusResult {
     pub deviation:   i128,  // Max deviation from median in BPS
     pub is_valid:    bool,
     pub timestamp:   u64,
+    pub aggregate_confidence: u32, // Quality-weighted source confidence 0–100
 }
 
 // ─────────────────────────────────────────────
@@ -96,7 +138,7 @@ impl OracleValidation {
             .unwrap_or(Vec::new(&env));
         list.push_back(source.clone());
         env.storage().instance().set(&OracleKey::SourceList, &list);
-        env.storage().instance().set(&OracleKey::QualityScore(source), &100u32);
+        env.storage().instance().set(&OracleKey::QualityScore(source), &MAX_QUALITY_SCORE);
     }
 
     pub fn remove_source(env: Env, caller: Address, source: Address) {
@@ -116,20 +158,141 @@ impl OracleValidation {
         env.storage().instance().set(&OracleKey::SourceList, &new_list);
     }
 
+    /// Set the minimum self-reported confidence (0–100) a submission needs
+    /// to take part in consensus. Lower submissions are stored but ignored.
+    pub fn set_min_confidence(env: Env, caller: Address, min_confidence: u32) {
+        caller.require_auth();
+        Self::require_governance(&env, &caller);
+        if min_confidence > 100 {
+            panic!("confidence must be 0–100");
+        }
+        env.storage().instance().set(&OracleKey::MinConfidence, &min_confidence);
+    }
+
+    pub fn get_min_confidence(env: Env) -> u32 {
+        env.storage().instance().get(&OracleKey::MinConfidence).unwrap_or(0)
+    }
+
+    /// Configure how source quality scores move: `decay_per_miss` is taken
+    /// off whenever a source's submission is stale at consensus time, and
+    /// `recovery_per_round` is added back, up to 100, whenever its fresh
+    /// submission lands within the deviation bound of a valid consensus.
+    /// Scores never decay below `floor`.
+    pub fn set_quality_policy(
+        env: Env,
+        caller: Address,
+        decay_per_miss: u32,
+        recovery_per_round: u32,
+        floor: u32,
+    ) {
+        caller.require_auth();
+        Self::require_governance(&env, &caller);
+        if decay_per_miss > MAX_QUALITY_SCORE
+            || recovery_per_round > MAX_QUALITY_SCORE
+            || floor > MAX_QUALITY_SCORE
+        {
+            panic!("quality policy out of range");
+        }
+        let policy = QualityPolicy { decay_per_miss, recovery_per_round, floor };
+        env.storage().instance().set(&OracleKey::QualityPolicy, &policy);
+    }
+
+    pub fn get_quality_policy(env: Env) -> QualityPolicy {
+        Self::quality_policy(&env)
+    }
+
+    fn quality_policy(env: &Env) -> QualityPolicy {
+        env.storage().instance()
+            .get(&OracleKey::QualityPolicy)
+            .unwrap_or(QualityPolicy {
+                decay_per_miss:     DEFAULT_QUALITY_DECAY,
+                recovery_per_round: DEFAULT_QUALITY_RECOVERY,
+                floor:              0,
+            })
+    }
+
     // ── Price Submission ─────────────────────
 
     /// Called by each oracle source with its latest price for an asset.
+    /// `round_id` must be greater than the source's last accepted round,
+    /// so replayed or out-of-order submissions are rejected.
     pub fn submit_price(
         env:     Env,
         source:  Address,
         asset:   Symbol,
         price:   i128,
         confidence: u32,
+        round_id: u64,
+    ) {
+        source.require_auth();
+        Self::require_not_paused(&env);
+        Self::require_approved_source(&env, &source);
+        Self::advance_round(&env, &source, round_id);
+
+        Self::record_submission(&env, &source, &asset, price, confidence, round_id);
+
+        // Attempt to run consensus immediately
+        let result = Self::run_consensus_internal(&env, &asset);
+        if result.is_valid {
+            Self::store_consensus(&env, &asset, &result);
+        }
+    }
+
+    /// Submit prices for several assets as one round. Entries are
+    /// (asset, price, confidence); consensus runs once per affected asset
+    /// after every entry has been recorded.
+    pub fn submit_prices_batch(
+        env:     Env,
+        source:  Address,
+        entries: Vec<(Symbol, i128, u32)>,
+        round_id: u64,
     ) {
         source.require_auth();
         Self::require_not_paused(&env);
         Self::require_approved_source(&env, &source);
 
+        if entries.is_empty() || entries.len() > MAX_BATCH_SUBMISSIONS {
+            panic!("batch size out of range");
+        }
+        Self::advance_round(&env, &source, round_id);
+
+        let mut assets = Vec::<Symbol>::new(&env);
+        for (asset, price, confidence) in entries.iter() {
+            Self::record_submission(&env, &source, &asset, price, confidence, round_id);
+            if !assets.contains(&asset) {
+                assets.push_back(asset);
+            }
+        }
+
+        for asset in assets.iter() {
+            let result = Self::run_consensus_internal(&env, &asset);
+            if result.is_valid {
+                Self::store_consensus(&env, &asset, &result);
+            }
+        }
+    }
+
+    /// Reject rounds at or below the source's last accepted round, then
+    /// record `round_id` as the latest
+    fn advance_round(env: &Env, source: &Address, round_id: u64) {
+        let last_round: Option<u64> = env.storage().persistent()
+            .get(&OracleKey::LastRound(source.clone()));
+        if let Some(last) = last_round {
+            if round_id <= last {
+                panic!("stale or replayed round");
+            }
+        }
+        env.storage().persistent().set(&OracleKey::LastRound(source.clone()), &round_id);
+    }
+
+    fn record_submission(
+        env:     &Env,
+        source:  &Address,
+        asset:   &Symbol,
+        price:   i128,
+        confidence: u32,
+        round_id: u64,
+    ) {
         if price <= 0 {
             panic!("price must be positive");
         }
@@ -137,19 +300,16 @@ impl OracleValidation {
             panic!("confidence must be 0–100");
         }
 
+        let now = env.ledger().timestamp();
         let sub = PriceSubmission {
             source:     source.clone(),
             price,
-            timestamp:  env.ledger().timestamp(),
+            timestamp:  now,
             confidence,
+            round_id,
         };
-        env.storage().temporary().set(&OracleKey::SourcePrice(source.clone()), &sub);
-
-        // Attempt to run consensus immediately
-        let result = Self::run_consensus_internal(&env, &asset);
-        if result.is_valid {
-            Self::store_consensus(&env, &asset, &result);
-        }
+        env.storage().temporary().set(&OracleKey::SourcePrice(source.clone(), asset.clone()), &sub);
+        env.storage().persistent().set(&OracleKey::LastSubmission(source.clone()), &now);
     }
 
     // ── Consensus Engine ─────────────────────
@@ -169,30 +329,59 @@ impl OracleValidation {
             .unwrap_or(Vec::new(env));
 
         let now = env.ledger().timestamp();
+        let min_confidence: u32 = env.storage().instance()
+            .get(&OracleKey::MinConfidence)
+            .unwrap_or(0);
+        let policy = Self::quality_policy(env);
         let mut prices = Vec::<i128>::new(env);
+        let mut fresh_sources = Vec::<(Address, u64)>::new(env);
+        let mut stale_sources = Vec::<Address>::new(env);
+        let mut current_round: u64 = 0;
+        let mut weighted_confidence: u64 = 0;
+        let mut total_quality: u64 = 0;
 
         // Collect fresh, non-stale submissions
         for i in 0..sources.len() {
             let source = sources.get(i).unwrap();
             if let Some(sub) = env.storage().temporary()
-                .get::<OracleKey, PriceSubmission>(&OracleKey::SourcePrice(source.clone()))
+                .get::<OracleKey, PriceSubmission>(&OracleKey::SourcePrice(source.clone(), asset.clone()))
             {
+                if sub.confidence < min_confidence {
+                    continue;
+                }
                 if now.saturating_sub(sub.timestamp) <= STALENESS_THRESHOLD_SECS {
                     prices.push_back(sub.price);
-                } else {
-                    // Penalise stale source quality
-                    let score: u32 = env.storage().instance()
+                    fresh_sources.push_back((source.clone(), sub.round_id));
+                    current_round = current_round.max(sub.round_id);
+                    let quality: u32 = env.storage().instance()
                         .get(&OracleKey::QualityScore(source.clone()))
                         .unwrap_or(50);
-                    env.storage().instance().set(
-                        &OracleKey::QualityScore(source),
-                        &score.saturating_sub(QUALITY_DECAY_PER_MISS),
-                    );
+                    weighted_confidence += sub.confidence as u64 * quality as u64;
+                    total_quality += quality as u64;
+                } else {
+                    stale_sources.push_back(source);
                 }
             }
         }
 
+        // Penalise stale source quality, down to the floor, once for each
+        // round the fresh sources report that the stale source misses
+        for source in stale_sources.iter() {
+            let key = OracleKey::DecayedRound(source.clone(), asset.clone());
+            let decayed_round: u64 = env.storage().persistent().get(&key).unwrap_or(0);
+            if current_round <= decayed_round {
+                continue;
+            }
+            env.storage().persistent().set(&key, &current_round);
+            let score: u32 = env.storage().instance()
+                .get(&OracleKey::QualityScore(source.clone()))
+                .unwrap_or(50);
+            let decayed = score.saturating_sub(policy.decay_per_miss).max(policy.floor.min(score));
+            env.storage().instance().set(&OracleKey::QualityScore(source), &decayed);
+        }
+
         let count = prices.len();
+        let aggregate_confidence = weighted_confidence.checked_div(total_quality).unwrap_or(0) as u32;
         if count < MIN_SOURCES_FOR_CONSENSUS {
             return ConsensusResult {
                 price: 0,
@@ -200,43 +389,84 @@ impl OracleValidation {
                 deviation: 0,
                 is_valid: false,
                 timestamp: now,
+                aggregate_confidence,
             };
         }
 
         // Sort prices (insertion sort — small N, no_std)
         let sorted = Self::sort_prices(env, &prices);
-        let median  = Self::median(&sorted);
+        let median  = match Self::aggregation_mode(env, asset) {
+            AggregationMode::Median          => Self::median(&sorted),
+            AggregationMode::MedianOfMedians => Self::median_of_medians(env, &prices),
+        };
         let max_dev = Self::max_deviation_bps(&sorted, median);
 
         if max_dev > MAX_PRICE_DEVIATION_BPS {
             log!(env, "consensus rejected: deviation {} bps", max_dev);
+            Self::raise_alert(env, Symbol::new(env, "price_deviation"), asset, max_dev);
             return ConsensusResult {
                 price: median,
                 sources_used: count,
                 deviation: max_dev,
                 is_valid: false,
                 timestamp: now,
+                aggregate_confidence,
             };
         }
 
+        // Every fresh price is within the deviation bound here, so each
+        // contributing source recovers some quality, once per round it reports
+        for (source, round_id) in fresh_sources.iter() {
+            let key = OracleKey::RecoveredRound(source.clone(), asset.clone());
+            let recovered_round: u64 = env.storage().persistent().get(&key).unwrap_or(0);
+            if round_id <= recovered_round {
+                continue;
+            }
+            env.storage().persistent().set(&key, &round_id);
+            let score: u32 = env.storage().instance()
+                .get(&OracleKey::QualityScore(source.clone()))
+                .unwrap_or(50);
+            let recovered = (score + policy.recovery_per_round).min(MAX_QUALITY_SCORE);
+            env.storage().instance().set(&OracleKey::QualityScore(source), &recovered);
+        }
+
         ConsensusResult {
             price: median,
             sources_used: count,
             deviation: max_dev,
             is_valid: true,
             timestamp: now,
+            aggregate_confidence,
         }
     }
 
+    fn aggregation_mode(env: &Env, asset: &Symbol) -> AggregationMode {
+        env.storage().persistent()
+            .get(&OracleKey::AggregationMode(asset.clone()))
+            .unwrap_or(AggregationMode::Median)
+    }
+
+    /// Select how consensus prices are aggregated for an asset.
+    pub fn set_aggregation_mode(env: Env, caller: Address, asset: Symbol, mode: AggregationMode) {
+        caller.require_auth();
+        Self::require_governance(&env, &caller);
+        env.storage().persistent().set(&OracleKey::AggregationMode(asset), &mode);
+    }
+
+    pub fn get_aggregation_mode(env: Env, asset: Symbol) -> AggregationMode {
+        Self::aggregation_mode(&env, &asset)
+    }
+
     // ── Anomaly Detection ────────────────────
 
-    fn detect_anomaly(env: &Env, asset: &Symbol, new_price: i128) -> bool {
+    /// Signed move of `new_price` away from the latest recorded price, in BPS
+    fn price_move_bps(env: &Env, asset: &Symbol, new_price: i128) -> i128 {
         let history: Vec<PricePoint> = env.storage().persistent()
             .get(&OracleKey::PriceHistory(asset.clone()))
             .unwrap_or(Vec::new(env));
 
         if history.is_empty() {
-            return false; // No history to compare against
+            return 0; // No history to compare against
         }
 
         // Use latest historical price
@@ -244,21 +474,143 @@ impl OracleValidation {
         let prev = last.price;
 
         if prev == 0 {
-            return false;
+            return 0;
+        }
+
+        ((new_price - prev) * 10_000) / prev
+    }
+
+    // ── Correlation Groups ───────────────────
+
+    /// Group `assets` as expected to move together, replacing the group's
+    /// previous members. An empty list removes the group. Grouping is opt-in:
+    /// ungrouped assets are never checked for correlation anomalies.
+    pub fn set_correlation_group(env: Env, caller: Address, group: Symbol, assets: Vec<Symbol>) {
+        caller.require_auth();
+        Self::require_governance(&env, &caller);
+        if assets.len() == 1 {
+            panic!("correlation group needs at least two assets");
+        }
+
+        let previous: Vec<Symbol> = env.storage().persistent()
+            .get(&OracleKey::CorrelationGroup(group.clone()))
+            .unwrap_or(Vec::new(&env));
+        for asset in previous.iter() {
+            env.storage().persistent().remove(&OracleKey::AssetGroup(asset.clone()));
+            env.storage().persistent().remove(&OracleKey::CorrelationFlag(asset));
+        }
+
+        for asset in assets.iter() {
+            if env.storage().persistent().has(&OracleKey::AssetGroup(asset.clone())) {
+                panic!("asset already in a correlation group");
+            }
+            env.storage().persistent().set(&OracleKey::AssetGroup(asset), &group);
+        }
+
+        if assets.is_empty() {
+            env.storage().persistent().remove(&OracleKey::CorrelationGroup(group));
+        } else {
+            env.storage().persistent().set(&OracleKey::CorrelationGroup(group), &assets);
+        }
+    }
+
+    pub fn get_correlation_group(env: Env, group: Symbol) -> Vec<Symbol> {
+        env.storage().persistent()
+            .get(&OracleKey::CorrelationGroup(group))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Whether the asset's latest large move was not matched by any asset in
+    /// its correlation group.
+    pub fn is_correlation_anomaly(env: Env, asset: Symbol) -> bool {
+        env.storage().persistent()
+            .get(&OracleKey::CorrelationFlag(asset))
+            .unwrap_or(false)
+    }
+
+    /// Flag a large move of a grouped asset unless a peer moved the same way
+    /// within the window. A peer that follows clears both flags, so a
+    /// coordinated move isn't flagged whichever asset updates first.
+    fn check_correlation(env: &Env, asset: &Symbol, move_bps: i128, now: u64) {
+        let group: Symbol = match env.storage().persistent().get(&OracleKey::AssetGroup(asset.clone())) {
+            Some(group) => group,
+            None => return,
+        };
+        env.storage().persistent().set(&OracleKey::LastMove(asset.clone()), &(move_bps, now));
+
+        if move_bps.abs() < CORRELATION_JUMP_BPS {
+            env.storage().persistent().set(&OracleKey::CorrelationFlag(asset.clone()), &false);
+            return;
+        }
+
+        let peers: Vec<Symbol> = env.storage().persistent()
+            .get(&OracleKey::CorrelationGroup(group))
+            .unwrap_or(Vec::new(env));
+        let mut followed = false;
+        for peer in peers.iter() {
+            if peer == *asset {
+                continue;
+            }
+            if let Some((peer_move, at)) = env.storage().persistent()
+                .get::<OracleKey, (i128, u64)>(&OracleKey::LastMove(peer.clone()))
+            {
+                let recent = now.saturating_sub(at) <= CORRELATION_WINDOW_SECS;
+                let same_way = (peer_move > 0) == (move_bps > 0);
+                if recent && same_way && peer_move.abs() >= CORRELATION_JUMP_BPS / 2 {
+                    followed = true;
+                    env.storage().persistent().set(&OracleKey::CorrelationFlag(peer), &false);
+                }
+            }
+        }
+
+        env.storage().persistent().set(&OracleKey::CorrelationFlag(asset.clone()), &!followed);
+        if !followed {
+            log!(env, "isolated move of {} bps in correlation group", move_bps);
+            Self::raise_alert(env, Symbol::new(env, "correlation_anomaly"), asset, move_bps);
         }
+    }
+
+    // ── Alerting ─────────────────────────────
+
+    /// Route deviation and anomaly alerts to `alerting`, or stop with `None`.
+    pub fn set_alerting_contract(env: Env, caller: Address, alerting: Option<Address>) {
+        caller.require_auth();
+        Self::require_governance(&env, &caller);
+        match alerting {
+            Some(alerting) => env.storage().instance().set(&OracleKey::AlertingContract, &alerting),
+            None => env.storage().instance().remove(&OracleKey::AlertingContract),
+        }
+    }
+
+    pub fn get_alerting_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&OracleKey::AlertingContract)
+    }
 
-        let diff_bps = ((new_price - prev).abs() * 10_000) / prev;
-        diff_bps > ANOMALY_MULTIPLIER_BPS
+    /// Report `value` for `metric` on `asset` to the alerting contract, if
+    /// one is configured. A failing alerting contract never blocks pricing.
+    fn raise_alert(env: &Env, metric: Symbol, asset: &Symbol, value: i128) {
+        if let Some(alerting) = env.storage().instance().get::<OracleKey, Address>(&OracleKey::AlertingContract) {
+            let _ = AlertingClient::new(env, &alerting).try_evaluate_alerts(
+                &env.current_contract_address(),
+                &metric,
+                asset,
+                &value,
+            );
+        }
     }
 
     // ── Storage & History ────────────────────
 
     fn store_consensus(env: &Env, asset: &Symbol, result: &ConsensusResult) {
-        let anomaly = Self::detect_anomaly(env, asset, result.price);
+        let move_bps = Self::price_move_bps(env, asset, result.price);
+        let jump_bps = move_bps.abs();
+        let anomaly = jump_bps > ANOMALY_MULTIPLIER_BPS;
+        Self::check_correlation(env, asset, move_bps, result.timestamp);
 
         if anomaly {
             env.storage().instance().set(&OracleKey::AnomalyFlag(asset.clone()), &true);
             log!(env, "anomaly detected for asset");
+            Self::raise_alert(env, Symbol::new(env, "price_anomaly"), asset, jump_bps);
             // Still store but flag it; callers can decide how to handle
         } else {
             env.storage().instance().set(&OracleKey::AnomalyFlag(asset.clone()), &false);
@@ -269,25 +621,97 @@ impl OracleValidation {
             timestamp: result.timestamp,
             sources:   result.sources_used,
             anomaly,
+            confidence: result.aggregate_confidence,
         };
 
         // Persist aggregated price
         env.storage().persistent().set(&OracleKey::AggregatedPrice(asset.clone()), &result.price);
+        env.storage().persistent().set(
+            &OracleKey::AggregatedConfidence(asset.clone()),
+            &result.aggregate_confidence,
+        );
 
         // Append to history (capped)
         let mut history: Vec<PricePoint> = env.storage().persistent()
             .get(&OracleKey::PriceHistory(asset.clone()))
             .unwrap_or(Vec::new(env));
-        if history.len() >= HISTORY_MAX_ENTRIES {
-            // Remove oldest
+        let cap = Self::history_cap(env, asset);
+        if history.len() >= cap {
+            // Remove oldest, leaving room for the new point
+            let skip = history.len() + 1 - cap;
             let mut trimmed = Vec::<PricePoint>::new(env);
-            for i in 1..history.len() {
+            for i in skip..history.len() {
                 trimmed.push_back(history.get(i).unwrap());
             }
             history = trimmed;
         }
         history.push_back(point);
         env.storage().persistent().set(&OracleKey::PriceHistory(asset.clone()), &history);
+
+        // Notify subscribers on their own topic so they can filter cheaply
+        let subscribers: Vec<Address> = env.storage().persistent()
+            .get(&OracleKey::Subscribers(asset.clone()))
+            .unwrap_or(Vec::new(env));
+        for subscriber in subscribers.iter() {
+            env.events().publish(
+                (Symbol::new(env, "PRICE_UPDATE"), asset.clone(), subscriber),
+                (result.price, result.timestamp, result.aggregate_confidence),
+            );
+        }
+    }
+
+    // ── Subscriptions ────────────────────────
+
+    pub fn subscribe_to_asset(env: Env, subscriber: Address, asset: Symbol) {
+        subscriber.require_auth();
+        let mut subscribers: Vec<Address> = env.storage().persistent()
+            .get(&OracleKey::Subscribers(asset.clone()))
+            .unwrap_or(Vec::new(&env));
+        if !subscribers.contains(&subscriber) {
+            if subscribers.len() >= MAX_SUBSCRIBERS_PER_ASSET {
+                panic!("subscriber limit reached");
+            }
+            subscribers.push_back(subscriber);
+            env.storage().persistent().set(&OracleKey::Subscribers(asset), &subscribers);
+        }
+    }
+
+    pub fn unsubscribe_from_asset(env: Env, subscriber: Address, asset: Symbol) {
+        subscriber.require_auth();
+        let mut subscribers: Vec<Address> = env.storage().persistent()
+            .get(&OracleKey::Subscribers(asset.clone()))
+            .unwrap_or(Vec::new(&env));
+        if let Some(index) = subscribers.first_index_of(&subscriber) {
+            subscribers.remove(index);
+            env.storage().persistent().set(&OracleKey::Subscribers(asset), &subscribers);
+        }
+    }
+
+    pub fn get_subscribers(env: Env, asset: Symbol) -> Vec<Address> {
+        env.storage().persistent()
+            .get(&OracleKey::Subscribers(asset))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    fn history_cap(env: &Env, asset: &Symbol) -> u32 {
+        env.storage().persistent()
+            .get(&OracleKey::HistoryCap(asset.clone()))
+            .unwrap_or(HISTORY_MAX_ENTRIES)
+    }
+
+    /// Set how many price points are kept for an asset. Takes effect on
+    /// the next stored consensus.
+    pub fn set_history_cap(env: Env, caller: Address, asset: Symbol, cap: u32) {
+        caller.require_auth();
+        Self::require_governance(&env, &caller);
+        if cap == 0 || cap > HISTORY_ABSOLUTE_MAX {
+            panic!("history cap out of range");
+        }
+        env.storage().persistent().set(&OracleKey::HistoryCap(asset), &cap);
+    }
+
+    pub fn get_history_cap(env: Env, asset: Symbol) -> u32 {
+        Self::history_cap(&env, &asset)
     }
 
     // ── Fallback Pricing ──────────────────────
@@ -318,8 +742,36 @@ impl OracleValidation {
             .expect("no price available and no fallback set")
     }
 
+    /// Like `get_price`, but also returns the consensus confidence so
+    /// callers can enforce a minimum. Fallback prices report 0 confidence.
+    pub fn get_price_with_confidence(env: Env, asset: Symbol) -> (i128, u32) {
+        let anomaly: bool = env.storage().instance()
+            .get(&OracleKey::AnomalyFlag(asset.clone()))
+            .unwrap_or(false);
+
+        if !anomaly {
+            if let Some(price) = env.storage().persistent()
+                .get::<OracleKey, i128>(&OracleKey::AggregatedPrice(asset.clone()))
+            {
+                let confidence: u32 = env.storage().persistent()
+                    .get(&OracleKey::AggregatedConfidence(asset))
+                    .unwrap_or(0);
+                return (price, confidence);
+            }
+        }
+
+        let fallback: i128 = env.storage().persistent()
+            .get(&OracleKey::FallbackPrice(asset))
+            .expect("no price available and no fallback set");
+        (fallback, 0)
+    }
+
     // ── Data Quality Metrics ─────────────────
 
+    pub fn get_last_round(env: Env, source: Address) -> Option<u64> {
+        env.storage().persistent().get(&OracleKey::LastRound(source))
+    }
+
     pub fn get_source_quality(env: Env, source: Address) -> u32 {
         env.storage().instance()
             .get(&OracleKey::QualityScore(source))
@@ -338,6 +790,35 @@ impl OracleValidation {
             .unwrap_or(false)
     }
 
+    /// Liveness of every approved source as (source, live, last submission
+    /// timestamp). A source is live while its latest submission is within the
+    /// staleness threshold consensus uses; sources that never submitted report 0.
+    pub fn get_source_liveness(env: Env) -> Vec<(Address, bool, u64)> {
+        let sources: Vec<Address> = env.storage().instance()
+            .get(&OracleKey::SourceList)
+            .unwrap_or(Vec::new(&env));
+        let now = env.ledger().timestamp();
+
+        let mut liveness = Vec::new(&env);
+        for source in sources.iter() {
+            let last_submission: Option<u64> = env.storage().persistent()
+                .get(&OracleKey::LastSubmission(source.clone()));
+            let live = last_submission
+                .is_some_and(|ts| now.saturating_sub(ts) <= STALENESS_THRESHOLD_SECS);
+            liveness.push_back((source, live, last_submission.unwrap_or(0)));
+        }
+        liveness
+    }
+
+    // ── Health ───────────────────────────────
+
+    /// Health check for monitoring: (initialized, paused, schema version)
+    pub fn health(env: Env) -> (bool, bool, u32) {
+        let initialized = env.storage().instance().has(&OracleKey::Governance);
+        let paused = env.storage().instance().get(&OracleKey::Paused).unwrap_or(false);
+        (initialized, paused, SCHEMA_VERSION)
+    }
+
     // ── Utilities ───────────────────────────
 
     fn sort_prices(env: &Env, prices: &Vec<i128>) -> Vec<i128> {
@@ -371,6 +852,23 @@ impl OracleValidation {
         }
     }
 
+    /// Median of the medians of consecutive source subsets, so a cluster
+    /// of colluding sources can capture at most the subsets it dominates.
+    fn median_of_medians(env: &Env, prices: &Vec<i128>) -> i128 {
+        let mut medians = Vec::<i128>::new(env);
+        let mut start = 0;
+        while start < prices.len() {
+            let end = (start + MEDIAN_GROUP_SIZE).min(prices.len());
+            let mut group = Vec::<i128>::new(env);
+            for i in start..end {
+                group.push_back(prices.get(i).unwrap());
+            }
+            medians.push_back(Self::median(&Self::sort_prices(env, &group)));
+            start = end;
+        }
+        Self::median(&Self::sort_prices(env, &medians))
+    }
+
     fn max_deviation_bps(sorted: &Vec<i128>, median: i128) -> i128 {
         if median == 0 { return 0; }
         let mut max = 0i128;
@@ -412,4 +910,13 @@ impl OracleValidation {
             panic!("source not approved");
         }
     }
-}
\ No newline at end of file
+}
+
+// Client interface for the alerting system contract
+#[contractclient(name = "AlertingClient")]
+pub trait AlertingInterface {
+    fn evaluate_alerts(env: Env, contract_address: Address, metric_name: Symbol, asset: Symbol, value: i128);
+}
+
+#[cfg(test)]
+mod test;
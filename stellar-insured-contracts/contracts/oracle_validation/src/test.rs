@@ -0,0 +1,394 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::testutils::{Address as _, Events, Ledger};
+use soroban_sdk::{contract, contractimpl, symbol_short, TryFromVal};
+
+fn setup(env: &Env, source_count: u32) -> (OracleValidationClient<'_>, Address, Vec<Address>) {
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, OracleValidation);
+    let client = OracleValidationClient::new(env, &contract_id);
+    let governance = Address::generate(env);
+    client.initialize(&governance);
+
+    let mut sources = Vec::new(env);
+    for _ in 0..source_count {
+        let source = Address::generate(env);
+        client.add_source(&governance, &source);
+        sources.push_back(source);
+    }
+
+    (client, governance, sources)
+}
+
+#[test]
+fn test_history_cap_trims_oldest_points() {
+    let env = Env::default();
+    let (client, governance, sources) = setup(&env, MIN_SOURCES_FOR_CONSENSUS);
+    let asset = Symbol::new(&env, "XLM");
+
+    assert_eq!(client.get_history_cap(&asset), HISTORY_MAX_ENTRIES);
+    client.set_history_cap(&governance, &asset, &2);
+
+    for source in sources.iter() {
+        client.submit_price(&source, &asset, &1_000_000, &90, &1);
+    }
+    assert_eq!(client.get_price_history(&asset).len(), 1);
+
+    for _ in 0..3 {
+        env.ledger().with_mut(|li| li.timestamp += 10);
+        client.evaluate_consensus(&asset);
+    }
+
+    let history = client.get_price_history(&asset);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap().timestamp, 20);
+    assert_eq!(history.get(1).unwrap().timestamp, 30);
+}
+
+#[test]
+#[should_panic(expected = "history cap out of range")]
+fn test_history_cap_enforces_absolute_max() {
+    let env = Env::default();
+    let (client, governance, _sources) = setup(&env, MIN_SOURCES_FOR_CONSENSUS);
+
+    client.set_history_cap(&governance, &Symbol::new(&env, "XLM"), &(HISTORY_ABSOLUTE_MAX + 1));
+}
+
+#[test]
+fn test_median_of_medians_resists_clustered_sources() {
+    let env = Env::default();
+    let (client, governance, sources) = setup(&env, 9);
+    let asset = Symbol::new(&env, "XLM");
+
+    // Five honest sources around 1000 and four colluding at 1045, spread
+    // so the plain median is pulled up to the highest honest price
+    let prices = [990, 995, 1045, 1000, 1005, 1045, 1010, 1045, 1045];
+    for (i, price) in prices.iter().enumerate() {
+        client.submit_price(&sources.get(i as u32).unwrap(), &asset, price, &90, &1);
+    }
+
+    assert_eq!(client.get_aggregation_mode(&asset), AggregationMode::Median);
+    assert_eq!(client.evaluate_consensus(&asset).price, 1010);
+
+    client.set_aggregation_mode(&governance, &asset, &AggregationMode::MedianOfMedians);
+    let result = client.evaluate_consensus(&asset);
+    assert!(result.is_valid);
+    assert_eq!(result.price, 1005);
+}
+
+#[test]
+fn test_low_confidence_sources_yield_low_aggregate_confidence() {
+    let env = Env::default();
+    let (client, _governance, sources) = setup(&env, MIN_SOURCES_FOR_CONSENSUS);
+    let xlm = Symbol::new(&env, "XLM");
+
+    for source in sources.iter() {
+        client.submit_price(&source, &xlm, &1_000_000, &95, &1);
+    }
+    assert_eq!(client.get_price_with_confidence(&xlm), (1_000_000, 95));
+
+    let confidences = [10, 20, 30];
+    for (i, confidence) in confidences.iter().enumerate() {
+        client.submit_price(&sources.get(i as u32).unwrap(), &xlm, &1_000_000, confidence, &2);
+    }
+
+    let result = client.evaluate_consensus(&xlm);
+    assert!(result.is_valid);
+    assert_eq!(result.aggregate_confidence, 20);
+    assert_eq!(client.get_price_with_confidence(&xlm), (1_000_000, 20));
+
+    let history = client.get_price_history(&xlm);
+    assert_eq!(history.get(history.len() - 1).unwrap().confidence, 20);
+}
+
+#[test]
+fn test_submission_rounds_must_increase() {
+    let env = Env::default();
+    let (client, _governance, sources) = setup(&env, MIN_SOURCES_FOR_CONSENSUS);
+    let source = sources.get(0).unwrap();
+    let xlm = Symbol::new(&env, "XLM");
+
+    client.submit_price(&source, &xlm, &1_000_000, &90, &5);
+    assert_eq!(client.get_last_round(&source), Some(5));
+
+    assert!(client.try_submit_price(&source, &xlm, &1_000_000, &90, &5).is_err());
+    assert!(client.try_submit_price(&source, &xlm, &1_000_000, &90, &3).is_err());
+    assert_eq!(client.get_last_round(&source), Some(5));
+
+    client.submit_price(&source, &xlm, &1_010_000, &90, &6);
+    assert_eq!(client.get_last_round(&source), Some(6));
+}
+
+#[test]
+fn test_low_confidence_submissions_excluded_from_consensus() {
+    let env = Env::default();
+    let (client, governance, sources) = setup(&env, 4);
+    let xlm = Symbol::new(&env, "XLM");
+
+    let submissions = [(1_000, 90), (1_010, 90), (1_020, 90), (1_040, 20)];
+    for (i, (price, confidence)) in submissions.iter().enumerate() {
+        client.submit_price(&sources.get(i as u32).unwrap(), &xlm, price, confidence, &1);
+    }
+    assert_eq!(client.evaluate_consensus(&xlm).price, 1_015);
+
+    client.set_min_confidence(&governance, &50);
+    let result = client.evaluate_consensus(&xlm);
+    assert!(result.is_valid);
+    assert_eq!(result.sources_used, 3);
+    assert_eq!(result.price, 1_010);
+}
+
+fn price_updates_for(env: &Env, asset: &Symbol, subscriber: &Address) -> u32 {
+    let topic = Symbol::new(env, "PRICE_UPDATE");
+    env.events()
+        .all()
+        .iter()
+        .filter(|(_, topics, _)| {
+            topics.len() == 3
+                && Symbol::try_from_val(env, &topics.get(0).unwrap()).is_ok_and(|t| t == topic)
+                && Symbol::try_from_val(env, &topics.get(1).unwrap()).is_ok_and(|a| a == *asset)
+                && Address::try_from_val(env, &topics.get(2).unwrap()).is_ok_and(|s| s == *subscriber)
+        })
+        .count() as u32
+}
+
+#[test]
+fn test_price_update_events_only_for_subscribed_asset() {
+    let env = Env::default();
+    let (client, _governance, sources) = setup(&env, MIN_SOURCES_FOR_CONSENSUS);
+    let xlm = Symbol::new(&env, "XLM");
+    let btc = Symbol::new(&env, "BTC");
+
+    let subscriber = Address::generate(&env);
+    client.subscribe_to_asset(&subscriber, &xlm);
+    client.subscribe_to_asset(&subscriber, &xlm);
+    assert_eq!(client.get_subscribers(&xlm).len(), 1);
+
+    for source in sources.iter() {
+        client.submit_price(&source, &xlm, &1_000_000, &90, &1);
+    }
+    assert_eq!(price_updates_for(&env, &xlm, &subscriber), 1);
+
+    for source in sources.iter() {
+        client.submit_price(&source, &btc, &5_000_000, &90, &2);
+    }
+    assert!(!client.get_price_history(&btc).is_empty());
+    assert_eq!(price_updates_for(&env, &btc, &subscriber), 0);
+}
+
+#[test]
+#[should_panic(expected = "subscriber limit reached")]
+fn test_subscriptions_are_capped_per_asset() {
+    let env = Env::default();
+    let (client, _governance, _sources) = setup(&env, 0);
+    let xlm = Symbol::new(&env, "XLM");
+
+    for _ in 0..=MAX_SUBSCRIBERS_PER_ASSET {
+        client.subscribe_to_asset(&Address::generate(&env), &xlm);
+    }
+}
+
+#[test]
+fn test_health_reflects_pause_state() {
+    let env = Env::default();
+    let (client, _governance, _sources) = setup(&env, 0);
+    assert_eq!(client.health(), (true, false, SCHEMA_VERSION));
+
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(&OracleKey::Paused, &true);
+    });
+    assert_eq!(client.health(), (true, true, SCHEMA_VERSION));
+}
+
+#[test]
+fn test_source_liveness_reports_stale_sources() {
+    let env = Env::default();
+    let (client, _governance, sources) = setup(&env, 3);
+    let asset = Symbol::new(&env, "XLM");
+    let (stale, fresh, silent) = (sources.get(0).unwrap(), sources.get(1).unwrap(), sources.get(2).unwrap());
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    client.submit_price(&stale, &asset, &1_000_000, &90, &1);
+
+    env.ledger().with_mut(|li| li.timestamp += STALENESS_THRESHOLD_SECS + 1);
+    client.submit_price(&fresh, &asset, &1_000_000, &90, &1);
+
+    let liveness = client.get_source_liveness();
+    assert_eq!(liveness.len(), 3);
+    assert_eq!(liveness.get(0).unwrap(), (stale, false, 1_000));
+    assert_eq!(liveness.get(1).unwrap(), (fresh, true, 1_000 + STALENESS_THRESHOLD_SECS + 1));
+    assert_eq!(liveness.get(2).unwrap(), (silent, false, 0));
+}
+
+#[test]
+fn test_batch_submission_updates_each_asset() {
+    let env = Env::default();
+    let (client, _governance, sources) = setup(&env, MIN_SOURCES_FOR_CONSENSUS);
+    let xlm = Symbol::new(&env, "XLM");
+    let btc = Symbol::new(&env, "BTC");
+    let eth = Symbol::new(&env, "ETH");
+
+    for source in sources.iter() {
+        let entries = Vec::from_array(&env, [
+            (xlm.clone(), 1_000_000i128, 90u32),
+            (btc.clone(), 600_000_000_000, 90),
+            (eth.clone(), 30_000_000_000, 90),
+        ]);
+        client.submit_prices_batch(&source, &entries, &1);
+    }
+
+    assert_eq!(client.get_price(&xlm), 1_000_000);
+    assert_eq!(client.get_price(&btc), 600_000_000_000);
+    assert_eq!(client.get_price(&eth), 30_000_000_000);
+    assert_eq!(client.get_last_round(&sources.get(0).unwrap()), Some(1));
+
+    // The batch shares the round guard with single submissions
+    let replay = Vec::from_array(&env, [(xlm.clone(), 1_000_000i128, 90u32)]);
+    assert!(client.try_submit_prices_batch(&sources.get(0).unwrap(), &replay, &1).is_err());
+}
+
+#[test]
+#[should_panic(expected = "batch size out of range")]
+fn test_batch_submission_enforces_size_cap() {
+    let env = Env::default();
+    let (client, _governance, sources) = setup(&env, 1);
+
+    let mut entries = Vec::new(&env);
+    for _ in 0..=MAX_BATCH_SUBMISSIONS {
+        entries.push_back((Symbol::new(&env, "XLM"), 1_000_000i128, 90u32));
+    }
+    client.submit_prices_batch(&sources.get(0).unwrap(), &entries, &1);
+}
+
+#[test]
+fn test_quality_decays_to_floor_then_recovers() {
+    let env = Env::default();
+    let (client, governance, sources) = setup(&env, 4);
+    let asset = Symbol::new(&env, "XLM");
+    let laggard = sources.get(3).unwrap();
+    client.set_quality_policy(&governance, &20, &10, &30);
+
+    client.submit_price(&laggard, &asset, &1_000_000, &90, &1);
+
+    // The laggard goes quiet while the others keep reporting
+    for round in 1..=5u64 {
+        env.ledger().with_mut(|li| li.timestamp += STALENESS_THRESHOLD_SECS + 1);
+        for i in 0..3 {
+            client.submit_price(&sources.get(i).unwrap(), &asset, &1_000_000, &90, &round);
+        }
+    }
+    // 100 decays by 20 per stale evaluation and bottoms out at the floor
+    assert_eq!(client.get_source_quality(&laggard), 30);
+    assert_eq!(client.get_source_quality(&sources.get(0).unwrap()), 100);
+
+    // Fresh, accurate submissions climb back at the recovery rate
+    for round in 2..=4u64 {
+        env.ledger().with_mut(|li| li.timestamp += 10);
+        client.submit_price(&laggard, &asset, &1_000_000, &90, &round);
+    }
+    assert_eq!(client.get_source_quality(&laggard), 60);
+
+    for round in 5..=10u64 {
+        env.ledger().with_mut(|li| li.timestamp += 10);
+        client.submit_price(&laggard, &asset, &1_000_000, &90, &round);
+    }
+    assert_eq!(client.get_source_quality(&laggard), 100);
+}
+
+#[test]
+fn test_quality_credited_once_per_round() {
+    let env = Env::default();
+    let (client, governance, sources) = setup(&env, 4);
+    let asset = Symbol::new(&env, "XLM");
+    let laggard = sources.get(3).unwrap();
+    client.set_quality_policy(&governance, &20, &10, &30);
+
+    client.submit_price(&laggard, &asset, &1_000_000, &90, &1);
+    env.ledger().with_mut(|li| li.timestamp += STALENESS_THRESHOLD_SECS + 1);
+    for i in 0..3 {
+        client.submit_price(&sources.get(i).unwrap(), &asset, &1_000_000, &90, &1);
+    }
+
+    // Re-evaluating the same round neither decays nor recovers again
+    client.evaluate_consensus(&asset);
+    client.evaluate_consensus(&asset);
+    assert_eq!(client.get_source_quality(&laggard), 80);
+
+    client.submit_price(&laggard, &asset, &1_000_000, &90, &2);
+    client.evaluate_consensus(&asset);
+    client.evaluate_consensus(&asset);
+    assert_eq!(client.get_source_quality(&laggard), 90);
+}
+
+#[test]
+fn test_isolated_move_in_correlation_group_is_flagged() {
+    let env = Env::default();
+    let (client, governance, sources) = setup(&env, MIN_SOURCES_FOR_CONSENSUS);
+    let [btc, wbtc, eth, steth] = ["BTC", "WBTC", "ETH", "STETH"].map(|s| Symbol::new(&env, s));
+    client.set_correlation_group(&governance, &Symbol::new(&env, "btc"), &Vec::from_array(&env, [btc.clone(), wbtc.clone()]));
+    client.set_correlation_group(&governance, &Symbol::new(&env, "eth"), &Vec::from_array(&env, [eth.clone(), steth.clone()]));
+
+    // Everything starts at 1.0; then BTC jumps 15% alone while ETH and STETH move together
+    for (round, [btc_price, eth_price]) in [[1_000_000i128, 1_000_000], [1_150_000, 1_150_000]].iter().enumerate() {
+        for source in sources.iter() {
+            let entries = Vec::from_array(&env, [
+                (btc.clone(), *btc_price, 90u32),
+                (wbtc.clone(), 1_000_000, 90),
+                (eth.clone(), *eth_price, 90),
+                (steth.clone(), *eth_price, 90),
+            ]);
+            client.submit_prices_batch(&source, &entries, &(round as u64 + 1));
+        }
+    }
+
+    assert!(client.is_correlation_anomaly(&btc));
+    assert!(!client.is_correlation_anomaly(&wbtc));
+    assert!(!client.is_correlation_anomaly(&eth));
+    assert!(!client.is_correlation_anomaly(&steth));
+
+    // A 15% jump is below the single-asset anomaly threshold
+    assert!(!client.is_anomaly(&btc));
+}
+
+/// Stand-in alerting contract that records every alert it is asked to evaluate
+#[contract]
+struct MockAlerting;
+
+#[contractimpl]
+impl MockAlerting {
+    pub fn evaluate_alerts(env: Env, _contract_address: Address, metric_name: Symbol, asset: Symbol, value: i128) {
+        let mut alerts: Vec<(Symbol, Symbol, i128)> = env.storage().instance()
+            .get(&symbol_short!("alerts"))
+            .unwrap_or(Vec::new(&env));
+        alerts.push_back((metric_name, asset, value));
+        env.storage().instance().set(&symbol_short!("alerts"), &alerts);
+    }
+
+    pub fn alerts(env: Env) -> Vec<(Symbol, Symbol, i128)> {
+        env.storage().instance().get(&symbol_short!("alerts")).unwrap_or(Vec::new(&env))
+    }
+}
+
+#[test]
+fn test_rejected_consensus_raises_deviation_alert() {
+    let env = Env::default();
+    let (client, governance, sources) = setup(&env, 3);
+    let asset = Symbol::new(&env, "XLM");
+    let alerting = MockAlertingClient::new(&env, &env.register_contract(None, MockAlerting));
+    client.set_alerting_contract(&governance, &Some(alerting.address.clone()));
+
+    // Consensus first runs on the third submission, where the 20% outlier is rejected
+    for (i, price) in [1_000_000i128, 1_000_000, 1_200_000].iter().enumerate() {
+        client.submit_price(&sources.get(i as u32).unwrap(), &asset, price, &90, &1);
+    }
+    let alerts = alerting.alerts();
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts.get(0).unwrap(), (Symbol::new(&env, "price_deviation"), asset.clone(), 2_000));
+
+    // Once unset, further rejections are not reported
+    client.set_alerting_contract(&governance, &None);
+    client.submit_price(&sources.get(0).unwrap(), &asset, &1_000_000, &90, &2);
+    assert_eq!(alerting.alerts().len(), 1);
+}
@@ -1,15 +1,22 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, symbol_short, Address, BytesN, Env, Symbol, Vec, String, Map,
+    contract, contractclient, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env,
+    Symbol, Vec, String, Map,
 };
 use shared::{
     GasMeasurement, GasMetrics, authorization::{require_admin, require_role, Role},
+    idempotency::{idempotent_id, remember_idempotent_id},
+    index::AppendIndex,
+    metrics::{self, MetricDefinition},
 };
 
 #[contract]
 pub struct PerformanceMonitoringContract;
 
+/// Storage schema version written by this build of the contract
+const SCHEMA_VERSION: u32 = 1;
+
 // Storage keys
 const ADMIN: Symbol = symbol_short!("ADMIN");
 const PAUSED: Symbol = symbol_short!("PAUSED");
@@ -25,6 +32,20 @@ const ALERT_HISTORY: Symbol = symbol_short!("ALERT_HIST");
 const DASHBOARD_CONFIG: Symbol = symbol_short!("DASH_CFG");
 const CONTRACT_METRICS: Symbol = symbol_short!("CONT_MET");
 const TIME_SERIES_DATA: Symbol = symbol_short!("TIME_SER");
+const METRIC_POLICY: Symbol = symbol_short!("MET_POL");
+const METRIC_BUCKET: Symbol = symbol_short!("MET_BKT");
+const CIRCUIT_BREAKER: Symbol = symbol_short!("CIRC_BRK");
+const BREAKER_WINDOW: Symbol = symbol_short!("BRK_WIN");
+const METRIC_REPORTER: Symbol = symbol_short!("MET_RPT");
+const OWNER_DASHBOARDS: Symbol = symbol_short!("OWN_DASH");
+const ACK_EXPIRY: Symbol = symbol_short!("ACK_EXP");
+const ACKED_ALERTS: Symbol = symbol_short!("ACK_ALRT");
+
+/// Most alerts a bulk acknowledgement or expiry poll will touch
+const MAX_ALERT_BATCH: u32 = 50;
+
+/// Seconds an acknowledgement lasts unless the admin sets another window
+const DEFAULT_ACK_EXPIRY: u64 = 86400;
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -67,6 +88,42 @@ pub struct PerformanceMetric {
     pub operation: Symbol,
 }
 
+/// Write policy for a registered metric
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MetricPolicy {
+    /// Persist every raw metric in addition to the rolling bucket
+    pub keep_raw: bool,
+    /// Bucket granularity (minute, hour, day, week, month)
+    pub bucket_granularity: Symbol,
+}
+
+/// Rolling bucket of downsampled metric values
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MetricBucket {
+    /// Contract address
+    pub contract_address: Address,
+    /// Metric name
+    pub metric_name: Symbol,
+    /// Time granularity (minute, hour, day, week, month)
+    pub granularity: Symbol,
+    /// Start timestamp of bucket
+    pub start_time: u64,
+    /// End timestamp of bucket
+    pub end_time: u64,
+    /// Number of data points in bucket
+    pub data_count: u32,
+    /// Sum of values in bucket
+    pub sum: u64,
+    /// Minimum value in bucket
+    pub min: u64,
+    /// Maximum value in bucket
+    pub max: u64,
+    /// Last updated timestamp
+    pub updated_at: u64,
+}
+
 /// Aggregated metrics for time periods
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -157,6 +214,8 @@ pub struct AlertRecord {
     pub acknowledged_by: Option<Address>,
     /// Acknowledged timestamp
     pub acknowledged_at: Option<u64>,
+    /// When the acknowledgement lapses and the alert re-activates
+    pub ack_expires_at: Option<u64>,
 }
 
 /// Dashboard configuration
@@ -203,6 +262,38 @@ pub struct DashboardMetric {
     pub color: String,
 }
 
+/// Opt-in rule that pauses a monitored contract when its error rate spikes
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CircuitBreakerRule {
+    /// Contract to pause; must expose `set_paused(admin, paused)`
+    pub target: Address,
+    /// Metric name that marks a failed operation
+    pub error_metric: Symbol,
+    /// Maximum tolerated error rate (percentage)
+    pub max_error_rate: u32,
+    /// Evaluation window (seconds)
+    pub window: u64,
+    /// Minimum operations in the window before the rule can trip
+    pub min_operations: u32,
+    /// Whether the breaker has paused the target
+    pub tripped: bool,
+    /// Timestamp the breaker tripped
+    pub tripped_at: Option<u64>,
+}
+
+/// Operation and error counts for the current circuit breaker window
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ErrorRateWindow {
+    /// Start timestamp of window
+    pub window_start: u64,
+    /// Operations recorded in window
+    pub operations: u32,
+    /// Failed operations recorded in window
+    pub errors: u32,
+}
+
 /// Time series data point
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -263,6 +354,56 @@ fn get_next_dashboard_id(env: &Env) -> u64 {
     current + 1
 }
 
+/// Bucket size in seconds for a granularity, if it is supported
+fn bucket_size(granularity: &Symbol) -> Option<u64> {
+    if *granularity == symbol_short!("minute") {
+        Some(60)
+    } else if *granularity == symbol_short!("hour") {
+        Some(3600)
+    } else if *granularity == symbol_short!("day") {
+        Some(86400)
+    } else if *granularity == symbol_short!("week") {
+        Some(604800)
+    } else if *granularity == symbol_short!("month") {
+        Some(2592000)
+    } else {
+        None
+    }
+}
+
+/// Fold a metric into the rolling bucket covering its timestamp
+fn update_metric_bucket(env: &Env, metric: &PerformanceMetric, granularity: Symbol) -> Result<(), ContractError> {
+    let size = bucket_size(&granularity).ok_or(ContractError::InvalidInput)?;
+    let bucket_start = (metric.timestamp / size) * size;
+    let key = (METRIC_BUCKET, metric.contract_address.clone(), metric.metric_name.clone(), bucket_start);
+
+    let bucket = match env.storage().persistent().get::<_, MetricBucket>(&key) {
+        Some(mut bucket) => {
+            bucket.data_count += 1;
+            bucket.sum += metric.value;
+            bucket.min = bucket.min.min(metric.value);
+            bucket.max = bucket.max.max(metric.value);
+            bucket.updated_at = metric.timestamp;
+            bucket
+        }
+        None => MetricBucket {
+            contract_address: metric.contract_address.clone(),
+            metric_name: metric.metric_name.clone(),
+            granularity,
+            start_time: bucket_start,
+            end_time: bucket_start + size,
+            data_count: 1,
+            sum: metric.value,
+            min: metric.value,
+            max: metric.value,
+            updated_at: metric.timestamp,
+        },
+    };
+
+    env.storage().persistent().set(&key, &bucket);
+    Ok(())
+}
+
 /// Evaluate alert condition
 fn evaluate_alert_condition(condition: Symbol, actual: u64, threshold: u64) -> bool {
     match condition.to_string().as_str() {
@@ -294,7 +435,8 @@ impl PerformanceMonitoringContract {
         Ok(())
     }
 
-    /// Record a performance metric
+    /// Record a performance metric about the calling contract itself.
+    /// `contract_address` must authorize, so no one can report for another contract.
     pub fn record_metric(
         env: Env,
         contract_address: Address,
@@ -304,14 +446,82 @@ impl PerformanceMonitoringContract {
         operation: Symbol,
         metadata: Map<Symbol, String>,
     ) -> Result<u64, ContractError> {
-        // This function should be callable by any contract for self-monitoring
-        // In production, add authorization checks
+        contract_address.require_auth();
 
-        if is_paused(&env) {
+        Self::store_metric(&env, contract_address, metric_name, value, unit, operation, metadata)
+    }
+
+    /// Record a performance metric on behalf of `contract_address`. `reporter`
+    /// must be on that contract's reporter allowlist.
+    pub fn record_metric_for(
+        env: Env,
+        reporter: Address,
+        contract_address: Address,
+        metric_name: Symbol,
+        value: u64,
+        unit: Symbol,
+        operation: Symbol,
+        metadata: Map<Symbol, String>,
+    ) -> Result<u64, ContractError> {
+        reporter.require_auth();
+
+        let allowed: bool = env
+            .storage()
+            .persistent()
+            .get(&(METRIC_REPORTER, contract_address.clone(), reporter))
+            .unwrap_or(false);
+        if !allowed {
+            return Err(ContractError::Unauthorized);
+        }
+
+        Self::store_metric(&env, contract_address, metric_name, value, unit, operation, metadata)
+    }
+
+    /// Allow or revoke `reporter` recording metrics for `contract_address` (admin only)
+    pub fn set_metric_reporter(
+        env: Env,
+        admin: Address,
+        contract_address: Address,
+        reporter: Address,
+        allowed: bool,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().persistent().get(&ADMIN).ok_or(ContractError::NotInitialized)?;
+        if admin != stored_admin {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let key = (METRIC_REPORTER, contract_address.clone(), reporter.clone());
+        if allowed {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        env.events().publish((symbol_short!("met_rpt"), contract_address), (reporter, allowed));
+
+        Ok(())
+    }
+
+    fn store_metric(
+        env: &Env,
+        contract_address: Address,
+        metric_name: Symbol,
+        value: u64,
+        unit: Symbol,
+        operation: Symbol,
+        metadata: Map<Symbol, String>,
+    ) -> Result<u64, ContractError> {
+        if is_paused(env) {
             return Err(ContractError::Paused);
         }
 
-        let metric_id = get_next_metric_id(&env);
+        if !metrics::is_registered(env, &metric_name) {
+            return Err(ContractError::MetricNotFound);
+        }
+
+        let metric_id = get_next_metric_id(env);
 
         let metric = PerformanceMetric {
             metric_id,
@@ -324,15 +534,28 @@ impl PerformanceMonitoringContract {
             operation,
         };
 
-        env.storage()
+        // Downsampled metrics only keep raw points when the policy asks for them
+        let policy: Option<MetricPolicy> = env
+            .storage()
             .persistent()
-            .set(&(PERFORMANCE_METRIC, metric_id), &metric);
+            .get(&(METRIC_POLICY, metric.metric_name.clone()));
+        if let Some(policy) = &policy {
+            update_metric_bucket(env, &metric, policy.bucket_granularity.clone())?;
+        }
+        if policy.map_or(true, |policy| policy.keep_raw) {
+            env.storage()
+                .persistent()
+                .set(&(PERFORMANCE_METRIC, metric_id), &metric);
+        }
 
         // Update contract metrics
-        Self::update_contract_metrics(&env, contract_address.clone(), &metric)?;
+        Self::update_contract_metrics(env, contract_address.clone(), &metric)?;
 
         // Check alert rules
-        Self::check_alert_rules(&env, &metric)?;
+        Self::check_alert_rules(env, &metric)?;
+
+        // Trip the target's circuit breaker on an error-rate spike
+        Self::check_circuit_breaker(env, &metric);
 
         // Emit event
         env.events().publish(
@@ -343,7 +566,155 @@ impl PerformanceMonitoringContract {
         Ok(metric_id)
     }
 
-    /// Create an alert rule
+    /// Register a metric name so it can be recorded (admin only)
+    pub fn register_metric(
+        env: Env,
+        admin: Address,
+        name: Symbol,
+        unit: Symbol,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        require_admin(&env, &admin)?;
+
+        if !metrics::register_metric(&env, &name, &unit) {
+            return Err(ContractError::AlreadyExists);
+        }
+
+        env.events().publish((symbol_short!("met_reg"), name), unit);
+
+        Ok(())
+    }
+
+    /// Set the write policy for a registered metric (admin only). Without
+    /// `keep_raw`, recorded values only update the rolling bucket.
+    pub fn set_metric_policy(
+        env: Env,
+        admin: Address,
+        metric_name: Symbol,
+        keep_raw: bool,
+        bucket_granularity: Symbol,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().persistent().get(&ADMIN).ok_or(ContractError::NotInitialized)?;
+        if admin != stored_admin {
+            return Err(ContractError::Unauthorized);
+        }
+
+        if !metrics::is_registered(&env, &metric_name) {
+            return Err(ContractError::MetricNotFound);
+        }
+
+        if bucket_size(&bucket_granularity).is_none() {
+            return Err(ContractError::InvalidInput);
+        }
+
+        let policy = MetricPolicy {
+            keep_raw,
+            bucket_granularity: bucket_granularity.clone(),
+        };
+        env.storage().persistent().set(&(METRIC_POLICY, metric_name.clone()), &policy);
+
+        env.events().publish((symbol_short!("met_pol"), metric_name), (keep_raw, bucket_granularity));
+
+        Ok(())
+    }
+
+    /// Opt a contract into automatic pausing when its error rate over
+    /// `window` exceeds `max_error_rate` percent (admin only). Every metric
+    /// recorded for the target counts as an operation and metrics named
+    /// `error_metric` count as failures. This contract must be the target's
+    /// pause admin for the breaker to take effect.
+    pub fn set_circuit_breaker(
+        env: Env,
+        admin: Address,
+        target: Address,
+        error_metric: Symbol,
+        max_error_rate: u32,
+        window: u64,
+        min_operations: u32,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().persistent().get(&ADMIN).ok_or(ContractError::NotInitialized)?;
+        if admin != stored_admin {
+            return Err(ContractError::Unauthorized);
+        }
+
+        if max_error_rate > 100 || window == 0 || min_operations == 0 {
+            return Err(ContractError::InvalidInput);
+        }
+
+        if !metrics::is_registered(&env, &error_metric) {
+            return Err(ContractError::MetricNotFound);
+        }
+
+        let rule = CircuitBreakerRule {
+            target: target.clone(),
+            error_metric,
+            max_error_rate,
+            window,
+            min_operations,
+            tripped: false,
+            tripped_at: None,
+        };
+        env.storage().persistent().set(&(CIRCUIT_BREAKER, target.clone()), &rule);
+        env.storage().persistent().remove(&(BREAKER_WINDOW, target.clone()));
+
+        env.events().publish((symbol_short!("brk_set"), target), (max_error_rate, window));
+
+        Ok(())
+    }
+
+    /// Remove a contract's circuit breaker rule (admin only)
+    pub fn remove_circuit_breaker(env: Env, admin: Address, target: Address) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().persistent().get(&ADMIN).ok_or(ContractError::NotInitialized)?;
+        if admin != stored_admin {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let key = (CIRCUIT_BREAKER, target.clone());
+        if !env.storage().persistent().has(&key) {
+            return Err(ContractError::NotFound);
+        }
+        env.storage().persistent().remove(&key);
+        env.storage().persistent().remove(&(BREAKER_WINDOW, target.clone()));
+
+        env.events().publish((symbol_short!("brk_rm"), target), ());
+
+        Ok(())
+    }
+
+    /// Re-arm a tripped circuit breaker and unpause its target (admin only).
+    /// The rule is reset even if the target rejects the unpause.
+    pub fn reset_circuit_breaker(env: Env, admin: Address, target: Address) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        require_admin(&env, &admin)?;
+
+        let key = (CIRCUIT_BREAKER, target.clone());
+        let mut rule: CircuitBreakerRule = env.storage().persistent().get(&key).ok_or(ContractError::NotFound)?;
+        if !rule.tripped {
+            return Err(ContractError::InvalidState);
+        }
+
+        rule.tripped = false;
+        rule.tripped_at = None;
+        env.storage().persistent().set(&key, &rule);
+        env.storage().persistent().remove(&(BREAKER_WINDOW, target.clone()));
+
+        let unpaused = PausableClient::new(&env, &target).try_set_paused(&env.current_contract_address(), &false);
+
+        env.events().publish((symbol_short!("brk_rst"), target), matches!(unpaused, Ok(Ok(()))));
+
+        Ok(())
+    }
+
+    /// Create an alert rule. Retrying with the same `idempotency_key`
+    /// returns the originally created id.
     pub fn create_alert_rule(
         env: Env,
         admin: Address,
@@ -356,11 +727,16 @@ impl PerformanceMonitoringContract {
         min_data_points: u32,
         severity: Symbol,
         cooldown_period: u64,
+        idempotency_key: Option<BytesN<32>>,
     ) -> Result<u64, ContractError> {
         admin.require_auth();
 
         require_admin(&env, &admin)?;
 
+        if let Some(rule_id) = idempotent_id(&env, symbol_short!("rule"), &admin, &idempotency_key) {
+            return Ok(rule_id);
+        }
+
         // Validate condition
         let condition_str = condition.to_string();
         if !["gt", "lt", "eq", "gte", "lte"].contains(&condition_str.as_str()) {
@@ -394,6 +770,7 @@ impl PerformanceMonitoringContract {
         env.storage()
             .persistent()
             .set(&(ALERT_RULE, rule_id), &rule);
+        remember_idempotent_id(&env, symbol_short!("rule"), &admin, &idempotency_key, rule_id);
 
         env.events().publish(
             (symbol_short!("alert_rule_created"), rule_name),
@@ -403,7 +780,30 @@ impl PerformanceMonitoringContract {
         Ok(rule_id)
     }
 
-    /// Create a dashboard
+    /// Dry-run an alert rule against sample data, returning whether it would
+    /// fire. The current value plus `historical_data` count toward the rule's
+    /// minimum data points; no alert is recorded and nothing is notified.
+    pub fn test_alert_rule(
+        env: Env,
+        rule_id: u64,
+        current_value: u64,
+        historical_data: Vec<u64>,
+    ) -> Result<bool, ContractError> {
+        let rule: AlertRule = env
+            .storage()
+            .persistent()
+            .get(&(ALERT_RULE, rule_id))
+            .ok_or(ContractError::NotFound)?;
+
+        if historical_data.len() + 1 < rule.min_data_points {
+            return Ok(false);
+        }
+
+        Ok(evaluate_alert_condition(rule.condition, current_value, rule.threshold))
+    }
+
+    /// Create a dashboard. Retrying with the same `idempotency_key`
+    /// returns the originally created id.
     pub fn create_dashboard(
         env: Env,
         owner: Address,
@@ -413,6 +813,7 @@ impl PerformanceMonitoringContract {
         time_range: u64,
         refresh_interval: u64,
         is_public: bool,
+        idempotency_key: Option<BytesN<32>>,
     ) -> Result<u64, ContractError> {
         owner.require_auth();
 
@@ -420,6 +821,10 @@ impl PerformanceMonitoringContract {
             return Err(ContractError::Paused);
         }
 
+        if let Some(dashboard_id) = idempotent_id(&env, symbol_short!("dash"), &owner, &idempotency_key) {
+            return Ok(dashboard_id);
+        }
+
         let dashboard_id = get_next_dashboard_id(&env);
 
         let dashboard = DashboardConfig {
@@ -438,6 +843,8 @@ impl PerformanceMonitoringContract {
         env.storage()
             .persistent()
             .set(&(DASHBOARD_CONFIG, dashboard_id), &dashboard);
+        AppendIndex::new(&env, OWNER_DASHBOARDS, owner.clone()).push(&env, &dashboard_id);
+        remember_idempotent_id(&env, symbol_short!("dash"), &owner, &idempotency_key, dashboard_id);
 
         env.events().publish(
             (symbol_short!("dashboard_created"), owner),
@@ -495,6 +902,25 @@ impl PerformanceMonitoringContract {
         })
     }
 
+    /// Get the stored performance summaries for several contracts in one
+    /// call, skipping contracts with no recorded metrics
+    pub fn get_cross_contract_summary(
+        env: Env,
+        contracts: Vec<Address>,
+    ) -> Vec<ContractPerformanceSummary> {
+        let mut summaries = Vec::new(&env);
+        for contract_address in contracts.iter() {
+            if let Some(summary) = env
+                .storage()
+                .persistent()
+                .get::<_, ContractPerformanceSummary>(&(CONTRACT_METRICS, contract_address))
+            {
+                summaries.push_back(summary);
+            }
+        }
+        summaries
+    }
+
     /// Get time series data for a metric
     pub fn get_time_series_data(
         env: Env,
@@ -517,7 +943,8 @@ impl PerformanceMonitoringContract {
         Ok(Vec::new(&env))
     }
 
-    /// Acknowledge an alert
+    /// Acknowledge an alert. The acknowledgement lapses after the ack
+    /// expiry window unless the alert is dealt with first.
     pub fn acknowledge_alert(
         env: Env,
         user: Address,
@@ -525,32 +952,87 @@ impl PerformanceMonitoringContract {
     ) -> Result<(), ContractError> {
         user.require_auth();
 
-        let mut alert: AlertRecord = env
-            .storage()
-            .persistent()
-            .get(&(ALERT_HISTORY, alert_id))
-            .ok_or(ContractError::NotFound)?;
+        Self::apply_acknowledgement(&env, &user, alert_id)
+    }
 
-        if alert.acknowledged {
-            return Err(ContractError::InvalidState);
+    /// Acknowledge up to `MAX_ALERT_BATCH` alerts at once. Missing or
+    /// already-acknowledged alerts are skipped rather than aborting the
+    /// batch. Returns how many alerts were acknowledged.
+    pub fn acknowledge_alerts(
+        env: Env,
+        user: Address,
+        alert_ids: Vec<u64>,
+    ) -> Result<u32, ContractError> {
+        user.require_auth();
+
+        if alert_ids.len() > MAX_ALERT_BATCH {
+            return Err(ContractError::InvalidInput);
         }
 
-        alert.acknowledged = true;
-        alert.acknowledged_by = Some(user.clone());
-        alert.acknowledged_at = Some(env.ledger().timestamp());
+        let mut acknowledged = 0u32;
+        for alert_id in alert_ids.iter() {
+            match Self::apply_acknowledgement(&env, &user, alert_id) {
+                Ok(()) => acknowledged += 1,
+                Err(ContractError::NotFound) | Err(ContractError::InvalidState) => continue,
+                Err(err) => return Err(err),
+            }
+        }
 
-        env.storage()
-            .persistent()
-            .set(&(ALERT_HISTORY, alert_id), &alert);
+        Ok(acknowledged)
+    }
 
-        env.events().publish(
-            (symbol_short!("alert_acknowledged"), user),
-            alert_id,
-        );
+    /// Set how long an acknowledgement lasts before the alert re-activates (admin only)
+    pub fn set_ack_expiry(env: Env, admin: Address, seconds: u64) -> Result<(), ContractError> {
+        admin.require_auth();
+
+        require_admin(&env, &admin)?;
+
+        if seconds == 0 {
+            return Err(ContractError::InvalidInput);
+        }
+
+        env.storage().persistent().set(&ACK_EXPIRY, &seconds);
+
+        env.events().publish((symbol_short!("ack_exp"), admin), seconds);
 
         Ok(())
     }
 
+    /// Keeper entry point: re-activate acknowledged alerts whose
+    /// acknowledgement has lapsed, checking at most `MAX_ALERT_BATCH` of
+    /// the oldest acknowledgements per call. Returns how many re-activated.
+    pub fn poll_ack_expiries(env: Env) -> u32 {
+        let now = env.ledger().timestamp();
+        let index = AppendIndex::<u64>::new(&env, ACKED_ALERTS, ());
+
+        let mut reactivated = 0u32;
+        for alert_id in index.page(&env, 0, MAX_ALERT_BATCH).iter() {
+            let mut alert: AlertRecord = match env.storage().persistent().get(&(ALERT_HISTORY, alert_id)) {
+                Some(alert) => alert,
+                None => {
+                    index.remove(&env, &alert_id);
+                    continue;
+                }
+            };
+            match alert.ack_expires_at {
+                Some(expires_at) if expires_at <= now => {}
+                _ => continue,
+            }
+
+            alert.acknowledged = false;
+            alert.acknowledged_by = None;
+            alert.acknowledged_at = None;
+            alert.ack_expires_at = None;
+            env.storage().persistent().set(&(ALERT_HISTORY, alert_id), &alert);
+            index.remove(&env, &alert_id);
+            reactivated += 1;
+
+            env.events().publish((symbol_short!("alert_rea"), alert.contract_address), alert_id);
+        }
+
+        reactivated
+    }
+
     /// Update dashboard configuration
     pub fn update_dashboard(
         env: Env,
@@ -665,6 +1147,93 @@ impl PerformanceMonitoringContract {
         Ok(())
     }
 
+    /// Count the metric against the target's breaker window and pause the
+    /// target once the window's error rate exceeds the rule's threshold
+    fn check_circuit_breaker(env: &Env, metric: &PerformanceMetric) {
+        let key = (CIRCUIT_BREAKER, metric.contract_address.clone());
+        let mut rule: CircuitBreakerRule = match env.storage().persistent().get(&key) {
+            Some(rule) => rule,
+            None => return,
+        };
+        if rule.tripped {
+            return;
+        }
+
+        let window_key = (BREAKER_WINDOW, metric.contract_address.clone());
+        let mut window: ErrorRateWindow = env
+            .storage()
+            .persistent()
+            .get(&window_key)
+            .unwrap_or(ErrorRateWindow { window_start: metric.timestamp, operations: 0, errors: 0 });
+        if metric.timestamp >= window.window_start + rule.window {
+            window = ErrorRateWindow { window_start: metric.timestamp, operations: 0, errors: 0 };
+        }
+
+        window.operations += 1;
+        if metric.metric_name == rule.error_metric {
+            window.errors += 1;
+        }
+        env.storage().persistent().set(&window_key, &window);
+
+        let error_rate = window.errors * 100 / window.operations;
+
+        let summary_key = (CONTRACT_METRICS, metric.contract_address.clone());
+        if let Some(mut summary) = env.storage().persistent().get::<_, ContractPerformanceSummary>(&summary_key) {
+            summary.error_rate = error_rate;
+            summary.performance_score = Self::calculate_performance_score(&summary);
+            env.storage().persistent().set(&summary_key, &summary);
+        }
+
+        if window.operations < rule.min_operations || error_rate <= rule.max_error_rate {
+            return;
+        }
+
+        // Stay tripped even if the pause is rejected so a failing target
+        // isn't re-invoked on every metric until the admin resets the rule
+        rule.tripped = true;
+        rule.tripped_at = Some(metric.timestamp);
+        env.storage().persistent().set(&key, &rule);
+
+        let paused = PausableClient::new(env, &rule.target).try_set_paused(&env.current_contract_address(), &true);
+        if matches!(paused, Ok(Ok(()))) {
+            env.events().publish((symbol_short!("brk_trip"), rule.target), error_rate);
+        } else {
+            env.events().publish((symbol_short!("brk_fail"), rule.target), error_rate);
+        }
+    }
+
+    /// Mark an alert acknowledged by `user` and track it for ack expiry
+    fn apply_acknowledgement(env: &Env, user: &Address, alert_id: u64) -> Result<(), ContractError> {
+        let mut alert: AlertRecord = env
+            .storage()
+            .persistent()
+            .get(&(ALERT_HISTORY, alert_id))
+            .ok_or(ContractError::NotFound)?;
+
+        if alert.acknowledged {
+            return Err(ContractError::InvalidState);
+        }
+
+        let now = env.ledger().timestamp();
+        let ack_expiry: u64 = env.storage().persistent().get(&ACK_EXPIRY).unwrap_or(DEFAULT_ACK_EXPIRY);
+        alert.acknowledged = true;
+        alert.acknowledged_by = Some(user.clone());
+        alert.acknowledged_at = Some(now);
+        alert.ack_expires_at = Some(now + ack_expiry);
+
+        env.storage()
+            .persistent()
+            .set(&(ALERT_HISTORY, alert_id), &alert);
+        AppendIndex::new(env, ACKED_ALERTS, ()).push(env, &alert_id);
+
+        env.events().publish(
+            (symbol_short!("alert_acknowledged"), user.clone()),
+            alert_id,
+        );
+
+        Ok(())
+    }
+
     /// Calculate performance score (0-100)
     fn calculate_performance_score(summary: &ContractPerformanceSummary) -> u32 {
         // Simple scoring algorithm - can be made more sophisticated
@@ -677,11 +1246,43 @@ impl PerformanceMonitoringContract {
 
     // ===== View Functions =====
 
+    /// Health check for monitoring: (initialized, paused, schema version)
+    pub fn health(env: Env) -> (bool, bool, u32) {
+        (env.storage().persistent().has(&ADMIN), is_paused(&env), SCHEMA_VERSION)
+    }
+
     /// Get performance metric
     pub fn get_performance_metric(env: Env, metric_id: u64) -> Option<PerformanceMetric> {
         env.storage().persistent().get(&(PERFORMANCE_METRIC, metric_id))
     }
 
+    /// Get the write policy for a metric
+    pub fn get_metric_policy(env: Env, metric_name: Symbol) -> Option<MetricPolicy> {
+        env.storage().persistent().get(&(METRIC_POLICY, metric_name))
+    }
+
+    /// Get the downsampled bucket starting at `bucket_start`
+    pub fn get_metric_bucket(
+        env: Env,
+        contract_address: Address,
+        metric_name: Symbol,
+        bucket_start: u64,
+    ) -> Option<MetricBucket> {
+        env.storage()
+            .persistent()
+            .get(&(METRIC_BUCKET, contract_address, metric_name, bucket_start))
+    }
+
+    /// List all registered metric definitions
+    pub fn list_registered_metrics(env: Env) -> Vec<MetricDefinition> {
+        metrics::list_registered_metrics(&env)
+    }
+
+    /// Get a contract's circuit breaker rule
+    pub fn get_circuit_breaker(env: Env, target: Address) -> Option<CircuitBreakerRule> {
+        env.storage().persistent().get(&(CIRCUIT_BREAKER, target))
+    }
+
     /// Get alert rule
     pub fn get_alert_rule(env: Env, rule_id: u64) -> Option<AlertRule> {
         env.storage().persistent().get(&(ALERT_RULE, rule_id))
@@ -692,16 +1293,26 @@ impl PerformanceMonitoringContract {
         env.storage().persistent().get(&(ALERT_HISTORY, alert_id))
     }
 
+    /// Get the acknowledgement expiry window (seconds)
+    pub fn get_ack_expiry(env: Env) -> u64 {
+        env.storage().persistent().get(&ACK_EXPIRY).unwrap_or(DEFAULT_ACK_EXPIRY)
+    }
+
     /// Get dashboard configuration
     pub fn get_dashboard_config(env: Env, dashboard_id: u64) -> Option<DashboardConfig> {
         env.storage().persistent().get(&(DASHBOARD_CONFIG, dashboard_id))
     }
 
-    /// Get all dashboards for an owner
-    pub fn get_dashboards_for_owner(env: Env, owner: Address) -> Vec<DashboardConfig> {
-        // In production, maintain an index for efficient querying
-        // For now, return empty vector
-        Vec::new(&env)
+    /// Get up to `limit` dashboards for an owner, oldest first, skipping `offset`
+    pub fn get_dashboards_for_owner(env: Env, owner: Address, offset: u32, limit: u32) -> Vec<DashboardConfig> {
+        let ids: Vec<u64> = AppendIndex::new(&env, OWNER_DASHBOARDS, owner).page(&env, offset, limit);
+        let mut dashboards = Vec::new(&env);
+        for dashboard_id in ids.iter() {
+            if let Some(dashboard) = env.storage().persistent().get(&(DASHBOARD_CONFIG, dashboard_id)) {
+                dashboards.push_back(dashboard);
+            }
+        }
+        dashboards
     }
 
     /// Get active alerts for a contract
@@ -719,5 +1330,11 @@ impl PerformanceMonitoringContract {
     }
 }
 
+// Standard pause interface exposed by monitored contracts
+#[contractclient(name = "PausableClient")]
+pub trait PausableInterface {
+    fn set_paused(env: Env, admin: Address, paused: bool);
+}
+
 #[cfg(test)]
 mod tests;
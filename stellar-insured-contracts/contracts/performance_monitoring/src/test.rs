@@ -4,12 +4,40 @@ use soroban_sdk::{
 };
 use performance_monitoring::{
     PerformanceMetric, AlertRule, AlertRecord, DashboardConfig, DashboardMetric,
-    PerformanceMonitoringContract, ContractError,
+    PerformanceMonitoringContract, PerformanceMonitoringContractClient, ContractError,
+    MetricPolicy,
 };
 
 #[contract]
 pub struct PerformanceMonitoringTest;
 
+/// Monitored contract exposing the standard pause interface
+#[contract]
+pub struct MockPausableTarget;
+
+#[contractimpl]
+impl MockPausableTarget {
+    pub fn set_paused(env: Env, admin: Address, paused: bool) {
+        admin.require_auth();
+        env.storage().instance().set(&symbol_short!("PAUSED"), &paused);
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().get(&symbol_short!("PAUSED")).unwrap_or(false)
+    }
+}
+
+/// Monitored contract that rejects every pause request
+#[contract]
+pub struct MockUnpausableTarget;
+
+#[contractimpl]
+impl MockUnpausableTarget {
+    pub fn set_paused(_env: Env, _admin: Address, _paused: bool) {
+        panic!("not the pause admin");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -22,6 +50,28 @@ mod tests {
         (env, admin)
     }
 
+    /// Store an unacknowledged alert record; nothing raises alerts yet
+    fn seed_alert(env: &Env, contract_id: &Address, alert_id: u64) {
+        let alert = AlertRecord {
+            alert_id,
+            rule_id: 1,
+            contract_address: Address::generate(env),
+            metric_name: Symbol::new(env, "error"),
+            severity: Symbol::new(env, "high"),
+            message: String::from_str(env, "error rate above threshold"),
+            actual_value: 30,
+            threshold_value: 20,
+            timestamp: env.ledger().timestamp(),
+            acknowledged: false,
+            acknowledged_by: None,
+            acknowledged_at: None,
+            ack_expires_at: None,
+        };
+        env.as_contract(contract_id, || {
+            env.storage().persistent().set(&(Symbol::new(env, "ALERT_HIST"), alert_id), &alert);
+        });
+    }
+
     #[test]
     fn test_initialize() {
         let (env, admin) = setup_test_env();
@@ -49,6 +99,14 @@ mod tests {
         let operation = Symbol::new(&env, "mint_policy");
         let metadata = Map::new(&env);
 
+        PerformanceMonitoringContract::register_metric(
+            env.clone(),
+            contract_id,
+            admin.clone(),
+            metric_name.clone(),
+            unit.clone(),
+        ).unwrap();
+
         let metric_id = PerformanceMonitoringContract::record_metric(
             env.clone(),
             contract_id,
@@ -97,6 +155,7 @@ mod tests {
             min_data_points,
             severity,
             300u64, // cooldown period
+            None,
         ).unwrap();
 
         // Verify alert rule was created
@@ -149,6 +208,7 @@ mod tests {
             time_range,
             refresh_interval,
             is_public,
+            None,
         ).unwrap();
 
         // Verify dashboard was created
@@ -247,6 +307,67 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_acknowledge_alerts_skips_handled_alerts() {
+        let (env, admin) = setup_test_env();
+        let contract_id = env.register_contract(None, PerformanceMonitoringContract);
+        let client = PerformanceMonitoringContractClient::new(&env, &contract_id);
+        client.initialize(&admin);
+
+        for alert_id in 1..=3u64 {
+            seed_alert(&env, &contract_id, alert_id);
+        }
+        let user = Address::generate(&env);
+        client.acknowledge_alert(&user, &2);
+
+        // Alert 2 is already acknowledged and alert 9 doesn't exist
+        let acknowledged = client.acknowledge_alerts(&user, &Vec::from_array(&env, [1u64, 2, 3, 9]));
+        assert_eq!(acknowledged, 2);
+        for alert_id in 1..=3u64 {
+            let alert = client.get_alert_record(&alert_id).unwrap();
+            assert!(alert.acknowledged);
+            assert_eq!(alert.acknowledged_by, Some(user.clone()));
+        }
+
+        let mut too_many = Vec::new(&env);
+        for alert_id in 0..51u64 {
+            too_many.push_back(alert_id);
+        }
+        assert_eq!(client.try_acknowledge_alerts(&user, &too_many), Err(Ok(ContractError::InvalidInput)));
+    }
+
+    #[test]
+    fn test_lapsed_acknowledgement_reactivates_alert() {
+        let (env, admin) = setup_test_env();
+        let contract_id = env.register_contract(None, PerformanceMonitoringContract);
+        let client = PerformanceMonitoringContractClient::new(&env, &contract_id);
+        client.initialize(&admin);
+        client.set_ack_expiry(&admin, &3_600);
+
+        seed_alert(&env, &contract_id, 1);
+        seed_alert(&env, &contract_id, 2);
+        let user = Address::generate(&env);
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        client.acknowledge_alert(&user, &1);
+        env.ledger().with_mut(|li| li.timestamp = 2_000);
+        client.acknowledge_alert(&user, &2);
+        assert_eq!(client.get_alert_record(&1).unwrap().ack_expires_at, Some(4_600));
+
+        // Only the first acknowledgement has lapsed
+        env.ledger().with_mut(|li| li.timestamp = 4_600);
+        assert_eq!(client.poll_ack_expiries(), 1);
+        let alert = client.get_alert_record(&1).unwrap();
+        assert!(!alert.acknowledged);
+        assert_eq!(alert.acknowledged_by, None);
+        assert_eq!(alert.ack_expires_at, None);
+        assert!(client.get_alert_record(&2).unwrap().acknowledged);
+        assert_eq!(client.poll_ack_expiries(), 0);
+
+        // A re-activated alert can be acknowledged again
+        client.acknowledge_alert(&user, &1);
+        assert_eq!(client.get_alert_record(&1).unwrap().ack_expires_at, Some(8_200));
+    }
+
     #[test]
     fn test_update_dashboard() {
         let (env, admin) = setup_test_env();
@@ -272,6 +393,7 @@ mod tests {
             time_range,
             refresh_interval,
             is_public,
+            None,
         ).unwrap();
 
         // Update dashboard
@@ -320,6 +442,13 @@ mod tests {
         let contract_id = env.register_contract(None, PerformanceMonitoringContract);
         
         PerformanceMonitoringContract::initialize(env.clone(), contract_id, admin.clone());
+        PerformanceMonitoringContract::register_metric(
+            env.clone(),
+            contract_id,
+            admin.clone(),
+            Symbol::new(&env, "test_metric"),
+            Symbol::new(&env, "count"),
+        ).unwrap();
         
         // Pause contract
         PerformanceMonitoringContract::set_paused(env.clone(), contract_id, admin.clone(), true).unwrap();
@@ -447,6 +576,7 @@ mod tests {
             min_data_points,
             severity,
             300u64,
+            None,
         );
 
         // Should fail due to invalid condition
@@ -473,6 +603,7 @@ mod tests {
             3600u64,
             60u64,
             false,
+            None,
         ).unwrap();
 
         // Try to update dashboard with unauthorized user
@@ -507,6 +638,14 @@ mod tests {
         let operation = Symbol::new(&env, "test_operation");
         let metadata = Map::new(&env);
 
+        PerformanceMonitoringContract::register_metric(
+            env.clone(),
+            contract_id,
+            admin.clone(),
+            metric_name.clone(),
+            unit.clone(),
+        ).unwrap();
+
         // Test recording valid metric
         let result = PerformanceMonitoringContract::record_metric(
             env.clone(),
@@ -575,4 +714,293 @@ mod tests {
         // Should fail due to invalid limit
         assert_eq!(result, Err(ContractError::InvalidInput));
     }
+
+    #[test]
+    fn test_unregistered_metric_rejected() {
+        let (env, admin) = setup_test_env();
+        let contract_id = env.register_contract(None, PerformanceMonitoringContract);
+        let client = PerformanceMonitoringContractClient::new(&env, &contract_id);
+        client.initialize(&admin);
+
+        let contract_address = Address::generate(&env);
+        let gas = Symbol::new(&env, "gas");
+        let operation = Symbol::new(&env, "mint_policy");
+
+        client.register_metric(&admin, &Symbol::new(&env, "gas_used"), &gas);
+
+        // A typo in the metric name no longer creates an orphan series
+        let result = client.try_record_metric(
+            &contract_address,
+            &Symbol::new(&env, "gas_usd"),
+            &100,
+            &gas,
+            &operation,
+            &Map::new(&env),
+        );
+        assert_eq!(result, Err(Ok(ContractError::MetricNotFound)));
+
+        let metric_id = client.record_metric(
+            &contract_address,
+            &Symbol::new(&env, "gas_used"),
+            &100,
+            &gas,
+            &operation,
+            &Map::new(&env),
+        );
+        assert_eq!(client.get_performance_metric(&metric_id).unwrap().value, 100);
+
+        let registered = client.list_registered_metrics();
+        assert_eq!(registered.len(), 1);
+        assert_eq!(registered.get(0).unwrap().unit, gas);
+    }
+
+    #[test]
+    fn test_get_cross_contract_summary() {
+        let (env, admin) = setup_test_env();
+        let contract_id = env.register_contract(None, PerformanceMonitoringContract);
+        let client = PerformanceMonitoringContractClient::new(&env, &contract_id);
+        client.initialize(&admin);
+
+        let gas = Symbol::new(&env, "gas");
+        let gas_used = Symbol::new(&env, "gas_used");
+        let operation = Symbol::new(&env, "mint_policy");
+        client.register_metric(&admin, &gas_used, &gas);
+
+        let first = Address::generate(&env);
+        let second = Address::generate(&env);
+        let unknown = Address::generate(&env);
+
+        client.record_metric(&first, &gas_used, &100, &gas, &operation, &Map::new(&env));
+        client.record_metric(&second, &gas_used, &300, &gas, &operation, &Map::new(&env));
+
+        let contracts = Vec::from_array(&env, [first.clone(), unknown, second.clone()]);
+        let summaries = client.get_cross_contract_summary(&contracts);
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries.get(0).unwrap().contract_address, first);
+        assert_eq!(summaries.get(0).unwrap().total_gas_consumed, 100);
+        assert_eq!(summaries.get(1).unwrap().contract_address, second);
+        assert_eq!(summaries.get(1).unwrap().total_gas_consumed, 300);
+    }
+
+    #[test]
+    fn test_downsample_policy_bounds_storage() {
+        let (env, admin) = setup_test_env();
+        let contract_id = env.register_contract(None, PerformanceMonitoringContract);
+        let client = PerformanceMonitoringContractClient::new(&env, &contract_id);
+        client.initialize(&admin);
+
+        let gas = Symbol::new(&env, "gas");
+        let gas_used = Symbol::new(&env, "gas_used");
+        let operation = Symbol::new(&env, "mint_policy");
+        let minute = Symbol::new(&env, "minute");
+        client.register_metric(&admin, &gas_used, &gas);
+
+        let result = client.try_set_metric_policy(&admin, &gas_used, &false, &Symbol::new(&env, "fortnight"));
+        assert_eq!(result, Err(Ok(ContractError::InvalidInput)));
+
+        client.set_metric_policy(&admin, &gas_used, &false, &minute);
+        assert_eq!(
+            client.get_metric_policy(&gas_used),
+            Some(MetricPolicy { keep_raw: false, bucket_granularity: minute.clone() })
+        );
+
+        // 240 writes, two per second, across two one-minute buckets
+        let contract_address = Address::generate(&env);
+        env.ledger().with_mut(|li| li.timestamp = 600);
+        let mut last_id = 0;
+        for i in 0..240u64 {
+            if i % 2 == 0 && i > 0 {
+                env.ledger().with_mut(|li| li.timestamp += 1);
+            }
+            last_id = client.record_metric(&contract_address, &gas_used, &(i + 1), &gas, &operation, &Map::new(&env));
+        }
+
+        // No raw points are persisted, only one bucket per minute
+        assert!(client.get_performance_metric(&1).is_none());
+        assert!(client.get_performance_metric(&last_id).is_none());
+
+        let first = client.get_metric_bucket(&contract_address, &gas_used, &600).unwrap();
+        assert_eq!(first.data_count, 120);
+        assert_eq!((first.min, first.max, first.sum), (1, 120, 7_260));
+        assert_eq!(first.end_time, 660);
+
+        let second = client.get_metric_bucket(&contract_address, &gas_used, &660).unwrap();
+        assert_eq!(second.data_count, 120);
+        assert_eq!((second.min, second.max), (121, 240));
+        assert!(client.get_metric_bucket(&contract_address, &gas_used, &720).is_none());
+
+        // Opting back into raw points keeps both
+        client.set_metric_policy(&admin, &gas_used, &true, &minute);
+        let metric_id = client.record_metric(&contract_address, &gas_used, &500, &gas, &operation, &Map::new(&env));
+        assert_eq!(client.get_performance_metric(&metric_id).unwrap().value, 500);
+        assert_eq!(client.get_metric_bucket(&contract_address, &gas_used, &660).unwrap().data_count, 121);
+    }
+
+    #[test]
+    fn test_circuit_breaker_pauses_target_on_error_spike() {
+        let (env, admin) = setup_test_env();
+        let contract_id = env.register_contract(None, PerformanceMonitoringContract);
+        let client = PerformanceMonitoringContractClient::new(&env, &contract_id);
+        client.initialize(&admin);
+
+        let target = env.register_contract(None, MockPausableTarget);
+        let target_client = MockPausableTargetClient::new(&env, &target);
+
+        let count = Symbol::new(&env, "count");
+        let gas = Symbol::new(&env, "gas");
+        let gas_used = Symbol::new(&env, "gas_used");
+        let error = Symbol::new(&env, "error");
+        let operation = Symbol::new(&env, "claim");
+        client.register_metric(&admin, &gas_used, &gas);
+        client.register_metric(&admin, &error, &count);
+
+        // Trip above 20% errors once ten operations are in the window
+        client.set_circuit_breaker(&admin, &target, &error, &20, &3_600, &10);
+
+        for _ in 0..10 {
+            client.record_metric(&target, &gas_used, &1_000, &gas, &operation, &Map::new(&env));
+        }
+        client.record_metric(&target, &error, &1, &count, &operation, &Map::new(&env));
+        client.record_metric(&target, &error, &1, &count, &operation, &Map::new(&env));
+
+        // 2 of 12 operations failed (16%)
+        assert!(!target_client.is_paused());
+        assert!(!client.get_circuit_breaker(&target).unwrap().tripped);
+
+        // 3 of 13 operations failed (23%)
+        client.record_metric(&target, &error, &1, &count, &operation, &Map::new(&env));
+        assert!(target_client.is_paused());
+
+        let rule = client.get_circuit_breaker(&target).unwrap();
+        assert!(rule.tripped);
+        assert_eq!(rule.tripped_at, Some(env.ledger().timestamp()));
+        let summaries = client.get_cross_contract_summary(&Vec::from_array(&env, [target.clone()]));
+        assert_eq!(summaries.get(0).unwrap().error_rate, 23);
+
+        // Resetting re-arms the rule with a fresh window and unpauses the target
+        client.reset_circuit_breaker(&admin, &target);
+        assert!(!target_client.is_paused());
+        let rule = client.get_circuit_breaker(&target).unwrap();
+        assert!(!rule.tripped);
+        assert_eq!(rule.tripped_at, None);
+        assert_eq!(
+            client.try_reset_circuit_breaker(&admin, &target),
+            Err(Ok(ContractError::InvalidState))
+        );
+
+        // Targets without a rule are never paused
+        let other = env.register_contract(None, MockPausableTarget);
+        for _ in 0..20 {
+            client.record_metric(&other, &error, &1, &count, &operation, &Map::new(&env));
+        }
+        assert!(!MockPausableTargetClient::new(&env, &other).is_paused());
+    }
+
+    #[test]
+    fn test_circuit_breaker_survives_rejected_pause() {
+        let (env, admin) = setup_test_env();
+        let contract_id = env.register_contract(None, PerformanceMonitoringContract);
+        let client = PerformanceMonitoringContractClient::new(&env, &contract_id);
+        client.initialize(&admin);
+
+        let target = env.register_contract(None, MockUnpausableTarget);
+        let count = Symbol::new(&env, "count");
+        let error = Symbol::new(&env, "error");
+        let operation = Symbol::new(&env, "claim");
+        client.register_metric(&admin, &error, &count);
+        client.set_circuit_breaker(&admin, &target, &error, &20, &3_600, &2);
+
+        // Metrics keep recording even though the target refuses to pause
+        for _ in 0..5 {
+            client.record_metric(&target, &error, &1, &count, &operation, &Map::new(&env));
+        }
+
+        let rule = client.get_circuit_breaker(&target).unwrap();
+        assert!(rule.tripped);
+        assert!(rule.tripped_at.is_some());
+
+        let outsider = Address::generate(&env);
+        assert!(client.try_reset_circuit_breaker(&outsider, &target).is_err());
+
+        client.reset_circuit_breaker(&admin, &target);
+        assert!(!client.get_circuit_breaker(&target).unwrap().tripped);
+    }
+
+    #[test]
+    fn test_health_reflects_pause_state() {
+        let (env, admin) = setup_test_env();
+        let contract_id = env.register_contract(None, PerformanceMonitoringContract);
+        let client = PerformanceMonitoringContractClient::new(&env, &contract_id);
+        assert_eq!(client.health(), (false, false, 1));
+
+        client.initialize(&admin);
+        assert_eq!(client.health(), (true, false, 1));
+
+        client.set_paused(&admin, &true);
+        assert_eq!(client.health(), (true, true, 1));
+    }
+
+    #[test]
+    fn test_alert_rule_dry_run() {
+        let (env, admin) = setup_test_env();
+        let contract_id = env.register_contract(None, PerformanceMonitoringContract);
+        let client = PerformanceMonitoringContractClient::new(&env, &contract_id);
+        client.initialize(&admin);
+
+        let rule_id = client.create_alert_rule(
+            &admin,
+            &String::from_str(&env, "High Gas"),
+            &None,
+            &Symbol::new(&env, "gas_used"),
+            &Symbol::new(&env, "gt"),
+            &1_000,
+            &300,
+            &3,
+            &Symbol::new(&env, "high"),
+            &300,
+            &None,
+        );
+        let history = Vec::from_array(&env, [900u64, 950]);
+
+        assert!(client.test_alert_rule(&rule_id, &1_500, &history));
+        assert!(!client.test_alert_rule(&rule_id, &800, &history));
+
+        // Too few samples for the rule's minimum never fires
+        assert!(!client.test_alert_rule(&rule_id, &1_500, &Vec::new(&env)));
+
+        assert_eq!(client.try_test_alert_rule(&99, &1_500, &history), Err(Ok(ContractError::NotFound)));
+    }
+
+    #[test]
+    fn test_metric_reporter_allowlist() {
+        let (env, admin) = setup_test_env();
+        let contract_id = env.register_contract(None, PerformanceMonitoringContract);
+        let client = PerformanceMonitoringContractClient::new(&env, &contract_id);
+        client.initialize(&admin);
+
+        let gas = Symbol::new(&env, "gas");
+        let gas_used = Symbol::new(&env, "gas_used");
+        let operation = Symbol::new(&env, "mint_policy");
+        client.register_metric(&admin, &gas_used, &gas);
+
+        let monitored = Address::generate(&env);
+        let relayer = Address::generate(&env);
+
+        let result = client.try_record_metric_for(&relayer, &monitored, &gas_used, &100, &gas, &operation, &Map::new(&env));
+        assert_eq!(result, Err(Ok(ContractError::Unauthorized)));
+
+        client.set_metric_reporter(&admin, &monitored, &relayer, &true);
+        let metric_id = client.record_metric_for(&relayer, &monitored, &gas_used, &100, &gas, &operation, &Map::new(&env));
+        assert_eq!(client.get_performance_metric(&metric_id).unwrap().contract_address, monitored);
+
+        // The allowlist is per contract
+        let other = Address::generate(&env);
+        let result = client.try_record_metric_for(&relayer, &other, &gas_used, &100, &gas, &operation, &Map::new(&env));
+        assert_eq!(result, Err(Ok(ContractError::Unauthorized)));
+
+        client.set_metric_reporter(&admin, &monitored, &relayer, &false);
+        let result = client.try_record_metric_for(&relayer, &monitored, &gas_used, &100, &gas, &operation, &Map::new(&env));
+        assert_eq!(result, Err(Ok(ContractError::Unauthorized)));
+    }
 }
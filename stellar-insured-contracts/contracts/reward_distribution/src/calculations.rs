@@ -2,42 +2,153 @@ use soroban_sdk::Env;
 use crate::types::*;
 use crate::errors::Error;
 
-/// Calculate rewards based on stake amount, time, and pool parameters
+/// Calculate rewards based on stake amount, time, and pool parameters, in
+/// `REWARD_PRECISION` sub-units so the caller decides how to round
 pub fn calculate_base_rewards(
     env: &Env,
     stake_amount: i128,
     stake_duration: u64,
     base_apy: u32,
-) -> i128 {
+) -> Result<i128, Error> {
     // APY in basis points (10000 = 100%)
     // Formula: (amount * apy * duration) / (365 days * 10000)
     let seconds_per_year: i128 = 31_536_000;
     let basis_points: i128 = 10_000;
+    let denominator = seconds_per_year * basis_points;
     
-    let rewards = (stake_amount * base_apy as i128 * stake_duration as i128) 
-        / (seconds_per_year * basis_points);
-    
-    rewards
+    let numerator = stake_amount
+        .checked_mul(base_apy as i128)
+        .and_then(|v| v.checked_mul(stake_duration as i128))
+        .ok_or(Error::ArithmeticOverflow)?;
+
+    // Scale the quotient and remainder separately to keep headroom
+    let whole = numerator / denominator;
+    let fraction = (numerator % denominator) * REWARD_PRECISION / denominator;
+    whole
+        .checked_mul(REWARD_PRECISION)
+        .and_then(|v| v.checked_add(fraction))
+        .ok_or(Error::ArithmeticOverflow)
+}
+
+/// Calculate the accrual window since the last claim, excluding any
+/// warm-up period that follows the initial stake
+pub fn calculate_accrual_duration(
+    stake_time: u64,
+    last_claim_time: u64,
+    reward_start_delay: u64,
+    current_time: u64,
+) -> u64 {
+    let accrual_start = stake_time.saturating_add(reward_start_delay);
+    let from = if last_claim_time > accrual_start { last_claim_time } else { accrual_start };
+    current_time.saturating_sub(from)
 }
 
 /// Apply risk adjustment to rewards
 pub fn apply_risk_adjustment(
     base_rewards: i128,
     risk_adjustment_factor: u32,
-) -> i128 {
+) -> Result<i128, Error> {
     // Risk adjustment factor in basis points (10000 = 1x, lower = higher risk premium)
     // Higher risk = higher rewards
     let inverse_factor = 20_000 - risk_adjustment_factor as i128;
-    (base_rewards * inverse_factor) / 10_000
+    mul_div(base_rewards, inverse_factor, 10_000)
 }
 
 /// Apply performance multiplier to rewards
 pub fn apply_performance_multiplier(
     rewards: i128,
     multiplier: u32,
-) -> i128 {
+) -> Result<i128, Error> {
     // Multiplier in basis points (10000 = 1x)
-    (rewards * multiplier as i128) / 10_000
+    mul_div(rewards, multiplier as i128, 10_000)
+}
+
+/// `(value * numerator) / denominator`, failing instead of panicking when the
+/// intermediate product overflows
+pub fn mul_div(value: i128, numerator: i128, denominator: i128) -> Result<i128, Error> {
+    value
+        .checked_mul(numerator)
+        .map(|product| product / denominator)
+        .ok_or(Error::ArithmeticOverflow)
+}
+
+/// Apply the duration bonus to `rewards` earned evenly over holding times
+/// `held_from..held_to`. The multiplier ramps linearly from 1x for a fresh
+/// stake to `1x + max_bonus_bps` once it has been held for `bonus_period`,
+/// so each second is paid at the bonus reached by then rather than the
+/// bonus at the end of the window.
+pub fn apply_duration_bonus(
+    rewards: i128,
+    held_from: u64,
+    held_to: u64,
+    max_bonus_bps: u32,
+    bonus_period: u64,
+) -> Result<i128, Error> {
+    let window = held_to.saturating_sub(held_from);
+    if max_bonus_bps == 0 || bonus_period == 0 || window == 0 {
+        return Ok(rewards);
+    }
+
+    // Bonus integrated over the window, in basis-point seconds: a triangle
+    // over the part still ramping and a rectangle over the part past it
+    let ramp_from = held_from.min(bonus_period) as u128;
+    let ramp_to = held_to.min(bonus_period) as u128;
+    let ramp = (ramp_to - ramp_from)
+        .checked_mul(ramp_to + ramp_from)
+        .and_then(|area| area.checked_mul(max_bonus_bps as u128))
+        .ok_or(Error::ArithmeticOverflow)?
+        / (2 * bonus_period as u128);
+    let flat = held_to.saturating_sub(held_from.max(bonus_period)) as u128 * max_bonus_bps as u128;
+    let bonus = i128::try_from(ramp + flat).map_err(|_| Error::ArithmeticOverflow)?;
+
+    let bonus_rewards = mul_div(rewards, bonus, window as i128 * 10_000)?;
+    rewards.checked_add(bonus_rewards).ok_or(Error::ArithmeticOverflow)
+}
+
+/// Sub-units per reward unit used while accruing, before rounding
+pub const REWARD_PRECISION: i128 = 1_000_000;
+
+/// Decimals APY-based rewards are computed in before scaling to a reward
+/// token's own precision (Stellar assets use 7)
+pub const REWARD_VALUE_DECIMALS: u32 = 7;
+
+/// Re-express `amount` from `from` decimals in `to` decimals, truncating
+/// when precision is lost
+pub fn scale_decimals(amount: i128, from: u32, to: u32) -> Result<i128, Error> {
+    if to >= from {
+        10i128
+            .checked_pow(to - from)
+            .and_then(|factor| amount.checked_mul(factor))
+            .ok_or(Error::ArithmeticOverflow)
+    } else {
+        let factor = 10i128.checked_pow(from - to).ok_or(Error::ArithmeticOverflow)?;
+        Ok(amount / factor)
+    }
+}
+
+/// Convert a reward in `REWARD_PRECISION` sub-units to whole units under
+/// `mode`, returning (whole units, sub-unit remainder to carry). Only
+/// `AccumulateDust` adds `carried_dust` and returns a non-zero remainder.
+pub fn apply_rounding(
+    scaled_rewards: i128,
+    carried_dust: i128,
+    mode: RoundingMode,
+) -> Result<(i128, i128), Error> {
+    match mode {
+        RoundingMode::Floor => Ok((scaled_rewards / REWARD_PRECISION, 0)),
+        RoundingMode::Round => {
+            let rounded = scaled_rewards
+                .checked_add(REWARD_PRECISION / 2)
+                .ok_or(Error::ArithmeticOverflow)?;
+            Ok((rounded / REWARD_PRECISION, 0))
+        }
+        RoundingMode::AccumulateDust => {
+            let total = scaled_rewards
+                .checked_add(carried_dust)
+                .ok_or(Error::ArithmeticOverflow)?;
+            Ok((total / REWARD_PRECISION, total % REWARD_PRECISION))
+        }
+    }
 }
 
 /// Calculate vested amount based on vesting schedule
@@ -45,6 +156,11 @@ pub fn calculate_vested_amount(
     env: &Env,
     schedule: &VestingSchedule,
 ) -> Result<i128, Error> {
+    // A revoked schedule keeps only what had vested, all claimable now
+    if schedule.revoked {
+        return Ok(schedule.total_amount - schedule.claimed_amount);
+    }
+
     let current_time = env.ledger().timestamp();
     
     // Check if cliff period has passed
@@ -63,62 +179,77 @@ pub fn calculate_vested_amount(
     let vested_amount = match schedule.curve {
         VestingCurve::Linear => {
             // Linear vesting
-            (schedule.total_amount * elapsed as i128) / vesting_duration as i128
+            mul_div(schedule.total_amount, elapsed as i128, vesting_duration as i128)?
         },
         VestingCurve::Stepped => {
             // Stepped vesting (25% every quarter)
             let quarters_passed = elapsed / (vesting_duration / 4);
-            (schedule.total_amount * quarters_passed as i128) / 4
+            mul_div(schedule.total_amount, quarters_passed as i128, 4)?
         },
         VestingCurve::Exponential => {
             // Exponential vesting (accelerating)
             let progress = (elapsed as i128 * 10_000) / vesting_duration as i128;
             let exponential_progress = (progress * progress) / 10_000;
-            (schedule.total_amount * exponential_progress) / 10_000
+            mul_div(schedule.total_amount, exponential_progress, 10_000)?
         },
     };
     
     Ok(vested_amount.saturating_sub(schedule.claimed_amount))
 }
 
-/// Calculate performance-based bonus multiplier
+/// Total bonus the weights must distribute (up to 1.55x)
+pub const BONUS_WEIGHT_BASE: u32 = 5_500;
+
+/// Default weights, matching the original fixed bonus tiers
+pub fn default_bonus_weights() -> BonusWeights {
+    BonusWeights {
+        utilization: 2_000,
+        claim_ratio: 1_500,
+        volatility: 1_000,
+        counterparty: 1_000,
+    }
+}
+
+/// Calculate performance bonus multiplier from metrics. Each metric earns
+/// its full weight in the top tier and half its weight in the middle tier.
 pub fn calculate_performance_bonus(
     metrics: &PerformanceMetrics,
+    weights: &BonusWeights,
 ) -> u32 {
     // Base multiplier is 10000 (1x)
     let mut multiplier: u32 = 10_000;
     
-    // High utilization bonus (up to +20%)
+    // High utilization bonus
     if metrics.utilization_rate > 8_000 {
-        multiplier += 2_000;
+        multiplier += weights.utilization;
     } else if metrics.utilization_rate > 6_000 {
-        multiplier += 1_000;
+        multiplier += weights.utilization / 2;
     }
     
-    // Low claim ratio bonus (up to +15%)
+    // Low claim ratio bonus
     if metrics.claim_ratio < 1_000 {
-        multiplier += 1_500;
+        multiplier += weights.claim_ratio;
     } else if metrics.claim_ratio < 2_000 {
-        multiplier += 750;
+        multiplier += weights.claim_ratio / 2;
     }
     
-    // Low volatility bonus (up to +10%)
+    // Low volatility bonus
     if metrics.volatility_score < 2_000 {
-        multiplier += 1_000;
+        multiplier += weights.volatility;
     } else if metrics.volatility_score < 4_000 {
-        multiplier += 500;
+        multiplier += weights.volatility / 2;
     }
     
-    // Low counterparty risk bonus (up to +10%)
+    // Low counterparty risk bonus
     if metrics.counterparty_risk < 2_000 {
-        multiplier += 1_000;
+        multiplier += weights.counterparty;
     } else if metrics.counterparty_risk < 4_000 {
-        multiplier += 500;
+        multiplier += weights.counterparty / 2;
     }
     
     // Cap at 1.55x (15500)
-    if multiplier > 15_500 {
-        multiplier = 15_500;
+    if multiplier > 10_000 + BONUS_WEIGHT_BASE {
+        multiplier = 10_000 + BONUS_WEIGHT_BASE;
     }
     
     multiplier
@@ -160,6 +291,52 @@ pub fn calculate_emission_adjustment(
     }
 }
 
+/// Calculate the scheduled emission rate at a timestamp after applying
+/// every halving that has elapsed since the schedule started
+pub fn calculate_effective_emission_rate(schedule: &EmissionSchedule, timestamp: u64) -> i128 {
+    if timestamp < schedule.start_time || schedule.halving_interval == 0 {
+        return 0;
+    }
+
+    let halvings = (timestamp - schedule.start_time) / schedule.halving_interval;
+    if halvings >= 127 {
+        return 0;
+    }
+
+    schedule.initial_rate >> halvings
+}
+
+/// Calculate total tokens emitted by a schedule between two timestamps,
+/// summing each halving segment at its own rate
+pub fn calculate_scheduled_emission(schedule: &EmissionSchedule, from: u64, to: u64) -> Result<i128, Error> {
+    let mut cursor = if from > schedule.start_time { from } else { schedule.start_time };
+    let mut emitted: i128 = 0;
+
+    while cursor < to {
+        let rate = calculate_effective_emission_rate(schedule, cursor);
+        if rate == 0 {
+            break;
+        }
+
+        let segment = (cursor - schedule.start_time) / schedule.halving_interval;
+        let segment_end = schedule.start_time + (segment + 1) * schedule.halving_interval;
+        let end = if segment_end < to { segment_end } else { to };
+
+        emitted = rate
+            .checked_mul((end - cursor) as i128)
+            .and_then(|segment_emission| emitted.checked_add(segment_emission))
+            .ok_or(Error::ArithmeticOverflow)?;
+        cursor = end;
+    }
+
+    Ok(emitted)
+}
+
+/// Calculate the protocol fee taken from a claim
+pub fn calculate_claim_fee(amount: i128, claim_fee_bps: u32) -> Result<i128, Error> {
+    mul_div(amount, claim_fee_bps as i128, 10_000)
+}
+
 /// Calculate early withdrawal penalty
 pub fn calculate_early_withdrawal_penalty(
     amount: i128,
@@ -196,7 +373,7 @@ mod tests {
             stake_amount,
             stake_duration,
             base_apy,
-        );
+        ).unwrap();
         
         assert!(rewards > 0);
     }
@@ -206,18 +383,26 @@ mod tests {
         let base_rewards = 100_0000000;
         let risk_factor = 8_000; // Lower risk
         
-        let adjusted = apply_risk_adjustment(base_rewards, risk_factor);
+        let adjusted = apply_risk_adjustment(base_rewards, risk_factor).unwrap();
         
         // Should increase rewards for higher risk
         assert!(adjusted > base_rewards);
     }
     
+    #[test]
+    fn test_default_bonus_weights_sum_to_base() {
+        let weights = default_bonus_weights();
+        let total = weights.utilization + weights.claim_ratio + weights.volatility + weights.counterparty;
+
+        assert_eq!(total, BONUS_WEIGHT_BASE);
+    }
+
     #[test]
     fn test_performance_multiplier() {
         let rewards = 100_0000000;
         let multiplier = 12_000; // 1.2x
         
-        let result = apply_performance_multiplier(rewards, multiplier);
+        let result = apply_performance_multiplier(rewards, multiplier).unwrap();
         
         assert_eq!(result, 120_0000000);
     }
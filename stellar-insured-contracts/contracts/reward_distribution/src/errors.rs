@@ -1,3 +1,4 @@
+use shared::multisig::MultisigError;
 use soroban_sdk::contracterror;
 
 #[contracterror]
@@ -26,4 +27,33 @@ pub enum Error {
     InvalidAPY = 20,
     BatchSizeTooLarge = 21,
     InvalidPoolStatus = 22,
+    SchemaVersionMismatch = 23,
+    InvalidAmount = 24,
+    StakerNotAllowed = 25,
+    InvalidFee = 26,
+    ArithmeticOverflow = 27,
+    EmergencyCooldownActive = 28,
+    MultisigRequired = 29,
+    InvalidMultisigConfig = 30,
+    ProposalNotFound = 31,
+    ProposalAlreadyApproved = 32,
+    ProposalNotExecutable = 33,
+    PoolClosed = 34,
+    AboveMaximumStake = 35,
+    StakeTokenMismatch = 36,
+}
+
+impl From<MultisigError> for Error {
+    fn from(err: MultisigError) -> Self {
+        match err {
+            MultisigError::InvalidConfig | MultisigError::NotConfigured => Error::InvalidMultisigConfig,
+            MultisigError::NotSigner => Error::Unauthorized,
+            MultisigError::ProposalNotFound => Error::ProposalNotFound,
+            MultisigError::AlreadyApproved => Error::ProposalAlreadyApproved,
+            MultisigError::ProposalExpired
+            | MultisigError::ThresholdNotMet
+            | MultisigError::AlreadyExecuted
+            | MultisigError::ActionMismatch => Error::ProposalNotExecutable,
+        }
+    }
 }
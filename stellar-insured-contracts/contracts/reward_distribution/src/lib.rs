@@ -4,11 +4,28 @@ mod types;
 mod storage;
 mod errors;
 mod calculations;
+mod migrations;
 
-use soroban_sdk::{contract, contractimpl, Address, Env, String, Vec, token, symbol_short};
+use soroban_sdk::{contract, contractclient, contractimpl, Address, BytesN, Env, Map, String, Symbol, Vec, token, symbol_short};
+use shared::multisig::{self, MultisigConfig, MultisigProposal};
 use types::*;
 use errors::Error;
 
+/// Storage schema version written by this build of the contract
+const SCHEMA_VERSION: u32 = 13;
+
+/// Maximum APY history points retained per pool
+const MAX_APY_HISTORY: u32 = 100;
+const MAX_EMISSION_HISTORY: u32 = 50;
+const MAX_CLAIM_FEE_BPS: u32 = 5_000;
+const MAX_KYC_MULTIPLIER: u32 = 30_000;
+const MAX_DURATION_BONUS_BPS: u32 = 20_000;
+
+/// Operations that can be placed behind multisig approval
+const OP_ADJUST_EMISSION: Symbol = symbol_short!("ADJ_EMIT");
+const OP_SET_PAUSED: Symbol = symbol_short!("SET_PAUSE");
+const OP_UPGRADE: Symbol = symbol_short!("UPGRADE");
+
 #[contract]
 pub struct RewardDistribution;
 
@@ -23,6 +40,7 @@ impl RewardDistribution {
         env.storage().instance().set(&symbol_short!("ADMIN"), &admin);
         env.storage().instance().set(&symbol_short!("POOL_CNT"), &0u32);
         env.storage().instance().set(&symbol_short!("PAUSED"), &false);
+        env.storage().instance().set(&symbol_short!("SCHEMA"), &SCHEMA_VERSION);
 
         // Initialize emission config with defaults
         let emission_config = EmissionConfig {
@@ -63,7 +81,7 @@ impl RewardDistribution {
 
         let pool = RewardPool {
             pool_id,
-            name,
+            name: name.clone(),
             total_staked: 0,
             reward_tokens: Vec::new(&env),
             base_apy,
@@ -71,6 +89,16 @@ impl RewardDistribution {
             status: RewardStatus::Active,
             min_stake,
             lock_period,
+            reward_start_delay: 0,
+            allowlist_only: false,
+            claim_fee_bps: 0,
+            duration_bonus_bps: 0,
+            duration_bonus_period: 0,
+            max_rewards_per_period: 0,
+            reward_cap_period: 0,
+            emergency_cooldown: 0,
+            rounding_mode: RoundingMode::Floor,
+            max_stake_per_address: 0,
         };
 
         storage::set_pool(&env, &pool);
@@ -104,15 +132,24 @@ impl RewardDistribution {
             return Err(Error::InvalidEmissionRate);
         }
 
+        // Fall back to Stellar asset precision for tokens without metadata;
+        // admins can correct it with set_reward_token_decimals
+        let decimals = match token::Client::new(&env, &token_address).try_decimals() {
+            Ok(Ok(decimals)) => decimals,
+            _ => calculations::REWARD_VALUE_DECIMALS,
+        };
+
         let reward_token = RewardToken {
             token_address: token_address.clone(),
             emission_rate,
             total_allocated,
             total_distributed: 0,
             active: true,
+            decimals,
         };
 
         storage::set_reward_token(&env, pool_id, &reward_token);
+        storage::set_emission_adjusted_at(&env, pool_id, &token_address, env.ledger().timestamp());
         pool.reward_tokens.push_back(token_address.clone());
         storage::set_pool(&env, &pool);
 
@@ -131,37 +168,7 @@ impl RewardDistribution {
         staker.require_auth();
         Self::require_not_paused(&env)?;
 
-        let mut pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
-        
-        if pool.status != RewardStatus::Active {
-            return Err(Error::PoolPaused);
-        }
-        
-        if amount < pool.min_stake {
-            return Err(Error::BelowMinimumStake);
-        }
-
-        let current_time = env.ledger().timestamp();
-        
-        // Get or create stake position
-        let mut stake = storage::get_stake(&env, &staker, pool_id).unwrap_or(StakePosition {
-            staker: staker.clone(),
-            pool_id,
-            amount: 0,
-            stake_time: current_time,
-            last_claim_time: current_time,
-            performance_multiplier: 10_000, // Default 1x
-        });
-
-        stake.amount += amount;
-        pool.total_staked += amount;
-
-        storage::set_stake(&env, &stake);
-        storage::set_pool(&env, &pool);
-
-        env.events().publish((symbol_short!("STAKE"), pool_id), (staker, amount));
-
-        Ok(())
+        Self::credit_stake(&env, &staker, pool_id, amount)
     }
 
     /// Unstake tokens from a reward pool
@@ -184,15 +191,26 @@ impl RewardDistribution {
         let current_time = env.ledger().timestamp();
         let time_staked = current_time.saturating_sub(stake.stake_time);
 
-        // Check lock period
-        if time_staked < pool.lock_period {
+        // Check lock period; a closed pool lets everyone out
+        if time_staked < pool.lock_period && pool.status != RewardStatus::Closed {
             return Err(Error::LockPeriodNotMet);
         }
 
+        // Settle rewards earned on the full principal before reducing it
+        let (accrued_rewards, reward_dust) = Self::calculate_pending(&env, &stake, &pool, current_time)?;
+        stake.accrued_rewards = accrued_rewards;
+        stake.reward_dust = reward_dust;
+        stake.last_claim_time = current_time;
+
         stake.amount -= amount;
         pool.total_staked -= amount;
 
-        if stake.amount == 0 {
+        // The remaining principal starts a fresh holding period; rewards up to
+        // now were settled above at the old bonus
+        stake.bonus_start_time = current_time;
+
+        // Keep an emptied position around until its settled rewards are claimed
+        if stake.amount == 0 && !Self::has_unclaimed_rewards(&env, &stake, &pool) {
             storage::remove_stake(&env, &staker, pool_id);
         } else {
             storage::set_stake(&env, &stake);
@@ -205,7 +223,8 @@ impl RewardDistribution {
         Ok(())
     }
 
-    /// Emergency unstake with penalty
+    /// Emergency unstake with penalty. Only the principal is penalized;
+    /// rewards earned up to now are settled and stay claimable.
     pub fn emergency_unstake(
         env: Env,
         staker: Address,
@@ -213,7 +232,7 @@ impl RewardDistribution {
     ) -> Result<i128, Error> {
         staker.require_auth();
 
-        let stake = storage::get_stake(&env, &staker, pool_id)
+        let mut stake = storage::get_stake(&env, &staker, pool_id)
             .ok_or(Error::StakeNotFound)?;
         let mut pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
 
@@ -228,10 +247,27 @@ impl RewardDistribution {
 
         let amount_returned = stake.amount - penalty;
 
+        let (accrued_rewards, reward_dust) = Self::calculate_pending(&env, &stake, &pool, current_time)?;
+        stake.accrued_rewards = accrued_rewards;
+        stake.reward_dust = reward_dust;
+        stake.last_claim_time = current_time;
+        stake.bonus_start_time = current_time;
+
         pool.total_staked -= stake.amount;
-        storage::remove_stake(&env, &staker, pool_id);
+        stake.amount = 0;
+
+        if Self::has_unclaimed_rewards(&env, &stake, &pool) {
+            storage::set_stake(&env, &stake);
+        } else {
+            storage::remove_stake(&env, &staker, pool_id);
+        }
         storage::set_pool(&env, &pool);
 
+        if pool.emergency_cooldown > 0 {
+            let until = current_time.saturating_add(pool.emergency_cooldown);
+            storage::set_emergency_cooldown_end(&env, &staker, pool_id, until);
+        }
+
         env.events().publish(
             (symbol_short!("EMERG_OUT"), pool_id),
             (staker, amount_returned, penalty),
@@ -240,88 +276,249 @@ impl RewardDistribution {
         Ok(amount_returned)
     }
 
-    /// Claim rewards for a stake position
-    pub fn claim_rewards(
+    /// Slash a staker's position and credit the slashed amount to the pool's
+    /// treasury counter. Rewards earned before the slash are settled first.
+    pub fn slash(
         env: Env,
+        admin: Address,
         staker: Address,
         pool_id: u32,
-        token: Address,
-    ) -> Result<i128, Error> {
-        staker.require_auth();
+        amount: i128,
+        reason: String,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
 
         let mut stake = storage::get_stake(&env, &staker, pool_id)
             .ok_or(Error::StakeNotFound)?;
-        let pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
-        let mut reward_token = storage::get_reward_token(&env, pool_id, &token)
-            .ok_or(Error::TokenNotRegistered)?;
+        let mut pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
 
-        if !reward_token.active {
-            return Err(Error::NoRewardsAvailable);
+        if stake.amount < amount {
+            return Err(Error::InsufficientStake);
         }
 
+        // Rewards earned on the full principal before the slash stay with the staker
         let current_time = env.ledger().timestamp();
-        let time_since_last_claim = current_time.saturating_sub(stake.last_claim_time);
+        let (accrued_rewards, reward_dust) = Self::calculate_pending(&env, &stake, &pool, current_time)?;
+        stake.accrued_rewards = accrued_rewards;
+        stake.reward_dust = reward_dust;
+        stake.last_claim_time = current_time;
+        stake.bonus_start_time = current_time;
 
-        // Calculate base rewards
-        let base_rewards = calculations::calculate_base_rewards(
-            &env,
-            stake.amount,
-            time_since_last_claim,
-            pool.base_apy,
+        stake.amount -= amount;
+        pool.total_staked -= amount;
+
+        if stake.amount == 0 && !Self::has_unclaimed_rewards(&env, &stake, &pool) {
+            storage::remove_stake(&env, &staker, pool_id);
+        } else {
+            storage::set_stake(&env, &stake);
+        }
+
+        storage::set_pool(&env, &pool);
+
+        // The treasury balance is an accounting counter; no tokens are moved
+        let treasury_balance = storage::get_treasury_balance(&env, pool_id) + amount;
+        storage::set_treasury_balance(&env, pool_id, treasury_balance);
+
+        env.events().publish(
+            (symbol_short!("SLASH"), pool_id),
+            (staker, amount, reason),
         );
 
-        // Apply risk adjustment
-        let risk_adjusted = calculations::apply_risk_adjustment(
-            base_rewards,
-            pool.risk_adjustment_factor,
+        Ok(())
+    }
+
+    /// Claim rewards for a stake position. `caller` is the staker or the
+    /// staker's claim delegate; rewards are always paid to the staker.
+    pub fn claim_rewards(
+        env: Env,
+        caller: Address,
+        staker: Address,
+        pool_id: u32,
+        token: Address,
+    ) -> Result<i128, Error> {
+        caller.require_auth();
+        if caller != staker && storage::get_claim_delegate(&env, &staker, pool_id) != Some(caller) {
+            return Err(Error::Unauthorized);
+        }
+
+        let (payout, fee) = Self::settle_claim(&env, &staker, pool_id, &token)?;
+
+        // Transfer rewards
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &staker, &payout);
+
+        env.events().publish(
+            (symbol_short!("CLAIM"), pool_id),
+            (staker, token, payout, fee),
         );
 
-        // Apply performance multiplier
-        let final_rewards = calculations::apply_performance_multiplier(
-            risk_adjusted,
-            stake.performance_multiplier,
+        Ok(payout)
+    }
+
+    /// Claim `token` rewards from `from_pool` and stake them into `to_pool`
+    /// in one step. `to_pool` must be staked in `token` and the proceeds must
+    /// meet its entry rules; on any failure nothing is claimed or staked.
+    /// The rewards never leave the contract. Returns the amount restaked.
+    pub fn claim_and_migrate(
+        env: Env,
+        staker: Address,
+        from_pool: u32,
+        to_pool: u32,
+        token: Address,
+    ) -> Result<i128, Error> {
+        staker.require_auth();
+        Self::require_not_paused(&env)?;
+
+        if from_pool == to_pool {
+            return Err(Error::InvalidPoolStatus);
+        }
+        if storage::get_stake_token(&env, to_pool) != Some(token.clone()) {
+            return Err(Error::StakeTokenMismatch);
+        }
+
+        let (payout, fee) = Self::settle_claim(&env, &staker, from_pool, &token)?;
+        Self::credit_stake(&env, &staker, to_pool, payout)?;
+
+        env.events().publish(
+            (symbol_short!("CLM_MIG"), from_pool),
+            (staker, to_pool, token, payout, fee),
         );
 
-        if final_rewards == 0 {
-            return Err(Error::NoRewardsAvailable);
+        Ok(payout)
+    }
+
+    /// Authorize `delegate` to claim rewards on the staker's behalf
+    pub fn set_claim_delegate(
+        env: Env,
+        staker: Address,
+        pool_id: u32,
+        delegate: Address,
+    ) -> Result<(), Error> {
+        staker.require_auth();
+
+        storage::get_stake(&env, &staker, pool_id).ok_or(Error::StakeNotFound)?;
+        storage::set_claim_delegate(&env, &staker, pool_id, &delegate);
+
+        env.events().publish((symbol_short!("CLM_DLG"), pool_id), (staker, delegate));
+
+        Ok(())
+    }
+
+    /// Revoke the staker's claim delegate
+    pub fn remove_claim_delegate(env: Env, staker: Address, pool_id: u32) {
+        staker.require_auth();
+        storage::remove_claim_delegate(&env, &staker, pool_id);
+    }
+
+    /// Claim pending rewards for every active reward token in a pool. Each
+    /// token pays what claiming it alone would, except that the pool's
+    /// APY-based rewards are earned once and paid only in the first token
+    /// without an emission schedule. Anything a token can't cover is
+    /// carried to the next claim.
+    pub fn claim_all_rewards(
+        env: Env,
+        staker: Address,
+        pool_id: u32,
+    ) -> Result<Vec<(Address, i128)>, Error> {
+        staker.require_auth();
+
+        let mut stake = storage::get_stake(&env, &staker, pool_id)
+            .ok_or(Error::StakeNotFound)?;
+        let pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+
+        let current_time = env.ledger().timestamp();
+
+        // Every token must hold its reserves before any of them pays out
+        let mut reward_tokens = Vec::new(&env);
+        for token in pool.reward_tokens.iter() {
+            let reward_token = match storage::get_reward_token(&env, pool_id, &token) {
+                Some(reward_token) if reward_token.active => reward_token,
+                _ => continue,
+            };
+            if !Self::has_reserves(&env, &reward_token.token_address) {
+                return Err(Error::InsufficientRewardBalance);
+            }
+            reward_tokens.push_back(reward_token);
         }
 
-        // Check if enough rewards are available
-        let available = reward_token.total_allocated - reward_token.total_distributed;
-        if final_rewards > available {
-            return Err(Error::InsufficientRewardBalance);
+        let mut claimed = Vec::new(&env);
+        if reward_tokens.is_empty() {
+            return Ok(claimed);
         }
 
-        // Update state
-        stake.last_claim_time = current_time;
-        reward_token.total_distributed += final_rewards;
+        // Without an unscheduled token the settled rewards go with the first
+        // scheduled token only
+        let value_token = Self::value_token(&env, &pool);
+        let mut remaining = stake.clone();
+        let mut accrued_rewards: i128 = 0;
+        for mut reward_token in reward_tokens.iter() {
+            let token = reward_token.token_address.clone();
+            let scheduled = storage::get_emission_schedule(&env, pool_id, &token).is_some();
+            if !scheduled && value_token != Some(token.clone()) {
+                continue;
+            }
+
+            let (pending, _, reward_dust) =
+                Self::calculate_token_claim(&env, &remaining, &pool, &token, current_time)?;
+            if scheduled && value_token.is_none() {
+                remaining.accrued_rewards = 0;
+            }
+
+            // Pay what the token can cover, up to the pool's reward cap
+            let available = reward_token.total_allocated - reward_token.total_distributed;
+            let amount = if pending < available { pending } else { available };
+            let amount = Self::apply_reward_cap(&env, &pool, &token, amount, current_time).max(0);
+            if scheduled {
+                storage::set_held_rewards(&env, &staker, pool_id, &token, pending - amount);
+            } else {
+                accrued_rewards = calculations::scale_decimals(
+                    pending - amount,
+                    reward_token.decimals,
+                    calculations::REWARD_VALUE_DECIMALS,
+                )?;
+                stake.reward_dust = reward_dust;
+            }
+            if amount == 0 {
+                continue;
+            }
+
+            reward_token.total_distributed += amount;
+            storage::set_reward_token(&env, pool_id, &reward_token);
+
+            let payout = amount - Self::take_claim_fee(&env, &pool, &token, amount)?;
+
+            storage::add_claim_record(&env, &ClaimRecord {
+                claimer: staker.clone(),
+                pool_id,
+                token: token.clone(),
+                amount: payout,
+                timestamp: current_time,
+            });
 
-        storage::set_stake(&env, &stake);
-        storage::set_reward_token(&env, pool_id, &reward_token);
+            let token_client = token::Client::new(&env, &token);
+            token_client.transfer(&env.current_contract_address(), &staker, &payout);
 
-        // Record claim
-        let claim_record = ClaimRecord {
-            claimer: staker.clone(),
-            pool_id,
-            token: token.clone(),
-            amount: final_rewards,
-            timestamp: current_time,
-        };
-        storage::add_claim_record(&env, &claim_record);
+            claimed.push_back((token, payout));
+        }
 
-        // Transfer rewards
-        let token_client = token::Client::new(&env, &token);
-        token_client.transfer(&env.current_contract_address(), &staker, &final_rewards);
+        stake.last_claim_time = current_time;
+        stake.accrued_rewards = accrued_rewards;
+        storage::set_stake(&env, &stake);
 
         env.events().publish(
-            (symbol_short!("CLAIM"), pool_id),
-            (staker, token, final_rewards),
+            (symbol_short!("CLAIM_ALL"), pool_id),
+            (staker, claimed.len()),
         );
 
-        Ok(final_rewards)
+        Ok(claimed)
     }
 
-    /// Create a vesting schedule for rewards
+    /// Create a vesting schedule for rewards, returning its id
     pub fn create_vesting_schedule(
         env: Env,
         admin: Address,
@@ -331,7 +528,7 @@ impl RewardDistribution {
         cliff_duration: u64,
         vesting_duration: u64,
         curve: VestingCurve,
-    ) -> Result<(), Error> {
+    ) -> Result<u32, Error> {
         admin.require_auth();
         Self::require_admin(&env, &admin)?;
 
@@ -339,6 +536,9 @@ impl RewardDistribution {
             return Err(Error::InvalidVestingSchedule);
         }
 
+        let schedule_id: u32 = env.storage().instance().get(&symbol_short!("VEST_CNT")).unwrap_or(0) + 1;
+        env.storage().instance().set(&symbol_short!("VEST_CNT"), &schedule_id);
+
         let schedule = VestingSchedule {
             cliff_duration,
             vesting_duration,
@@ -346,6 +546,8 @@ impl RewardDistribution {
             start_time: env.ledger().timestamp(),
             total_amount,
             claimed_amount: 0,
+            revoked: false,
+            schedule_id,
         };
 
         storage::set_vesting(&env, &beneficiary, pool_id, &schedule);
@@ -355,7 +557,44 @@ impl RewardDistribution {
             (beneficiary, total_amount),
         );
 
-        Ok(())
+        Ok(schedule_id)
+    }
+
+    /// Revoke a vesting schedule. The portion vested so far stays claimable;
+    /// the unvested remainder is clawed back to the pool treasury. Fails if
+    /// the beneficiary's schedule is no longer the one `schedule_id` names.
+    pub fn revoke_vesting(
+        env: Env,
+        admin: Address,
+        beneficiary: Address,
+        pool_id: u32,
+        schedule_id: u32,
+    ) -> Result<i128, Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+
+        let mut schedule = storage::get_vesting(&env, &beneficiary, pool_id)
+            .ok_or(Error::InvalidVestingSchedule)?;
+        if schedule.revoked || schedule.schedule_id != schedule_id {
+            return Err(Error::InvalidVestingSchedule);
+        }
+
+        let vested = schedule.claimed_amount + calculations::calculate_vested_amount(&env, &schedule)?;
+        let clawback = schedule.total_amount - vested;
+
+        schedule.total_amount = vested;
+        schedule.revoked = true;
+        storage::set_vesting(&env, &beneficiary, pool_id, &schedule);
+
+        let treasury_balance = storage::get_treasury_balance(&env, pool_id) + clawback;
+        storage::set_treasury_balance(&env, pool_id, treasury_balance);
+
+        env.events().publish(
+            (symbol_short!("VEST_REV"), pool_id),
+            (beneficiary, vested, clawback),
+        );
+
+        Ok(clawback)
     }
 
     /// Claim vested rewards
@@ -404,6 +643,8 @@ impl RewardDistribution {
         admin.require_auth();
         Self::require_admin(&env, &admin)?;
 
+        let pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+
         let metrics = PerformanceMetrics {
             pool_id,
             utilization_rate,
@@ -415,7 +656,15 @@ impl RewardDistribution {
         storage::set_metrics(&env, &metrics);
 
         // Calculate and update performance bonus for all stakers
-        let bonus_multiplier = calculations::calculate_performance_bonus(&metrics);
+        let bonus_multiplier = calculations::calculate_performance_bonus(&metrics, &Self::bonus_weights(&env));
+
+        // Record the resulting APY for historical queries
+        let adjusted_apy = calculations::calculate_risk_adjusted_yield(
+            pool.base_apy,
+            pool.risk_adjustment_factor,
+            bonus_multiplier,
+        );
+        storage::add_apy_point(&env, pool_id, env.ledger().timestamp(), adjusted_apy, MAX_APY_HISTORY);
 
         env.events().publish(
             (symbol_short!("PERF_UPD"), pool_id),
@@ -440,7 +689,7 @@ impl RewardDistribution {
         let mut stake = storage::get_stake(&env, &staker, pool_id)
             .ok_or(Error::StakeNotFound)?;
 
-        let bonus_multiplier = calculations::calculate_performance_bonus(&metrics);
+        let bonus_multiplier = calculations::calculate_performance_bonus(&metrics, &Self::bonus_weights(&env));
         stake.performance_multiplier = bonus_multiplier;
 
         storage::set_stake(&env, &stake);
@@ -453,6 +702,37 @@ impl RewardDistribution {
         Ok(bonus_multiplier)
     }
 
+    /// Set how much each performance metric contributes to the bonus
+    /// multiplier. Weights must sum to `BONUS_WEIGHT_BASE`.
+    pub fn set_bonus_weights(
+        env: Env,
+        admin: Address,
+        weights: BonusWeights,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+
+        let total = weights.utilization
+            .checked_add(weights.claim_ratio)
+            .and_then(|total| total.checked_add(weights.volatility))
+            .and_then(|total| total.checked_add(weights.counterparty))
+            .ok_or(Error::InvalidAmount)?;
+        if total != calculations::BONUS_WEIGHT_BASE {
+            return Err(Error::InvalidPerformanceMultiplier);
+        }
+
+        env.storage().instance().set(&symbol_short!("BONUS_WT"), &weights);
+
+        env.events().publish((symbol_short!("BONUS_WT"),), total);
+
+        Ok(())
+    }
+
+    /// Get the current performance bonus weights
+    pub fn get_bonus_weights(env: Env) -> BonusWeights {
+        Self::bonus_weights(&env)
+    }
+
     /// Adjust emission rate based on inflation cap
     pub fn adjust_emission_rate(
         env: Env,
@@ -463,43 +743,126 @@ impl RewardDistribution {
     ) -> Result<i128, Error> {
         admin.require_auth();
         Self::require_admin(&env, &admin)?;
+        if multisig::is_guarded(&env, &OP_ADJUST_EMISSION) {
+            return Err(Error::MultisigRequired);
+        }
+
+        Self::adjust_token_emission(&env, pool_id, &token, total_supply)
+    }
+
+    /// Execute an approved multisig proposal to adjust a token's emission rate
+    pub fn execute_adjust_emission_rate(
+        env: Env,
+        proposal_id: u64,
+        pool_id: u32,
+        token: Address,
+        total_supply: i128,
+    ) -> Result<i128, Error> {
+        let action = multisig::action_hash(&env, &OP_ADJUST_EMISSION, (pool_id, token.clone(), total_supply));
+        multisig::execute(&env, proposal_id, &OP_ADJUST_EMISSION, &action)?;
+
+        Self::adjust_token_emission(&env, pool_id, &token, total_supply)
+    }
+
+    /// Attach a halving emission schedule to a reward token, starting now
+    pub fn set_emission_schedule(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        token: Address,
+        initial_rate: i128,
+        halving_interval: u64,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+        if multisig::is_guarded(&env, &OP_ADJUST_EMISSION) {
+            return Err(Error::MultisigRequired);
+        }
 
         let emission_config: EmissionConfig = env.storage()
             .instance()
             .get(&symbol_short!("EMISSION"))
             .unwrap();
 
-        let current_time = env.ledger().timestamp();
-        let time_elapsed = current_time.saturating_sub(emission_config.last_adjustment);
-
-        if time_elapsed < emission_config.adjustment_interval {
+        if initial_rate <= 0 || initial_rate > emission_config.max_emission_rate || halving_interval == 0 {
             return Err(Error::InvalidEmissionRate);
         }
 
         let mut reward_token = storage::get_reward_token(&env, pool_id, &token)
             .ok_or(Error::TokenNotRegistered)?;
 
-        let adjusted_rate = calculations::calculate_emission_adjustment(
-            reward_token.emission_rate,
-            total_supply,
-            emission_config.inflation_cap,
-            time_elapsed,
-        );
+        let schedule = EmissionSchedule {
+            initial_rate,
+            halving_interval,
+            start_time: env.ledger().timestamp(),
+        };
+        storage::set_emission_schedule(&env, pool_id, &token, &schedule);
 
-        reward_token.emission_rate = adjusted_rate;
+        reward_token.emission_rate = initial_rate;
         storage::set_reward_token(&env, pool_id, &reward_token);
 
-        // Update last adjustment time
-        let mut new_config = emission_config;
-        new_config.last_adjustment = current_time;
-        env.storage().instance().set(&symbol_short!("EMISSION"), &new_config);
+        env.events().publish(
+            (symbol_short!("EMIT_SCHD"), pool_id),
+            (token, initial_rate, halving_interval),
+        );
+
+        Ok(())
+    }
+
+    /// Keeper entry point: adjust every reward token in a pool whose
+    /// adjustment interval has elapsed, skipping the rest. Unavailable while
+    /// emission adjustments require multisig approval.
+    pub fn auto_adjust_all(
+        env: Env,
+        caller: Address,
+        pool_id: u32,
+        total_supply: i128,
+    ) -> Result<Vec<Address>, Error> {
+        caller.require_auth();
+        if !storage::is_keeper(&env, &caller) {
+            Self::require_admin(&env, &caller)?;
+        }
+        if multisig::is_guarded(&env, &OP_ADJUST_EMISSION) {
+            return Err(Error::MultisigRequired);
+        }
+
+        let pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+        let emission_config: EmissionConfig = env.storage()
+            .instance()
+            .get(&symbol_short!("EMISSION"))
+            .unwrap();
+        let current_time = env.ledger().timestamp();
+
+        let mut adjusted = Vec::new(&env);
+        for token in pool.reward_tokens.iter() {
+            let last_adjustment = storage::get_emission_adjusted_at(&env, pool_id, &token)
+                .unwrap_or(emission_config.last_adjustment);
+            if current_time.saturating_sub(last_adjustment) < emission_config.adjustment_interval {
+                continue;
+            }
+
+            Self::adjust_token_emission(&env, pool_id, &token, total_supply)?;
+            adjusted.push_back(token);
+        }
 
         env.events().publish(
-            (symbol_short!("EMIT_ADJ"), pool_id),
-            (token, adjusted_rate),
+            (symbol_short!("AUTO_ADJ"), pool_id),
+            (pool.reward_tokens.len(), adjusted.len()),
         );
 
-        Ok(adjusted_rate)
+        Ok(adjusted)
+    }
+
+    /// Allow or revoke an address to run keeper functions
+    pub fn set_keeper(env: Env, admin: Address, keeper: Address, enabled: bool) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+
+        storage::set_keeper(&env, &keeper, enabled);
+
+        env.events().publish((symbol_short!("KEEPER"),), (keeper, enabled));
+
+        Ok(())
     }
 
     /// Batch distribute rewards to multiple stakers
@@ -514,9 +877,7 @@ impl RewardDistribution {
         admin.require_auth();
         Self::require_admin(&env, &admin)?;
 
-        if recipients.len() != amounts.len() || recipients.len() > 100 {
-            return Err(Error::BatchSizeTooLarge);
-        }
+        Self::validate_batch(&recipients, &amounts)?;
 
         let token_client = token::Client::new(&env, &token);
         let contract_address = env.current_contract_address();
@@ -545,7 +906,26 @@ impl RewardDistribution {
         Ok(())
     }
 
-    /// Update pool status
+    /// Dry run of `batch_distribute`: validate the batch and total it
+    /// without transferring, reporting whether the contract can cover it
+    pub fn simulate_batch_distribute(
+        env: Env,
+        pool_id: u32,
+        token: Address,
+        recipients: Vec<Address>,
+        amounts: Vec<i128>,
+    ) -> Result<(i128, bool), Error> {
+        storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+
+        let total = Self::validate_batch(&recipients, &amounts)?;
+        let balance = token::Client::new(&env, &token).balance(&env.current_contract_address());
+
+        Ok((total, balance >= total))
+    }
+
+    /// Update pool status. `Closed` is terminal: the pool stops accepting
+    /// stakes and accruing rewards, while stakers can still unstake (without
+    /// the lock period) and claim what accrued before closure.
     pub fn update_pool_status(
         env: Env,
         admin: Address,
@@ -556,6 +936,12 @@ impl RewardDistribution {
         Self::require_admin(&env, &admin)?;
 
         let mut pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+        if pool.status == RewardStatus::Closed {
+            return Err(Error::PoolClosed);
+        }
+        if status == RewardStatus::Closed {
+            storage::set_pool_closed_at(&env, pool_id, env.ledger().timestamp());
+        }
         pool.status = status;
         storage::set_pool(&env, &pool);
 
@@ -564,273 +950,2716 @@ impl RewardDistribution {
         Ok(())
     }
 
-    /// Pause/unpause the contract
-    pub fn set_paused(env: Env, admin: Address, paused: bool) -> Result<(), Error> {
+    /// Merge `source_pool` into `target_pool`: every stake and reward token
+    /// moves to the target and the source is closed. Positions are settled
+    /// at the source pool's rate before moving; a staker already in the
+    /// target has both positions settled and combined.
+    pub fn merge_pools(
+        env: Env,
+        admin: Address,
+        source_pool: u32,
+        target_pool: u32,
+    ) -> Result<(), Error> {
         admin.require_auth();
         Self::require_admin(&env, &admin)?;
 
-        env.storage().instance().set(&symbol_short!("PAUSED"), &paused);
+        if source_pool == target_pool {
+            return Err(Error::InvalidPoolStatus);
+        }
 
-        env.events().publish(symbol_short!("PAUSED"), paused);
+        let mut source = storage::get_pool(&env, source_pool).ok_or(Error::PoolNotFound)?;
+        let mut target = storage::get_pool(&env, target_pool).ok_or(Error::PoolNotFound)?;
 
-        Ok(())
-    }
+        if source.status == RewardStatus::Closed || target.status == RewardStatus::Closed {
+            return Err(Error::InvalidPoolStatus);
+        }
 
-    // View functions
+        let current_time = env.ledger().timestamp();
+        for staker in storage::get_pool_stakers(&env, source_pool).iter() {
+            let stake = match storage::get_stake(&env, &staker, source_pool) {
+                Some(stake) => stake,
+                None => continue,
+            };
 
-    /// Get pool information
-    pub fn get_pool(env: Env, pool_id: u32) -> Result<RewardPool, Error> {
-        storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)
-    }
+            let merged = match storage::get_stake(&env, &staker, target_pool) {
+                Some(existing) => {
+                    let (existing_pending, existing_dust) = Self::calculate_pending(&env, &existing, &target, current_time)?;
+                    let (moved_pending, moved_dust) = Self::calculate_pending(&env, &stake, &source, current_time)?;
+                    let dust = existing_dust + moved_dust;
+
+                    StakePosition {
+                        staker: staker.clone(),
+                        pool_id: target_pool,
+                        amount: existing.amount + stake.amount,
+                        stake_time: if stake.stake_time < existing.stake_time { stake.stake_time } else { existing.stake_time },
+                        last_claim_time: current_time,
+                        performance_multiplier: existing.performance_multiplier,
+                        accrued_rewards: existing_pending
+                            .checked_add(moved_pending)
+                            .and_then(|v| v.checked_add(dust / calculations::REWARD_PRECISION))
+                            .ok_or(Error::ArithmeticOverflow)?,
+                        bonus_start_time: if stake.bonus_start_time > existing.bonus_start_time { stake.bonus_start_time } else { existing.bonus_start_time },
+                        reward_dust: dust % calculations::REWARD_PRECISION,
+                    }
+                }
+                None => {
+                    let (moved_pending, moved_dust) = Self::calculate_pending(&env, &stake, &source, current_time)?;
+
+                    StakePosition {
+                        pool_id: target_pool,
+                        last_claim_time: current_time,
+                        accrued_rewards: moved_pending,
+                        reward_dust: moved_dust,
+                        ..stake
+                    }
+                }
+            };
 
-    /// Get stake position
-    pub fn get_stake(env: Env, staker: Address, pool_id: u32) -> Result<StakePosition, Error> {
-        storage::get_stake(&env, &staker, pool_id).ok_or(Error::StakeNotFound)
-    }
+            // Rewards the source's scheduled tokens held back follow the
+            // tokens into the target
+            for token in source.reward_tokens.iter() {
+                let held = storage::get_held_rewards(&env, &staker, source_pool, &token);
+                if held > 0 {
+                    let target_held = storage::get_held_rewards(&env, &staker, target_pool, &token);
+                    storage::set_held_rewards(&env, &staker, target_pool, &token, target_held + held);
+                    storage::set_held_rewards(&env, &staker, source_pool, &token, 0);
+                }
+            }
+
+            storage::remove_stake(&env, &staker, source_pool);
+            storage::set_stake(&env, &merged);
+        }
 
-    /// Get vesting schedule
-    pub fn get_vesting(
-        env: Env,
-        beneficiary: Address,
-        pool_id: u32,
-    ) -> Result<VestingSchedule, Error> {
-        storage::get_vesting(&env, &beneficiary, pool_id)
-            .ok_or(Error::InvalidVestingSchedule)
-    }
+        for token in source.reward_tokens.iter() {
+            let mut source_token = match storage::get_reward_token(&env, source_pool, &token) {
+                Some(reward_token) => reward_token,
+                None => continue,
+            };
 
-    /// Get claimable vested amount
-    pub fn get_claimable_vested(
-        env: Env,
-        beneficiary: Address,
-        pool_id: u32,
-    ) -> Result<i128, Error> {
-        let schedule = storage::get_vesting(&env, &beneficiary, pool_id)
-            .ok_or(Error::InvalidVestingSchedule)?;
+            let target_token = match storage::get_reward_token(&env, target_pool, &token) {
+                Some(mut existing) => {
+                    existing.total_allocated += source_token.total_allocated;
+                    existing.total_distributed += source_token.total_distributed;
+                    existing
+                }
+                None => {
+                    target.reward_tokens.push_back(token.clone());
+                    storage::set_emission_adjusted_at(&env, target_pool, &token, current_time);
+                    source_token.clone()
+                }
+            };
+            storage::set_reward_token(&env, target_pool, &target_token);
 
-        calculations::calculate_vested_amount(&env, &schedule)
+            source_token.active = false;
+            storage::set_reward_token(&env, source_pool, &source_token);
+        }
+
+        let moved = source.total_staked;
+        target.total_staked += moved;
+        source.total_staked = 0;
+        source.status = RewardStatus::Closed;
+        storage::set_pool_closed_at(&env, source_pool, env.ledger().timestamp());
+
+        storage::set_pool(&env, &source);
+        storage::set_pool(&env, &target);
+
+        env.events().publish((symbol_short!("POOL_MRG"), source_pool), (target_pool, moved));
+
+        Ok(())
     }
 
-    /// Get pending rewards
-    pub fn get_pending_rewards(
+    /// Split `stakers` out of `source_pool` into a new child pool with the
+    /// same parameters. Positions keep their claim time and accrued rewards,
+    /// and the child takes over the source's entry rules: the moved
+    /// stakers' allowlist entries, the KYC multipliers and the stake token.
+    /// Reward tokens and their emission schedules are not copied; register
+    /// them on the child as needed, which also makes any rewards the
+    /// source's scheduled tokens held back claimable there.
+    pub fn split_pool(
         env: Env,
-        staker: Address,
-        pool_id: u32,
-    ) -> Result<i128, Error> {
-        let stake = storage::get_stake(&env, &staker, pool_id)
-            .ok_or(Error::StakeNotFound)?;
-        let pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+        admin: Address,
+        source_pool: u32,
+        name: String,
+        stakers: Vec<Address>,
+    ) -> Result<u32, Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
 
-        let current_time = env.ledger().timestamp();
-        let time_since_last_claim = current_time.saturating_sub(stake.last_claim_time);
+        let mut source = storage::get_pool(&env, source_pool).ok_or(Error::PoolNotFound)?;
+        if source.status == RewardStatus::Closed {
+            return Err(Error::InvalidPoolStatus);
+        }
 
-        let base_rewards = calculations::calculate_base_rewards(
-            &env,
-            stake.amount,
-            time_since_last_claim,
-            pool.base_apy,
-        );
+        // Validate every staker before moving anything
+        for staker in stakers.iter() {
+            if storage::get_stake(&env, &staker, source_pool).is_none() {
+                return Err(Error::StakeNotFound);
+            }
+        }
 
-        let risk_adjusted = calculations::apply_risk_adjustment(
-            base_rewards,
-            pool.risk_adjustment_factor,
-        );
+        let pool_count: u32 = env.storage().instance().get(&symbol_short!("POOL_CNT")).unwrap_or(0);
+        let child_id = pool_count + 1;
 
-        let final_rewards = calculations::apply_performance_multiplier(
-            risk_adjusted,
-            stake.performance_multiplier,
-        );
+        let mut child = RewardPool {
+            pool_id: child_id,
+            name: name.clone(),
+            total_staked: 0,
+            reward_tokens: Vec::new(&env),
+            ..source.clone()
+        };
 
-        Ok(final_rewards)
-    }
+        for staker in stakers.iter() {
+            let stake = match storage::get_stake(&env, &staker, source_pool) {
+                Some(stake) => stake,
+                None => continue,
+            };
 
-    /// Get performance metrics
-    pub fn get_metrics(env: Env, pool_id: u32) -> Result<PerformanceMetrics, Error> {
-        storage::get_metrics(&env, pool_id).ok_or(Error::PoolNotFound)
+            source.total_staked -= stake.amount;
+            child.total_staked += stake.amount;
+
+            if storage::is_staker_allowed(&env, source_pool, &staker) {
+                storage::set_staker_allowed(&env, child_id, &staker, true);
+            }
+            for token in source.reward_tokens.iter() {
+                let held = storage::get_held_rewards(&env, &staker, source_pool, &token);
+                if held > 0 {
+                    storage::set_held_rewards(&env, &staker, child_id, &token, held);
+                    storage::set_held_rewards(&env, &staker, source_pool, &token, 0);
+                }
+            }
+
+            storage::remove_stake(&env, &staker, source_pool);
+            storage::set_stake(&env, &StakePosition { pool_id: child_id, ..stake });
+        }
+
+        if let Some(kyc_config) = storage::get_kyc_config(&env, source_pool) {
+            storage::set_kyc_config(&env, child_id, &kyc_config);
+        }
+        if let Some(stake_token) = storage::get_stake_token(&env, source_pool) {
+            storage::set_stake_token(&env, child_id, &stake_token);
+        }
+
+        storage::set_pool(&env, &source);
+        storage::set_pool(&env, &child);
+        env.storage().instance().set(&symbol_short!("POOL_CNT"), &child_id);
+
+        env.events().publish((symbol_short!("POOL_SPL"), source_pool), (child_id, name));
+
+        Ok(child_id)
     }
 
-    /// Get claim history
-    pub fn get_claim_history(
+    /// Set the warm-up period after staking before rewards begin to accrue
+    pub fn set_reward_start_delay(
         env: Env,
-        claimer: Address,
+        admin: Address,
         pool_id: u32,
-    ) -> Vec<ClaimRecord> {
-        storage::get_claim_history(&env, &claimer, pool_id)
-    }
+        reward_start_delay: u64,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
 
-    /// Get risk-adjusted APY
-    pub fn get_risk_adjusted_apy(env: Env, pool_id: u32) -> Result<u32, Error> {
-        let pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
-        let metrics = storage::get_metrics(&env, pool_id).unwrap_or(PerformanceMetrics {
-            pool_id,
-            utilization_rate: 5_000,
-            claim_ratio: 1_000,
-            volatility_score: 3_000,
-            counterparty_risk: 2_000,
-        });
+        let mut pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+        pool.reward_start_delay = reward_start_delay;
+        storage::set_pool(&env, &pool);
 
-        let performance_multiplier = calculations::calculate_performance_bonus(&metrics);
-        let adjusted_apy = calculations::calculate_risk_adjusted_yield(
-            pool.base_apy,
-            pool.risk_adjustment_factor,
-            performance_multiplier,
-        );
+        env.events().publish((symbol_short!("WARMUP"), pool_id), reward_start_delay);
 
-        Ok(adjusted_apy)
+        Ok(())
     }
 
-    // Helper functions
-
-    fn require_admin(env: &Env, address: &Address) -> Result<(), Error> {
-        let admin: Address = env.storage()
-            .instance()
-            .get(&symbol_short!("ADMIN"))
-            .ok_or(Error::NotInitialized)?;
+    /// Set the protocol fee, in basis points, deducted from each claim
+    pub fn set_claim_fee(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        claim_fee_bps: u32,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
 
-        if admin != *address {
-            return Err(Error::Unauthorized);
+        if claim_fee_bps > MAX_CLAIM_FEE_BPS {
+            return Err(Error::InvalidFee);
         }
 
+        let mut pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+        pool.claim_fee_bps = claim_fee_bps;
+        storage::set_pool(&env, &pool);
+
+        env.events().publish((symbol_short!("CLAIM_FEE"), pool_id), claim_fee_bps);
+
         Ok(())
     }
 
-    fn require_not_paused(env: &Env) -> Result<(), Error> {
-        let paused: bool = env.storage()
-            .instance()
-            .get(&symbol_short!("PAUSED"))
-            .unwrap_or(false);
+    /// Configure the duration bonus curve: rewards ramp linearly up to an extra
+    /// `max_bonus_bps` once a stake has been held for `bonus_period` seconds.
+    /// A zero bonus disables the curve.
+    pub fn set_duration_bonus(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        max_bonus_bps: u32,
+        bonus_period: u64,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
 
-        if paused {
-            return Err(Error::ContractPaused);
+        if max_bonus_bps > MAX_DURATION_BONUS_BPS || (max_bonus_bps > 0 && bonus_period == 0) {
+            return Err(Error::InvalidPerformanceMultiplier);
         }
 
+        let mut pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+        pool.duration_bonus_bps = max_bonus_bps;
+        pool.duration_bonus_period = bonus_period;
+        storage::set_pool(&env, &pool);
+
+        env.events().publish((symbol_short!("DUR_BONUS"), pool_id), (max_bonus_bps, bonus_period));
+
         Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use soroban_sdk::testutils::{Address as _, Ledger};
+    /// Set how long a staker is barred from re-entering a pool after an
+    /// emergency unstake
+    pub fn set_emergency_cooldown(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        emergency_cooldown: u64,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
 
-    #[test]
-    fn test_initialize() {
-        let env = Env::default();
-        let admin = Address::generate(&env);
+        let mut pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+        pool.emergency_cooldown = emergency_cooldown;
+        storage::set_pool(&env, &pool);
 
-        let result = RewardDistribution::initialize(env.clone(), admin.clone());
-        assert!(result.is_ok());
+        env.events().publish((symbol_short!("EMERG_CD"), pool_id), emergency_cooldown);
 
-        // Test double initialization
-        let result2 = RewardDistribution::initialize(env, admin);
-        assert_eq!(result2, Err(Error::AlreadyInitialized));
+        Ok(())
     }
 
-    #[test]
-    fn test_create_pool() {
-        let env = Env::default();
-        let admin = Address::generate(&env);
+    /// Record the token a pool's stake is denominated in, so rewards paid in
+    /// that token can be restaked into it with `claim_and_migrate`
+    pub fn set_stake_token(env: Env, admin: Address, pool_id: u32, token: Address) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
 
-        RewardDistribution::initialize(env.clone(), admin.clone()).unwrap();
+        storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+        storage::set_stake_token(&env, pool_id, &token);
 
-        let pool_id = RewardDistribution::create_pool(
-            env.clone(),
-            admin,
-            String::from_str(&env, "Test Pool"),
-            1_000, // 10% APY
-            8_000, // Risk factor
-            100_0000000, // Min stake
-            86400, // 1 day lock
-        ).unwrap();
+        env.events().publish((symbol_short!("STK_TKN"), pool_id), token);
 
-        assert_eq!(pool_id, 1);
+        Ok(())
     }
 
-    #[test]
-    fn test_stake_and_unstake() {
-        let env = Env::default();
-        env.mock_all_auths();
-        
-        let admin = Address::generate(&env);
-        let staker = Address::generate(&env);
-
-        RewardDistribution::initialize(env.clone(), admin.clone()).unwrap();
-
-        let pool_id = RewardDistribution::create_pool(
-            env.clone(),
-            admin,
-            String::from_str(&env, "Test Pool"),
-            1_000,
-            8_000,
-            100_0000000,
-            0, // No lock period for test
-        ).unwrap();
+    /// Override the decimals a reward token's payouts are scaled to, for
+    /// tokens whose on-chain metadata doesn't reflect their precision
+    pub fn set_reward_token_decimals(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        token: Address,
+        decimals: u32,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
 
-        // Stake
-        let stake_amount = 1000_0000000;
-        RewardDistribution::stake(
-            env.clone(),
-            staker.clone(),
-            pool_id,
-            stake_amount,
-        ).unwrap();
+        if decimals > 18 {
+            return Err(Error::InvalidAmount);
+        }
 
-        // Verify stake
-        let stake = RewardDistribution::get_stake(env.clone(), staker.clone(), pool_id).unwrap();
-        assert_eq!(stake.amount, stake_amount);
+        let mut reward_token = storage::get_reward_token(&env, pool_id, &token)
+            .ok_or(Error::TokenNotRegistered)?;
+        reward_token.decimals = decimals;
+        storage::set_reward_token(&env, pool_id, &reward_token);
 
-        // Unstake
-        RewardDistribution::unstake(
-            env.clone(),
-            staker.clone(),
-            pool_id,
-            stake_amount,
-        ).unwrap();
+        env.events().publish((symbol_short!("TKN_DEC"), pool_id), (token, decimals));
 
-        // Verify unstake
-        let result = RewardDistribution::get_stake(env, staker, pool_id);
-        assert_eq!(result, Err(Error::StakeNotFound));
+        Ok(())
+    }
+
+    /// Cap how much a single address may have staked in a pool (0 = uncapped)
+    pub fn set_max_stake_per_address(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        max_stake_per_address: i128,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+
+        if max_stake_per_address < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+        pool.max_stake_per_address = max_stake_per_address;
+        storage::set_pool(&env, &pool);
+
+        env.events().publish((symbol_short!("MAX_STK"), pool_id), max_stake_per_address);
+
+        Ok(())
+    }
+
+    /// Choose how a pool rounds sub-unit rewards: truncate, round half up, or
+    /// carry the remainder on each position until it adds up to a whole unit
+    pub fn set_rounding_mode(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        rounding_mode: RoundingMode,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+
+        let mut pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+        pool.rounding_mode = rounding_mode;
+        storage::set_pool(&env, &pool);
+
+        env.events().publish((symbol_short!("RND_MODE"), pool_id), rounding_mode);
+
+        Ok(())
+    }
+
+    /// Cap the rewards a pool pays out per token within each `period` seconds,
+    /// independent of its emission config. A zero period caps each claim; a
+    /// zero cap removes the limit.
+    pub fn set_reward_cap(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        max_rewards_per_period: i128,
+        period: u64,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+
+        if max_rewards_per_period < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+        pool.max_rewards_per_period = max_rewards_per_period;
+        pool.reward_cap_period = period;
+        storage::set_pool(&env, &pool);
+
+        env.events().publish((symbol_short!("RWD_CAP"), pool_id), (max_rewards_per_period, period));
+
+        Ok(())
+    }
+
+    /// Withdraw the claim fees collected for a pool's reward token
+    pub fn withdraw_fees(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        token: Address,
+    ) -> Result<i128, Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+
+        let amount = storage::get_accrued_fees(&env, pool_id, &token);
+        if amount == 0 {
+            return Err(Error::NoRewardsAvailable);
+        }
+
+        storage::set_accrued_fees(&env, pool_id, &token, 0);
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &admin, &amount);
+
+        env.events().publish((symbol_short!("FEE_WD"), pool_id), (token, amount));
+
+        Ok(amount)
+    }
+
+    /// Get the claim fees collected for a pool's reward token
+    pub fn get_accrued_fees(env: Env, pool_id: u32, token: Address) -> i128 {
+        storage::get_accrued_fees(&env, pool_id, &token)
+    }
+
+    /// Scale a pool's rewards by each staker's KYC level, looked up from
+    /// `kyc_contract`. Levels without an entry earn 1x.
+    pub fn set_kyc_multipliers(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        kyc_contract: Address,
+        multipliers: Map<u32, u32>,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+
+        storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+        for (_, multiplier) in multipliers.iter() {
+            if multiplier == 0 || multiplier > MAX_KYC_MULTIPLIER {
+                return Err(Error::InvalidPerformanceMultiplier);
+            }
+        }
+
+        storage::set_kyc_config(&env, pool_id, &KycRewardConfig { kyc_contract, multipliers });
+
+        env.events().publish((symbol_short!("KYC_MULT"), pool_id), true);
+
+        Ok(())
+    }
+
+    /// Stop applying KYC-level multipliers to a pool
+    pub fn clear_kyc_multipliers(env: Env, admin: Address, pool_id: u32) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+
+        storage::remove_kyc_config(&env, pool_id);
+
+        env.events().publish((symbol_short!("KYC_MULT"), pool_id), false);
+
+        Ok(())
+    }
+
+    /// Get a pool's KYC contract and per-level multipliers, if configured
+    pub fn get_kyc_multipliers(env: Env, pool_id: u32) -> Option<KycRewardConfig> {
+        storage::get_kyc_config(&env, pool_id)
+    }
+
+    /// Restrict staking in a pool to allowlisted addresses, or reopen it
+    pub fn set_allowlist_mode(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        allowlist_only: bool,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+
+        let mut pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+        pool.allowlist_only = allowlist_only;
+        storage::set_pool(&env, &pool);
+
+        env.events().publish((symbol_short!("ALLOW_MD"), pool_id), allowlist_only);
+
+        Ok(())
+    }
+
+    /// Add or remove a staker from a pool's allowlist
+    pub fn set_staker_allowed(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        staker: Address,
+        allowed: bool,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+
+        storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+        storage::set_staker_allowed(&env, pool_id, &staker, allowed);
+
+        env.events().publish((symbol_short!("ALLOWED"), pool_id), (staker, allowed));
+
+        Ok(())
+    }
+
+    /// Pause/unpause the contract
+    pub fn set_paused(env: Env, admin: Address, paused: bool) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+        if multisig::is_guarded(&env, &OP_SET_PAUSED) {
+            return Err(Error::MultisigRequired);
+        }
+
+        Self::apply_paused(&env, paused);
+
+        Ok(())
+    }
+
+    /// Execute an approved multisig proposal to pause or unpause the contract
+    pub fn execute_set_paused(env: Env, proposal_id: u64, paused: bool) -> Result<(), Error> {
+        let action = multisig::action_hash(&env, &OP_SET_PAUSED, (paused,));
+        multisig::execute(&env, proposal_id, &OP_SET_PAUSED, &action)?;
+
+        Self::apply_paused(&env, paused);
+
+        Ok(())
+    }
+
+    /// Put the listed operations (`ADJ_EMIT`, `SET_PAUSE`, `UPGRADE`) behind M-of-N
+    /// signer approval. Opt-in and one-way: once configured, the single
+    /// admin can no longer call them directly or change the signer set.
+    pub fn configure_multisig(
+        env: Env,
+        admin: Address,
+        signers: Vec<Address>,
+        threshold: u32,
+        window_secs: u64,
+        operations: Vec<Symbol>,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+        if multisig::get_config(&env).is_some() {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        for operation in operations.iter() {
+            if operation != OP_ADJUST_EMISSION
+                && operation != OP_SET_PAUSED
+                && operation != OP_UPGRADE
+            {
+                return Err(Error::InvalidMultisigConfig);
+            }
+        }
+
+        let config = MultisigConfig { signers, threshold, window_secs, operations };
+        multisig::configure(&env, &config)?;
+
+        env.events().publish((symbol_short!("MULTISIG"),), (config.threshold, config.signers.len()));
+
+        Ok(())
+    }
+
+    /// Propose a guarded operation; `action` is `multisig::action_hash` of
+    /// the operation and its arguments. Counts as the proposer's approval.
+    pub fn propose_admin_action(
+        env: Env,
+        proposer: Address,
+        operation: Symbol,
+        action: BytesN<32>,
+    ) -> Result<u64, Error> {
+        Ok(multisig::propose(&env, &proposer, operation, action)?)
+    }
+
+    /// Approve a pending proposal, returning its approval count
+    pub fn approve_admin_action(env: Env, signer: Address, proposal_id: u64) -> Result<u32, Error> {
+        Ok(multisig::approve(&env, &signer, proposal_id)?)
+    }
+
+    fn apply_paused(env: &Env, paused: bool) {
+        env.storage().instance().set(&symbol_short!("PAUSED"), &paused);
+
+        env.events().publish((symbol_short!("PAUSED"),), paused);
+    }
+
+    /// Upgrade the contract code to a new WASM hash
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+        if multisig::is_guarded(&env, &OP_UPGRADE) {
+            return Err(Error::MultisigRequired);
+        }
+
+        Self::apply_upgrade(&env, new_wasm_hash);
+
+        Ok(())
+    }
+
+    /// Execute an approved multisig proposal to upgrade the contract wasm
+    pub fn execute_upgrade(env: Env, proposal_id: u64, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        let action = multisig::action_hash(&env, &OP_UPGRADE, (new_wasm_hash.clone(),));
+        multisig::execute(&env, proposal_id, &OP_UPGRADE, &action)?;
+
+        Self::apply_upgrade(&env, new_wasm_hash);
+
+        Ok(())
+    }
+
+    fn apply_upgrade(env: &Env, new_wasm_hash: BytesN<32>) {
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+
+        env.events().publish((symbol_short!("UPGRADE"),), new_wasm_hash);
+    }
+
+    /// Run storage migrations after an upgrade, bumping the stored schema version
+    pub fn migrate(env: Env, admin: Address) -> Result<u32, Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+
+        // Deployments that predate schema tracking are version 1
+        let mut version: u32 = env.storage()
+            .instance()
+            .get(&symbol_short!("SCHEMA"))
+            .unwrap_or(1);
+
+        if version > SCHEMA_VERSION {
+            return Err(Error::SchemaVersionMismatch);
+        }
+
+        let from_version = version;
+        while version < SCHEMA_VERSION {
+            migrations::run_step(&env, version)?;
+            version += 1;
+        }
+
+        env.storage().instance().set(&symbol_short!("SCHEMA"), &version);
+
+        env.events().publish((symbol_short!("MIGRATE"),), (from_version, version));
+
+        Ok(version)
+    }
+
+    /// Upgrade `stakers`' positions in a pool to the current schema. `migrate`
+    /// only reaches positions in the pool's staker index; run this in batches
+    /// for deployments whose stakes predate the index. Returns how many of
+    /// `stakers` hold a position.
+    pub fn migrate_positions(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        stakers: Vec<Address>,
+    ) -> Result<u32, Error> {
+        admin.require_auth();
+        Self::require_admin(&env, &admin)?;
+
+        if stakers.len() > 100 {
+            return Err(Error::BatchSizeTooLarge);
+        }
+
+        let mut upgraded = 0u32;
+        for staker in stakers.iter() {
+            if migrations::upgrade_position(&env, &staker, pool_id) {
+                upgraded += 1;
+            }
+        }
+
+        env.events().publish((symbol_short!("MIGR_POS"), pool_id), upgraded);
+
+        Ok(upgraded)
+    }
+
+    // View functions
+
+    /// Get pool information
+    pub fn get_pool(env: Env, pool_id: u32) -> Result<RewardPool, Error> {
+        storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)
+    }
+
+    /// Get stake position
+    pub fn get_stake(env: Env, staker: Address, pool_id: u32) -> Result<StakePosition, Error> {
+        storage::get_stake(&env, &staker, pool_id).ok_or(Error::StakeNotFound)
+    }
+
+    /// Amount `staker` has staked in a pool, or zero without a position
+    pub fn get_staked_amount(env: Env, staker: Address, pool_id: u32) -> i128 {
+        storage::get_stake(&env, &staker, pool_id).map_or(0, |stake| stake.amount)
+    }
+
+    /// Get reward token configuration for a pool
+    pub fn get_reward_token(env: Env, pool_id: u32, token: Address) -> Result<RewardToken, Error> {
+        storage::get_reward_token(&env, pool_id, &token).ok_or(Error::TokenNotRegistered)
+    }
+
+    /// Get the multisig signers, threshold and guarded operations, if configured
+    pub fn get_multisig_config(env: Env) -> Option<MultisigConfig> {
+        multisig::get_config(&env)
+    }
+
+    /// Get a multisig proposal and its approvals
+    pub fn get_admin_proposal(env: Env, proposal_id: u64) -> Option<MultisigProposal> {
+        multisig::get_proposal(&env, proposal_id)
+    }
+
+    /// Get the treasury balance accumulated from slashing
+    pub fn get_treasury_balance(env: Env, pool_id: u32) -> i128 {
+        storage::get_treasury_balance(&env, pool_id)
+    }
+
+    /// Check that the contract holds enough of a pool's reward token to
+    /// cover what every pool still owes in it
+    pub fn verify_reserves(env: Env, pool_id: u32, token: Address) -> bool {
+        match storage::get_reward_token(&env, pool_id, &token) {
+            Some(_) => Self::has_reserves(&env, &token),
+            None => false,
+        }
+    }
+
+    /// Get the emission schedule attached to a reward token
+    pub fn get_emission_schedule(env: Env, pool_id: u32, token: Address) -> Option<EmissionSchedule> {
+        storage::get_emission_schedule(&env, pool_id, &token)
+    }
+
+    /// Get a reward token's current emission rate, applying any halvings
+    pub fn get_effective_emission_rate(env: Env, pool_id: u32, token: Address) -> Result<i128, Error> {
+        let reward_token = storage::get_reward_token(&env, pool_id, &token)
+            .ok_or(Error::TokenNotRegistered)?;
+
+        Ok(match storage::get_emission_schedule(&env, pool_id, &token) {
+            Some(schedule) => {
+                calculations::calculate_effective_emission_rate(&schedule, env.ledger().timestamp())
+            }
+            None => reward_token.emission_rate,
+        })
+    }
+
+    /// Check whether a staker is on a pool's allowlist
+    pub fn is_staker_allowed(env: Env, pool_id: u32, staker: Address) -> bool {
+        storage::is_staker_allowed(&env, pool_id, &staker)
+    }
+
+    /// Get vesting schedule
+    pub fn get_vesting(
+        env: Env,
+        beneficiary: Address,
+        pool_id: u32,
+    ) -> Result<VestingSchedule, Error> {
+        storage::get_vesting(&env, &beneficiary, pool_id)
+            .ok_or(Error::InvalidVestingSchedule)
+    }
+
+    /// Get claimable vested amount
+    pub fn get_claimable_vested(
+        env: Env,
+        beneficiary: Address,
+        pool_id: u32,
+    ) -> Result<i128, Error> {
+        let schedule = storage::get_vesting(&env, &beneficiary, pool_id)
+            .ok_or(Error::InvalidVestingSchedule)?;
+
+        calculations::calculate_vested_amount(&env, &schedule)
+    }
+
+    /// Get pending APY-based rewards, in value units before scaling to any
+    /// reward token's decimals
+    pub fn get_pending_rewards(
+        env: Env,
+        staker: Address,
+        pool_id: u32,
+    ) -> Result<i128, Error> {
+        let stake = storage::get_stake(&env, &staker, pool_id)
+            .ok_or(Error::StakeNotFound)?;
+        let pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+
+        Self::calculate_pending(&env, &stake, &pool, env.ledger().timestamp())
+            .map(|(pending, _)| pending)
+    }
+
+    /// Get performance metrics
+    pub fn get_metrics(env: Env, pool_id: u32) -> Result<PerformanceMetrics, Error> {
+        storage::get_metrics(&env, pool_id).ok_or(Error::PoolNotFound)
+    }
+
+    /// Get claim history
+    pub fn get_claim_history(
+        env: Env,
+        claimer: Address,
+        pool_id: u32,
+    ) -> Vec<ClaimRecord> {
+        storage::get_claim_history(&env, &claimer, pool_id)
+    }
+
+    /// Get risk-adjusted APY. Closed pools no longer pay a yield and
+    /// return `PoolClosed`.
+    pub fn get_risk_adjusted_apy(env: Env, pool_id: u32) -> Result<u32, Error> {
+        let pool = storage::get_pool(&env, pool_id).ok_or(Error::PoolNotFound)?;
+        if pool.status == RewardStatus::Closed {
+            return Err(Error::PoolClosed);
+        }
+        let metrics = storage::get_metrics(&env, pool_id).unwrap_or(PerformanceMetrics {
+            pool_id,
+            utilization_rate: 5_000,
+            claim_ratio: 1_000,
+            volatility_score: 3_000,
+            counterparty_risk: 2_000,
+        });
+
+        let performance_multiplier = calculations::calculate_performance_bonus(&metrics, &Self::bonus_weights(&env));
+        let adjusted_apy = calculations::calculate_risk_adjusted_yield(
+            pool.base_apy,
+            pool.risk_adjustment_factor,
+            performance_multiplier,
+        );
+
+        Ok(adjusted_apy)
+    }
+
+    /// Get the recorded emission rate adjustments for a token, oldest first
+    pub fn get_emission_history(env: Env, pool_id: u32, token: Address) -> Vec<EmissionAdjustment> {
+        storage::get_emission_history(&env, pool_id, &token)
+    }
+
+    /// Get up to `limit` of the most recent APY history points, oldest first
+    pub fn get_apy_history(env: Env, pool_id: u32, limit: u32) -> Vec<(u64, u32)> {
+        let history = storage::get_apy_history(&env, pool_id);
+        let start = history.len().saturating_sub(limit);
+        history.slice(start..)
+    }
+
+    /// Health check for monitoring: (initialized, paused, schema version)
+    pub fn health(env: Env) -> (bool, bool, u32) {
+        let initialized = env.storage().instance().has(&symbol_short!("ADMIN"));
+        let paused = env.storage().instance().get(&symbol_short!("PAUSED")).unwrap_or(false);
+        (initialized, paused, Self::get_schema_version(env))
+    }
+
+    /// Get the stored storage schema version
+    pub fn get_schema_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("SCHEMA"))
+            .unwrap_or(1)
+    }
+
+    // Helper functions
+
+    fn require_admin(env: &Env, address: &Address) -> Result<(), Error> {
+        let admin: Address = env.storage()
+            .instance()
+            .get(&symbol_short!("ADMIN"))
+            .ok_or(Error::NotInitialized)?;
+
+        if admin != *address {
+            return Err(Error::Unauthorized);
+        }
+
+        Ok(())
+    }
+
+    fn bonus_weights(env: &Env) -> BonusWeights {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("BONUS_WT"))
+            .unwrap_or_else(calculations::default_bonus_weights)
+    }
+
+    /// Add `amount` to the staker's position, enforcing the pool's entry rules
+    fn credit_stake(env: &Env, staker: &Address, pool_id: u32, amount: i128) -> Result<(), Error> {
+        let mut pool = storage::get_pool(env, pool_id).ok_or(Error::PoolNotFound)?;
+        
+        if pool.status == RewardStatus::Closed {
+            return Err(Error::PoolClosed);
+        }
+        if pool.status != RewardStatus::Active {
+            return Err(Error::PoolPaused);
+        }
+        
+        if amount < pool.min_stake {
+            return Err(Error::BelowMinimumStake);
+        }
+
+        if pool.allowlist_only && !storage::is_staker_allowed(env, pool_id, staker) {
+            return Err(Error::StakerNotAllowed);
+        }
+
+        if pool.max_stake_per_address > 0 {
+            let existing = storage::get_stake(env, staker, pool_id).map_or(0, |stake| stake.amount);
+            let total = existing.checked_add(amount).ok_or(Error::ArithmeticOverflow)?;
+            if total > pool.max_stake_per_address {
+                return Err(Error::AboveMaximumStake);
+            }
+        }
+
+        let current_time = env.ledger().timestamp();
+
+        if current_time < storage::get_emergency_cooldown_end(env, staker, pool_id) {
+            return Err(Error::EmergencyCooldownActive);
+        }
+        
+        // Get or create stake position
+        let mut stake = storage::get_stake(env, staker, pool_id).unwrap_or(StakePosition {
+            staker: staker.clone(),
+            pool_id,
+            amount: 0,
+            stake_time: current_time,
+            last_claim_time: current_time,
+            performance_multiplier: 10_000, // Default 1x
+            accrued_rewards: 0,
+            bonus_start_time: current_time,
+            reward_dust: 0,
+        });
+
+        stake.amount += amount;
+        pool.total_staked += amount;
+
+        storage::set_stake(env, &stake);
+        storage::set_pool(env, &pool);
+
+        env.events().publish((symbol_short!("STAKE"), pool_id), (staker.clone(), amount));
+
+        Ok(())
+    }
+
+    /// Settle a claim of `token` rewards: update the position and token
+    /// accounting, take the claim fee and record the claim. Returns the
+    /// (payout, fee); moving the payout is left to the caller.
+    fn settle_claim(env: &Env, staker: &Address, pool_id: u32, token: &Address) -> Result<(i128, i128), Error> {
+        let mut stake = storage::get_stake(env, staker, pool_id)
+            .ok_or(Error::StakeNotFound)?;
+        let pool = storage::get_pool(env, pool_id).ok_or(Error::PoolNotFound)?;
+        let mut reward_token = storage::get_reward_token(env, pool_id, token)
+            .ok_or(Error::TokenNotRegistered)?;
+
+        if !reward_token.active {
+            return Err(Error::NoRewardsAvailable);
+        }
+
+        if !Self::has_reserves(env, token) {
+            return Err(Error::InsufficientRewardBalance);
+        }
+
+        let current_time = env.ledger().timestamp();
+        let (pending, accrued_rewards, reward_dust) =
+            Self::calculate_token_claim(env, &stake, &pool, token, current_time)?;
+
+        if pending == 0 {
+            return Err(Error::NoRewardsAvailable);
+        }
+
+        let final_rewards = Self::apply_reward_cap(env, &pool, token, pending, current_time);
+        if final_rewards == 0 {
+            return Err(Error::NoRewardsAvailable);
+        }
+
+        // Check if enough rewards are available
+        let available = reward_token.total_allocated - reward_token.total_distributed;
+        if final_rewards > available {
+            return Err(Error::InsufficientRewardBalance);
+        }
+
+        // Update state, carrying anything held back by the cap to the next
+        // claim: a scheduled token keeps its own remainder, while the APY
+        // value's remainder stays settled in value units
+        let held_back = pending - final_rewards;
+        if storage::get_emission_schedule(env, pool_id, token).is_some() {
+            storage::set_held_rewards(env, staker, pool_id, token, held_back);
+            stake.accrued_rewards = accrued_rewards;
+        } else {
+            stake.accrued_rewards = calculations::scale_decimals(
+                held_back,
+                reward_token.decimals,
+                calculations::REWARD_VALUE_DECIMALS,
+            )?;
+        }
+        stake.last_claim_time = current_time;
+        stake.reward_dust = reward_dust;
+        reward_token.total_distributed += final_rewards;
+
+        storage::set_stake(env, &stake);
+        storage::set_reward_token(env, pool_id, &reward_token);
+
+        let fee = Self::take_claim_fee(env, &pool, token, final_rewards)?;
+        let payout = final_rewards - fee;
+
+        // Record claim
+        let claim_record = ClaimRecord {
+            claimer: staker.clone(),
+            pool_id,
+            token: token.clone(),
+            amount: payout,
+            timestamp: current_time,
+        };
+        storage::add_claim_record(env, &claim_record);
+
+        Ok((payout, fee))
+    }
+
+    /// Whether the contract's balance of `token` covers what all pools owe
+    /// in it: rewards allocated to active reward tokens but not yet
+    /// distributed, plus claim fees not yet withdrawn
+    fn has_reserves(env: &Env, token: &Address) -> bool {
+        let pool_count: u32 = env.storage().instance().get(&symbol_short!("POOL_CNT")).unwrap_or(0);
+        let mut obligations: i128 = 0;
+        for pool_id in 1..=pool_count {
+            if let Some(reward_token) = storage::get_reward_token(env, pool_id, token) {
+                if reward_token.active {
+                    let outstanding = reward_token.total_allocated - reward_token.total_distributed;
+                    obligations = obligations.saturating_add(outstanding);
+                }
+            }
+            obligations = obligations.saturating_add(storage::get_accrued_fees(env, pool_id, token));
+        }
+
+        let balance = token::Client::new(env, token).balance(&env.current_contract_address());
+        balance >= obligations
+    }
+
+    /// `current_time`, or the closing time for a closed pool
+    fn accrual_end(env: &Env, pool: &RewardPool, current_time: u64) -> u64 {
+        match storage::get_pool_closed_at(env, pool.pool_id) {
+            Some(closed_at) if pool.status == RewardStatus::Closed => current_time.min(closed_at),
+            _ => current_time,
+        }
+    }
+
+    fn calculate_pending(
+        env: &Env,
+        stake: &StakePosition,
+        pool: &RewardPool,
+        current_time: u64,
+    ) -> Result<(i128, i128), Error> {
+        let current_time = Self::accrual_end(env, pool, current_time);
+        let accrual_duration = calculations::calculate_accrual_duration(
+            stake.stake_time,
+            stake.last_claim_time,
+            pool.reward_start_delay,
+            current_time,
+        );
+
+        // Calculate base rewards, in sub-units until rounded below
+        let base_rewards = calculations::calculate_base_rewards(
+            env,
+            stake.amount,
+            accrual_duration,
+            pool.base_apy,
+        )?;
+
+        // Apply risk adjustment
+        let risk_adjusted = calculations::apply_risk_adjustment(
+            base_rewards,
+            pool.risk_adjustment_factor,
+        )?;
+
+        // Apply performance multiplier
+        let performance_adjusted = calculations::apply_performance_multiplier(
+            risk_adjusted,
+            stake.performance_multiplier,
+        )?;
+
+        // Apply KYC-level multiplier
+        let kyc_adjusted = calculations::apply_performance_multiplier(
+            performance_adjusted,
+            Self::kyc_multiplier(env, pool.pool_id, &stake.staker),
+        )?;
+
+        // Apply duration bonus for long-held stakes
+        let final_rewards = calculations::apply_duration_bonus(
+            kyc_adjusted,
+            (current_time - accrual_duration).saturating_sub(stake.bonus_start_time),
+            current_time.saturating_sub(stake.bonus_start_time),
+            pool.duration_bonus_bps,
+            pool.duration_bonus_period,
+        )?;
+
+        let (final_rewards, reward_dust) = calculations::apply_rounding(
+            final_rewards,
+            stake.reward_dust,
+            pool.rounding_mode,
+        )?;
+
+        let pending = stake.accrued_rewards.checked_add(final_rewards).ok_or(Error::ArithmeticOverflow)?;
+        Ok((pending, reward_dust))
+    }
+
+    /// Reward multiplier for the staker's KYC level, 1x when the pool has
+    /// no KYC contract configured or the level has no entry
+    fn kyc_multiplier(env: &Env, pool_id: u32, staker: &Address) -> u32 {
+        match storage::get_kyc_config(env, pool_id) {
+            Some(config) => {
+                let level = KycIntegrationClient::new(env, &config.kyc_contract).get_kyc_level(staker);
+                config.multipliers.get(level).unwrap_or(10_000)
+            }
+            None => 10_000,
+        }
+    }
+
+    /// What a claim of `token` pays, in its native units, along with the
+    /// stake's settled rewards and dust after it. The pool's APY value,
+    /// settled rewards included, is paid in full in whichever unscheduled
+    /// token is claimed. A scheduled token pays its share of what the
+    /// schedule emitted over the accrual window plus anything it held back
+    /// before; the APY value earned meanwhile stays settled for an
+    /// unscheduled token, or is paid here when the pool has none.
+    fn calculate_token_claim(
+        env: &Env,
+        stake: &StakePosition,
+        pool: &RewardPool,
+        token: &Address,
+        current_time: u64,
+    ) -> Result<(i128, i128, i128), Error> {
+        let decimals = storage::get_reward_token(env, pool.pool_id, token)
+            .map_or(calculations::REWARD_VALUE_DECIMALS, |reward_token| reward_token.decimals);
+        let schedule = match storage::get_emission_schedule(env, pool.pool_id, token) {
+            Some(schedule) => schedule,
+            None => {
+                let (pending, reward_dust) = Self::calculate_pending(env, stake, pool, current_time)?;
+                let pending = calculations::scale_decimals(pending, calculations::REWARD_VALUE_DECIMALS, decimals)?;
+                return Ok((pending, 0, reward_dust));
+            }
+        };
+
+        let held = storage::get_held_rewards(env, &stake.staker, pool.pool_id, token);
+        let share = Self::scheduled_share(env, stake, pool, &schedule, current_time)?
+            .checked_add(held)
+            .ok_or(Error::ArithmeticOverflow)?;
+
+        if Self::value_token(env, pool).is_some() {
+            let (accrued_rewards, reward_dust) = Self::calculate_pending(env, stake, pool, current_time)?;
+            return Ok((share, accrued_rewards, reward_dust));
+        }
+
+        let settled = calculations::scale_decimals(
+            stake.accrued_rewards,
+            calculations::REWARD_VALUE_DECIMALS,
+            decimals,
+        )?;
+        let pending = share.checked_add(settled).ok_or(Error::ArithmeticOverflow)?;
+        Ok((pending, 0, stake.reward_dust))
+    }
+
+    /// The stake's share of what `schedule` emitted over its accrual window
+    fn scheduled_share(
+        env: &Env,
+        stake: &StakePosition,
+        pool: &RewardPool,
+        schedule: &EmissionSchedule,
+        current_time: u64,
+    ) -> Result<i128, Error> {
+        if pool.total_staked <= 0 {
+            return Ok(0);
+        }
+
+        let current_time = Self::accrual_end(env, pool, current_time);
+        let accrual_duration = calculations::calculate_accrual_duration(
+            stake.stake_time,
+            stake.last_claim_time,
+            pool.reward_start_delay,
+            current_time,
+        );
+        let emitted = calculations::calculate_scheduled_emission(
+            schedule,
+            current_time - accrual_duration,
+            current_time,
+        )?;
+
+        calculations::mul_div(emitted, stake.amount, pool.total_staked)
+    }
+
+    /// The first active reward token without an emission schedule, which
+    /// `claim_all_rewards` pays the pool's APY value in
+    fn value_token(env: &Env, pool: &RewardPool) -> Option<Address> {
+        pool.reward_tokens.iter().find(|token| {
+            storage::get_reward_token(env, pool.pool_id, token).is_some_and(|reward_token| reward_token.active)
+                && storage::get_emission_schedule(env, pool.pool_id, token).is_none()
+        })
+    }
+
+    /// Whether the position still has settled or held-back rewards to claim
+    fn has_unclaimed_rewards(env: &Env, stake: &StakePosition, pool: &RewardPool) -> bool {
+        stake.accrued_rewards > 0
+            || pool.reward_tokens.iter().any(|token| {
+                storage::get_held_rewards(env, &stake.staker, pool.pool_id, &token) > 0
+            })
+    }
+
+    /// Clamp a payout to what remains of the pool's reward cap for the current
+    /// window and record it against the window
+    fn apply_reward_cap(
+        env: &Env,
+        pool: &RewardPool,
+        token: &Address,
+        amount: i128,
+        current_time: u64,
+    ) -> i128 {
+        if pool.max_rewards_per_period == 0 || amount <= 0 {
+            return amount;
+        }
+
+        let (mut window_start, mut paid) = storage::get_reward_cap_window(env, pool.pool_id, token);
+        if paid == 0 || current_time >= window_start.saturating_add(pool.reward_cap_period) {
+            window_start = current_time;
+            paid = 0;
+        }
+
+        let remaining = pool.max_rewards_per_period.saturating_sub(paid).max(0);
+        let allowed = if amount < remaining { amount } else { remaining };
+        storage::set_reward_cap_window(env, pool.pool_id, token, &(window_start, paid + allowed));
+
+        if allowed < amount {
+            env.events().publish(
+                (Symbol::new(env, "REWARD_CAPPED"), pool.pool_id),
+                (token.clone(), amount, allowed),
+            );
+        }
+
+        allowed
+    }
+
+    /// Set aside the pool's claim fee from a claim, returning the fee
+    fn take_claim_fee(env: &Env, pool: &RewardPool, token: &Address, amount: i128) -> Result<i128, Error> {
+        let fee = calculations::calculate_claim_fee(amount, pool.claim_fee_bps)?;
+        if fee > 0 {
+            let accrued = storage::get_accrued_fees(env, pool.pool_id, token);
+            storage::set_accrued_fees(env, pool.pool_id, token, accrued + fee);
+        }
+        Ok(fee)
+    }
+
+    /// Check batch shape and amounts, returning the batch total
+    fn validate_batch(recipients: &Vec<Address>, amounts: &Vec<i128>) -> Result<i128, Error> {
+        if recipients.len() != amounts.len() || recipients.len() > 100 {
+            return Err(Error::BatchSizeTooLarge);
+        }
+
+        let mut total: i128 = 0;
+        for amount in amounts.iter() {
+            if amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+            total = total.checked_add(amount).ok_or(Error::InvalidAmount)?;
+        }
+
+        Ok(total)
+    }
+
+    fn adjust_token_emission(
+        env: &Env,
+        pool_id: u32,
+        token: &Address,
+        total_supply: i128,
+    ) -> Result<i128, Error> {
+        let emission_config: EmissionConfig = env.storage()
+            .instance()
+            .get(&symbol_short!("EMISSION"))
+            .unwrap();
+
+        let current_time = env.ledger().timestamp();
+        let last_adjustment = storage::get_emission_adjusted_at(env, pool_id, token)
+            .unwrap_or(emission_config.last_adjustment);
+        let time_elapsed = current_time.saturating_sub(last_adjustment);
+
+        if time_elapsed < emission_config.adjustment_interval {
+            return Err(Error::InvalidEmissionRate);
+        }
+
+        let mut reward_token = storage::get_reward_token(env, pool_id, token)
+            .ok_or(Error::TokenNotRegistered)?;
+
+        let adjusted_rate = calculations::calculate_emission_adjustment(
+            reward_token.emission_rate,
+            total_supply,
+            emission_config.inflation_cap,
+            time_elapsed,
+        );
+
+        let old_rate = reward_token.emission_rate;
+        reward_token.emission_rate = adjusted_rate;
+        storage::set_reward_token(env, pool_id, &reward_token);
+        storage::set_emission_adjusted_at(env, pool_id, token, current_time);
+
+        let adjustment = EmissionAdjustment {
+            timestamp: current_time,
+            old_rate,
+            new_rate: adjusted_rate,
+            total_supply,
+            time_elapsed,
+        };
+        storage::add_emission_adjustment(env, pool_id, token, &adjustment, MAX_EMISSION_HISTORY);
+
+        env.events().publish(
+            (symbol_short!("EMIT_ADJ"), pool_id),
+            (token.clone(), old_rate, adjusted_rate, total_supply, time_elapsed),
+        );
+
+        // Update last adjustment time
+        let mut new_config = emission_config;
+        new_config.last_adjustment = current_time;
+        env.storage().instance().set(&symbol_short!("EMISSION"), &new_config);
+
+        Ok(adjusted_rate)
+    }
+
+    fn require_not_paused(env: &Env) -> Result<(), Error> {
+        let paused: bool = env.storage()
+            .instance()
+            .get(&symbol_short!("PAUSED"))
+            .unwrap_or(false);
+
+        if paused {
+            return Err(Error::ContractPaused);
+        }
+
+        Ok(())
+    }
+}
+
+// Client interface for the kyc_integration contract
+#[contractclient(name = "KycIntegrationClient")]
+pub trait KycIntegrationInterface {
+    fn get_kyc_level(env: Env, account: Address) -> u32;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Events, Ledger};
+    use soroban_sdk::{IntoVal, TryFromVal, Val};
+
+    #[test]
+    fn test_initialize() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, RewardDistribution);
+
+        env.as_contract(&contract_id, || {
+            let admin = Address::generate(&env);
+
+            let result = RewardDistribution::initialize(env.clone(), admin.clone());
+            assert!(result.is_ok());
+
+            // Test double initialization
+            let result2 = RewardDistribution::initialize(env.clone(), admin);
+            assert_eq!(result2, Err(Error::AlreadyInitialized));
+        });
+    }
+
+    #[test]
+    fn test_create_pool() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, RewardDistribution);
+
+        env.as_contract(&contract_id, || {
+            let admin = Address::generate(&env);
+
+            RewardDistribution::initialize(env.clone(), admin.clone()).unwrap();
+
+            let pool_id = RewardDistribution::create_pool(
+                env.clone(),
+                admin,
+                String::from_str(&env, "Test Pool"),
+                1_000, // 10% APY
+                8_000, // Risk factor
+                100_0000000, // Min stake
+                86400, // 1 day lock
+            ).unwrap();
+
+            assert_eq!(pool_id, 1);
+        });
+    }
+
+    #[test]
+    fn test_stake_and_unstake() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let staker = Address::generate(&env);
+
+        let pool_id = client.create_pool(
+            &admin,
+            &String::from_str(&env, "Test Pool"),
+            &1_000,
+            &8_000,
+            &100_0000000,
+            &0, // No lock period for test
+        );
+
+        // Stake
+        let stake_amount = 1000_0000000;
+        client.stake(&staker, &pool_id, &stake_amount);
+
+        // Verify stake
+        let stake = client.get_stake(&staker, &pool_id);
+        assert_eq!(stake.amount, stake_amount);
+
+        // Unstake
+        client.unstake(&staker, &pool_id, &stake_amount);
+
+        // Verify unstake
+        assert_eq!(client.try_get_stake(&staker, &pool_id), Err(Ok(Error::StakeNotFound)));
+    }
+
+    #[test]
+    fn test_vesting_schedule() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let beneficiary = Address::generate(&env);
+
+        let pool_id = client.create_pool(
+            &admin,
+            &String::from_str(&env, "Test Pool"),
+            &1_000,
+            &8_000,
+            &100_0000000,
+            &0,
+        );
+
+        // Create vesting schedule
+        client.create_vesting_schedule(
+            &admin,
+            &beneficiary,
+            &pool_id,
+            &1000_0000000,
+            &86400,  // 1 day cliff
+            &2592000, // 30 day vesting
+            &VestingCurve::Linear,
+        );
+
+        // Verify schedule
+        let schedule = client.get_vesting(&beneficiary, &pool_id);
+
+        assert_eq!(schedule.total_amount, 1000_0000000);
+        assert_eq!(schedule.cliff_duration, 86400);
+    }
+
+    fn setup_client(env: &Env) -> (RewardDistributionClient<'_>, Address) {
+        env.mock_all_auths();
+
+        let contract_id = env.register_contract(None, RewardDistribution);
+        let client = RewardDistributionClient::new(env, &contract_id);
+        let admin = Address::generate(env);
+        client.initialize(&admin);
+
+        (client, admin)
+    }
+
+    #[test]
+    fn test_migrate_bumps_schema_version() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        assert_eq!(client.get_schema_version(), SCHEMA_VERSION);
+
+        // Simulate a deployment that predates schema tracking
+        env.as_contract(&client.address, || {
+            env.storage().instance().remove(&symbol_short!("SCHEMA"));
+        });
+        assert_eq!(client.get_schema_version(), 1);
+
+        assert_eq!(client.migrate(&admin), SCHEMA_VERSION);
+        assert_eq!(client.get_schema_version(), SCHEMA_VERSION);
+
+        // Running again is a no-op
+        assert_eq!(client.migrate(&admin), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_migrate_positions_indexes_unindexed_stakes() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let source = create_client_pool(&env, &client, &admin, 0);
+        let target = create_client_pool(&env, &client, &admin, 0);
+        let staker = Address::generate(&env);
+        client.stake(&staker, &source, &1_000);
+
+        // Simulate a stake written before pools indexed their stakers
+        env.as_contract(&client.address, || {
+            env.storage().persistent().remove(&(source, "STAKERS"));
+        });
+
+        let stakers = Vec::from_array(&env, [staker.clone(), Address::generate(&env)]);
+        assert_eq!(client.migrate_positions(&admin, &source, &stakers), 1);
+
+        client.merge_pools(&admin, &source, &target);
+        assert_eq!(client.get_stake(&staker, &target).amount, 1_000);
+    }
+
+    /// Fields added to each persisted struct since schema v2
+    const POOL_FIELDS_SINCE_V2: [&str; 10] = [
+        "reward_start_delay",
+        "allowlist_only",
+        "claim_fee_bps",
+        "duration_bonus_bps",
+        "duration_bonus_period",
+        "max_rewards_per_period",
+        "reward_cap_period",
+        "emergency_cooldown",
+        "rounding_mode",
+        "max_stake_per_address",
+    ];
+    const TOKEN_FIELDS_SINCE_V2: [&str; 1] = ["decimals"];
+    const STAKE_FIELDS_SINCE_V2: [&str; 3] = ["accrued_rewards", "bonus_start_time", "reward_dust"];
+    const VESTING_FIELDS_SINCE_V2: [&str; 2] = ["revoked", "schedule_id"];
+
+    /// Raw field map stored under `key`, optionally without `fields`
+    fn strip_fields<K: IntoVal<Env, Val>>(env: &Env, contract: &Address, key: &K, fields: &[&str]) -> Map<Symbol, Val> {
+        env.as_contract(contract, || {
+            let storage = env.storage().persistent();
+            let entry: Map<Symbol, Val> = storage.get(key).unwrap();
+            let mut stripped = entry.clone();
+            for field in fields {
+                stripped.remove(Symbol::new(env, field));
+            }
+            storage.set(key, &stripped);
+            entry
+        })
+    }
+
+    #[test]
+    fn test_migrate_backfills_fields_added_since_v2() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let reward_token = create_reward_token(&env, &client, &admin, pool_id);
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &1_000);
+        client.create_vesting_schedule(&admin, &staker, &pool_id, &1_000, &0, &100, &VestingCurve::Linear);
+
+        // Rewrite each entry as a v2 deployment would have stored it
+        let contract = client.address.clone();
+        let pool = strip_fields(&env, &contract, &(pool_id,), &POOL_FIELDS_SINCE_V2);
+        let token = strip_fields(&env, &contract, &(pool_id, reward_token.address.clone()), &TOKEN_FIELDS_SINCE_V2);
+        let stake = strip_fields(&env, &contract, &(staker.clone(), pool_id), &STAKE_FIELDS_SINCE_V2);
+        let mut vesting = strip_fields(&env, &contract, &(staker.clone(), pool_id, "VESTING"), &VESTING_FIELDS_SINCE_V2);
+        // Schedules from before ids were issued come back as id 0
+        vesting.set(Symbol::new(&env, "schedule_id"), 0u32.into_val(&env));
+        env.as_contract(&contract, || {
+            env.storage().instance().set(&symbol_short!("SCHEMA"), &2u32);
+        });
+
+        assert_eq!(client.migrate(&admin), SCHEMA_VERSION);
+        assert_eq!(client.migrate_positions(&admin, &pool_id, &Vec::from_array(&env, [staker.clone()])), 1);
+
+        assert_eq!(strip_fields(&env, &contract, &(pool_id,), &[]), pool);
+        assert_eq!(strip_fields(&env, &contract, &(pool_id, reward_token.address.clone()), &[]), token);
+        assert_eq!(strip_fields(&env, &contract, &(staker.clone(), pool_id), &[]), stake);
+        assert_eq!(strip_fields(&env, &contract, &(staker.clone(), pool_id, "VESTING"), &[]), vesting);
+        assert_eq!(client.get_vesting(&staker, &pool_id).total_amount, 1_000);
+    }
+
+    #[test]
+    fn test_migrate_moves_legacy_vesting_and_claims() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let beneficiary = Address::generate(&env);
+        let claimer = Address::generate(&env);
+        client.create_vesting_schedule(&admin, &beneficiary, &pool_id, &1_000, &0, &100, &VestingCurve::Linear);
+
+        // Before v3 both shared the (address, pool_id) stake key
+        env.as_contract(&client.address, || {
+            let storage = env.storage().persistent();
+            let vesting: VestingSchedule = storage.get(&(beneficiary.clone(), pool_id, "VESTING")).unwrap();
+            storage.remove(&(beneficiary.clone(), pool_id, "VESTING"));
+            storage.set(&(beneficiary.clone(), pool_id), &vesting);
+
+            let record = ClaimRecord {
+                claimer: claimer.clone(),
+                pool_id,
+                token: Address::generate(&env),
+                amount: 50,
+                timestamp: 0,
+            };
+            storage.set(&(claimer.clone(), pool_id), &Vec::from_array(&env, [record]));
+            env.storage().instance().set(&symbol_short!("SCHEMA"), &2u32);
+        });
+
+        client.migrate(&admin);
+        let addresses = Vec::from_array(&env, [beneficiary.clone(), claimer.clone()]);
+        assert_eq!(client.migrate_positions(&admin, &pool_id, &addresses), 0);
+
+        assert_eq!(client.get_vesting(&beneficiary, &pool_id).total_amount, 1_000);
+        assert_eq!(client.get_claim_history(&claimer, &pool_id).get(0).unwrap().amount, 50);
+        assert_eq!(client.try_get_stake(&beneficiary, &pool_id), Err(Ok(Error::StakeNotFound)));
+    }
+
+    fn create_client_pool(env: &Env, client: &RewardDistributionClient, admin: &Address, lock_period: u64) -> u32 {
+        client.create_pool(
+            admin,
+            &String::from_str(env, "Test Pool"),
+            &1_000,
+            &10_000,
+            &100,
+            &lock_period,
+        )
+    }
+
+    fn create_reward_token<'a>(
+        env: &Env,
+        client: &RewardDistributionClient,
+        admin: &Address,
+        pool_id: u32,
+    ) -> token::Client<'a> {
+        let token_address = env.register_stellar_asset_contract_v2(admin.clone()).address();
+        token::StellarAssetClient::new(env, &token_address).mint(&client.address, &1_000_000);
+        client.add_reward_token(admin, &pool_id, &token_address, &1_000, &1_000_000);
+        token::Client::new(env, &token_address)
+    }
+
+    #[test]
+    fn test_reward_start_delay_excludes_warm_up() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = client.create_pool(
+            &admin,
+            &String::from_str(&env, "Warm-up Pool"),
+            &1_000,
+            &10_000,
+            &100,
+            &0,
+        );
+        client.set_reward_start_delay(&admin, &pool_id, &1_000);
+        let reward_token = create_reward_token(&env, &client, &admin, pool_id);
+
+        // 10% APY on this amount accrues exactly one token per second
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &315_360_000);
+
+        env.ledger().with_mut(|li| li.timestamp += 500);
+        assert_eq!(client.get_pending_rewards(&staker, &pool_id), 0);
+        let result = client.try_claim_rewards(&staker, &staker, &pool_id, &reward_token.address);
+        assert_eq!(result, Err(Ok(Error::NoRewardsAvailable)));
+
+        env.ledger().with_mut(|li| li.timestamp += 1_000);
+        assert_eq!(client.get_pending_rewards(&staker, &pool_id), 500);
+        assert_eq!(client.claim_rewards(&staker, &staker, &pool_id, &reward_token.address), 500);
+        assert_eq!(reward_token.balance(&staker), 500);
+    }
+
+    #[test]
+    fn test_slash_moves_stake_to_treasury() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 86_400);
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &1_000);
+
+        let reason = String::from_str(&env, "fraudulent claim");
+        let result = client.try_slash(&admin, &staker, &pool_id, &1_001, &reason);
+        assert_eq!(result, Err(Ok(Error::InsufficientStake)));
+
+        client.slash(&admin, &staker, &pool_id, &400, &reason);
+
+        assert_eq!(client.get_stake(&staker, &pool_id).amount, 600);
+        assert_eq!(client.get_pool(&pool_id).total_staked, 600);
+        assert_eq!(client.get_treasury_balance(&pool_id), 400);
+    }
+
+    #[test]
+    fn test_slash_settles_rewards_before_reducing_stake() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &315_360_000);
+        env.ledger().with_mut(|li| li.timestamp += 100);
+
+        // A full slash keeps the position so its settled rewards can be claimed
+        let reason = String::from_str(&env, "fraudulent claim");
+        client.slash(&admin, &staker, &pool_id, &315_360_000, &reason);
+        let stake = client.get_stake(&staker, &pool_id);
+        assert_eq!(stake.amount, 0);
+        assert_eq!(stake.accrued_rewards, 100);
+        assert_eq!(client.get_treasury_balance(&pool_id), 315_360_000);
+    }
+
+    #[test]
+    fn test_claim_all_rewards_pays_apy_value_once() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = client.create_pool(
+            &admin,
+            &String::from_str(&env, "Multi Pool"),
+            &1_000,
+            &10_000,
+            &100,
+            &0,
+        );
+        let token_a = create_reward_token(&env, &client, &admin, pool_id);
+        let token_b = create_reward_token(&env, &client, &admin, pool_id);
+
+        let staker = Address::generate(&env);
+        let single = Address::generate(&env);
+        client.stake(&staker, &pool_id, &315_360_000);
+        client.stake(&single, &pool_id, &315_360_000);
+        env.ledger().with_mut(|li| li.timestamp += 500);
+
+        // The 500 earned is paid once, in the first token, and the second
+        // token has nothing left to pay
+        let claimed = client.claim_all_rewards(&staker, &pool_id);
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed.get(0).unwrap(), (token_a.address.clone(), 500));
+        assert_eq!(token_a.balance(&staker), 500);
+        assert_eq!(token_b.balance(&staker), 0);
+
+        // Claiming a single token pays the same total
+        assert_eq!(client.claim_rewards(&single, &single, &pool_id, &token_a.address), 500);
+
+        // Nothing has accrued since, so every token is skipped
+        assert_eq!(client.claim_all_rewards(&staker, &pool_id).len(), 0);
+    }
+
+    #[test]
+    fn test_claim_all_rewards_keeps_scheduled_remainder_with_its_token() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let value_token = create_reward_token(&env, &client, &admin, pool_id);
+        let scheduled_token = create_reward_token(&env, &client, &admin, pool_id);
+        client.set_emission_schedule(&admin, &pool_id, &scheduled_token.address, &10, &1_000_000);
+        client.set_reward_cap(&admin, &pool_id, &300, &1_000);
+
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &315_360_000);
+        env.ledger().with_mut(|li| li.timestamp += 100);
+
+        // The schedule's 1_000 is capped at 300; its remainder is not
+        // settled as APY value that another token could pay
+        let claimed = client.claim_all_rewards(&staker, &pool_id);
+        assert_eq!(claimed.get(0).unwrap(), (value_token.address.clone(), 100));
+        assert_eq!(claimed.get(1).unwrap(), (scheduled_token.address.clone(), 300));
+        assert_eq!(client.get_stake(&staker, &pool_id).accrued_rewards, 0);
+
+        env.ledger().with_mut(|li| li.timestamp += 100);
+        assert_eq!(client.claim_rewards(&staker, &staker, &pool_id, &value_token.address), 100);
+    }
+
+    #[test]
+    fn test_emission_schedule_halving() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let reward_token = create_reward_token(&env, &client, &admin, pool_id);
+        client.set_emission_schedule(&admin, &pool_id, &reward_token.address, &10, &100);
+
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &1_000);
+
+        // First segment emits at the initial rate
+        env.ledger().with_mut(|li| li.timestamp += 100);
+        assert_eq!(client.claim_rewards(&staker, &staker, &pool_id, &reward_token.address), 1_000);
+
+        // Second segment emits at half the rate
+        assert_eq!(client.get_effective_emission_rate(&pool_id, &reward_token.address), 5);
+        env.ledger().with_mut(|li| li.timestamp += 100);
+        assert_eq!(client.claim_rewards(&staker, &staker, &pool_id, &reward_token.address), 500);
+    }
+
+    #[test]
+    fn test_emission_schedule_pays_settled_rewards() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let reward_token = create_reward_token(&env, &client, &admin, pool_id);
+        client.set_emission_schedule(&admin, &pool_id, &reward_token.address, &10, &1_000_000);
+
+        // 10% APY on this amount settles one token per second on unstake
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &315_360_000);
+        env.ledger().with_mut(|li| li.timestamp += 100);
+        client.unstake(&staker, &pool_id, &157_680_000);
+        assert_eq!(client.get_stake(&staker, &pool_id).accrued_rewards, 100);
+
+        // The schedule's share since the unstake plus the settled rewards
+        env.ledger().with_mut(|li| li.timestamp += 100);
+        assert_eq!(client.claim_rewards(&staker, &staker, &pool_id, &reward_token.address), 1_100);
+        assert_eq!(client.get_stake(&staker, &pool_id).accrued_rewards, 0);
+    }
+
+    #[test]
+    fn test_allowlisted_staker_can_stake() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        client.set_allowlist_mode(&admin, &pool_id, &true);
+
+        let staker = Address::generate(&env);
+        client.set_staker_allowed(&admin, &pool_id, &staker, &true);
+        client.stake(&staker, &pool_id, &1_000);
+
+        assert_eq!(client.get_stake(&staker, &pool_id).amount, 1_000);
+    }
+
+    #[test]
+    fn test_non_allowlisted_staker_rejected() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let staker = Address::generate(&env);
+
+        // Open pools accept anyone
+        client.stake(&staker, &pool_id, &1_000);
+
+        client.set_allowlist_mode(&admin, &pool_id, &true);
+        let outsider = Address::generate(&env);
+        let result = client.try_stake(&outsider, &pool_id, &1_000);
+        assert_eq!(result, Err(Ok(Error::StakerNotAllowed)));
+    }
+
+    #[test]
+    fn test_apy_history_records_metric_updates() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = client.create_pool(
+            &admin,
+            &String::from_str(&env, "APY Pool"),
+            &1_000,
+            &10_000,
+            &100,
+            &0,
+        );
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        client.update_performance_metrics(&admin, &pool_id, &5_000, &5_000, &5_000, &5_000);
+
+        // Maximum performance bonus of 1.55x
+        env.ledger().with_mut(|li| li.timestamp = 2_000);
+        client.update_performance_metrics(&admin, &pool_id, &9_000, &500, &1_000, &1_000);
+
+        let history = client.get_apy_history(&pool_id, &10);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.get(0).unwrap(), (1_000, 1_000));
+        assert_eq!(history.get(1).unwrap(), (2_000, 1_550));
+
+        let latest = client.get_apy_history(&pool_id, &1);
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest.get(0).unwrap(), (2_000, client.get_risk_adjusted_apy(&pool_id)));
     }
 
     #[test]
-    fn test_vesting_schedule() {
+    fn test_partial_unstake_settles_accrued_rewards() {
         let env = Env::default();
-        env.mock_all_auths();
-        
-        let admin = Address::generate(&env);
+        let (client, admin) = setup_client(&env);
+        let pool_id = client.create_pool(
+            &admin,
+            &String::from_str(&env, "Settle Pool"),
+            &1_000,
+            &10_000,
+            &100,
+            &0,
+        );
+
+        // 10% APY on this amount accrues exactly one token per second
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &315_360_000);
+        env.ledger().with_mut(|li| li.timestamp += 1_000);
+
+        client.unstake(&staker, &pool_id, &157_680_000);
+        let stake = client.get_stake(&staker, &pool_id);
+        assert_eq!(stake.accrued_rewards, 1_000);
+        assert_eq!(client.get_pending_rewards(&staker, &pool_id), 1_000);
+
+        // The remaining half accrues at half a token per second
+        env.ledger().with_mut(|li| li.timestamp += 1_000);
+        assert_eq!(client.get_pending_rewards(&staker, &pool_id), 1_500);
+    }
+
+    #[test]
+    fn test_reserves_cover_every_pool_and_unwithdrawn_fees() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let reward_token = create_reward_token(&env, &client, &admin, pool_id);
+        client.set_claim_fee(&admin, &pool_id, &1_000);
+
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &315_360_000);
+        env.ledger().with_mut(|li| li.timestamp += 500);
+        assert_eq!(client.claim_rewards(&staker, &staker, &pool_id, &reward_token.address), 450);
+
+        // The 50 in fees is still owed, so the balance covers exactly what
+        // the first pool owes
+        assert!(client.verify_reserves(&pool_id, &reward_token.address));
+
+        // A second pool allocating the same token is short against the
+        // combined obligations, and so is the first
+        let other_pool = create_client_pool(&env, &client, &admin, 0);
+        client.add_reward_token(&admin, &other_pool, &reward_token.address, &1_000, &1);
+        assert!(!client.verify_reserves(&pool_id, &reward_token.address));
+        assert!(!client.verify_reserves(&other_pool, &reward_token.address));
+    }
+
+    #[test]
+    fn test_verify_reserves() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+
+        let funded = create_reward_token(&env, &client, &admin, pool_id);
+        assert!(client.verify_reserves(&pool_id, &funded.address));
+
+        // Allocation advertises more than the contract holds
+        let underfunded = env.register_stellar_asset_contract_v2(admin.clone()).address();
+        token::StellarAssetClient::new(&env, &underfunded).mint(&client.address, &100);
+        client.add_reward_token(&admin, &pool_id, &underfunded, &1_000, &1_000);
+        assert!(!client.verify_reserves(&pool_id, &underfunded));
+
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &1_000);
+        env.ledger().with_mut(|li| li.timestamp += 1_000);
+
+        let result = client.try_claim_rewards(&staker, &staker, &pool_id, &underfunded);
+        assert_eq!(result, Err(Ok(Error::InsufficientRewardBalance)));
+
+        // Claiming every token pays none of them while one is short
+        let result = client.try_claim_all_rewards(&staker, &pool_id);
+        assert_eq!(result, Err(Ok(Error::InsufficientRewardBalance)));
+        assert_eq!(funded.balance(&staker), 0);
+    }
+
+    #[test]
+    fn test_claim_delegate_claims_to_staker() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let reward_token = create_reward_token(&env, &client, &admin, pool_id);
+
+        // 10% APY on this amount accrues exactly one token per second
+        let staker = Address::generate(&env);
+        let operator = Address::generate(&env);
+        client.stake(&staker, &pool_id, &315_360_000);
+        client.set_claim_delegate(&staker, &pool_id, &operator);
+        env.ledger().with_mut(|li| li.timestamp += 100);
+
+        let stranger = Address::generate(&env);
+        let result = client.try_claim_rewards(&stranger, &staker, &pool_id, &reward_token.address);
+        assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+        assert_eq!(client.claim_rewards(&operator, &staker, &pool_id, &reward_token.address), 100);
+        assert_eq!(reward_token.balance(&staker), 100);
+        assert_eq!(reward_token.balance(&operator), 0);
+    }
+
+    #[test]
+    fn test_bonus_weights_shift_multiplier() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &1_000);
+
+        // Top-tier utilization only
+        client.update_performance_metrics(&admin, &pool_id, &9_000, &5_000, &5_000, &5_000);
+        assert_eq!(client.apply_performance_bonus(&admin, &staker, &pool_id), 12_000);
+
+        let unbalanced = BonusWeights {
+            utilization: 2_000,
+            claim_ratio: 2_000,
+            volatility: 2_000,
+            counterparty: 2_000,
+        };
+        let result = client.try_set_bonus_weights(&admin, &unbalanced);
+        assert_eq!(result, Err(Ok(Error::InvalidPerformanceMultiplier)));
+
+        // Weights that wrap around to the base are rejected, not accepted
+        let overflowing = BonusWeights {
+            utilization: u32::MAX,
+            claim_ratio: calculations::BONUS_WEIGHT_BASE + 1,
+            volatility: 0,
+            counterparty: 0,
+        };
+        let result = client.try_set_bonus_weights(&admin, &overflowing);
+        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+
+        client.set_bonus_weights(&admin, &BonusWeights {
+            utilization: 4_000,
+            claim_ratio: 500,
+            volatility: 500,
+            counterparty: 500,
+        });
+        assert_eq!(client.apply_performance_bonus(&admin, &staker, &pool_id), 14_000);
+    }
+
+    #[test]
+    fn test_auto_adjust_all_only_adjusts_eligible_tokens() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+
+        let token_a = Address::generate(&env);
+        let token_b = Address::generate(&env);
+
+        client.add_reward_token(&admin, &pool_id, &token_a, &1_000, &1_000_000);
+        env.ledger().with_mut(|li| li.timestamp += 43_200);
+        client.add_reward_token(&admin, &pool_id, &token_b, &1_000, &1_000_000);
+        env.ledger().with_mut(|li| li.timestamp += 43_200);
+
+        // Only token A has waited a full adjustment interval
+        let adjusted = client.auto_adjust_all(&admin, &pool_id, &1_000_000_000);
+        assert_eq!(adjusted.len(), 1);
+        assert_eq!(adjusted.get(0).unwrap(), token_a);
+
+        // 1e9 supply at a 10% cap allows ~3 tokens per second
+        assert_eq!(client.get_reward_token(&pool_id, &token_a).emission_rate, 3);
+        assert_eq!(client.get_reward_token(&pool_id, &token_b).emission_rate, 1_000);
+    }
+
+    #[test]
+    fn test_emission_history_records_transitions() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let token = Address::generate(&env);
+
+        client.add_reward_token(&admin, &pool_id, &token, &1_000, &1_000_000);
+
+        env.ledger().with_mut(|li| li.timestamp += 86_400);
+        assert_eq!(client.adjust_emission_rate(&admin, &pool_id, &token, &1_000_000_000), 3);
+
+        env.ledger().with_mut(|li| li.timestamp += 86_400);
+        assert_eq!(client.adjust_emission_rate(&admin, &pool_id, &token, &100_000_000), 0);
+
+        let history = client.get_emission_history(&pool_id, &token);
+        assert_eq!(history.len(), 2);
+
+        let first = history.get(0).unwrap();
+        assert_eq!((first.old_rate, first.new_rate), (1_000, 3));
+        assert_eq!(first.total_supply, 1_000_000_000);
+        assert_eq!(first.time_elapsed, 86_400);
+
+        let second = history.get(1).unwrap();
+        assert_eq!((second.old_rate, second.new_rate), (3, 0));
+        assert_eq!(second.total_supply, 100_000_000);
+    }
+
+    #[test]
+    fn test_simulate_batch_distribute_flags_underfunded_batch() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let reward_token = create_reward_token(&env, &client, &admin, pool_id);
+
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let recipients = Vec::from_array(&env, [alice.clone(), bob.clone()]);
+
+        let funded = Vec::from_array(&env, [400_000i128, 600_000]);
+        assert_eq!(
+            client.simulate_batch_distribute(&pool_id, &reward_token.address, &recipients, &funded),
+            (1_000_000, true)
+        );
+
+        let underfunded = Vec::from_array(&env, [400_000i128, 600_001]);
+        assert_eq!(
+            client.simulate_batch_distribute(&pool_id, &reward_token.address, &recipients, &underfunded),
+            (1_000_001, false)
+        );
+
+        // Nothing moved and nothing was recorded
+        assert_eq!(reward_token.balance(&client.address), 1_000_000);
+        assert_eq!(reward_token.balance(&alice), 0);
+        assert_eq!(client.get_claim_history(&alice, &pool_id).len(), 0);
+
+        let invalid = Vec::from_array(&env, [400_000i128, 0]);
+        assert_eq!(
+            client.try_simulate_batch_distribute(&pool_id, &reward_token.address, &recipients, &invalid),
+            Err(Ok(Error::InvalidAmount))
+        );
+    }
+
+    #[test]
+    fn test_claim_fee_splits_claims_and_accumulates() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = client.create_pool(
+            &admin,
+            &String::from_str(&env, "Fee Pool"),
+            &1_000,
+            &10_000,
+            &100,
+            &0,
+        );
+        let reward_token = create_reward_token(&env, &client, &admin, pool_id);
+        let token_address = reward_token.address.clone();
+
+        assert_eq!(client.try_set_claim_fee(&admin, &pool_id, &5_001), Err(Ok(Error::InvalidFee)));
+        client.set_claim_fee(&admin, &pool_id, &1_000);
+
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &315_360_000);
+
+        env.ledger().with_mut(|li| li.timestamp += 500);
+        assert_eq!(client.claim_rewards(&staker, &staker, &pool_id, &token_address), 450);
+        assert_eq!(client.get_accrued_fees(&pool_id, &token_address), 50);
+
+        env.ledger().with_mut(|li| li.timestamp += 300);
+        assert_eq!(client.claim_rewards(&staker, &staker, &pool_id, &token_address), 270);
+        assert_eq!(client.get_accrued_fees(&pool_id, &token_address), 80);
+        assert_eq!(reward_token.balance(&staker), 720);
+
+        assert_eq!(client.withdraw_fees(&admin, &pool_id, &token_address), 80);
+        assert_eq!(reward_token.balance(&admin), 80);
+        assert_eq!(client.get_accrued_fees(&pool_id, &token_address), 0);
+        assert_eq!(
+            client.try_withdraw_fees(&admin, &pool_id, &token_address),
+            Err(Ok(Error::NoRewardsAvailable))
+        );
+    }
+
+    #[test]
+    fn test_merge_pools_conserves_total_staked() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let source = create_client_pool(&env, &client, &admin, 0);
+        let target = client.create_pool(&admin, &String::from_str(&env, "Target"), &2_000, &10_000, &100, &0);
+        let reward_token = create_reward_token(&env, &client, &admin, source);
+
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let carol = Address::generate(&env);
+
+        client.stake(&alice, &source, &1_000);
+        client.stake(&bob, &source, &315_360_000);
+        client.stake(&carol, &target, &700);
+        client.stake(&alice, &target, &300);
+
+        env.ledger().with_mut(|li| li.timestamp += 100);
+        client.merge_pools(&admin, &source, &target);
+
+        let merged = client.get_pool(&target);
+        assert_eq!(merged.total_staked, 315_362_000);
+        assert!(merged.reward_tokens.contains(&reward_token.address));
+
+        let closed = client.get_pool(&source);
+        assert_eq!(closed.total_staked, 0);
+        assert!(closed.status == RewardStatus::Closed);
+        assert_eq!(client.try_get_stake(&bob, &source), Err(Ok(Error::StakeNotFound)));
+
+        assert_eq!(client.get_stake(&alice, &target).amount, 1_300);
+        assert_eq!(client.get_stake(&bob, &target).amount, 315_360_000);
+        assert_eq!(client.get_stake(&carol, &target).amount, 700);
+
+        // Time before the merge earns the source's 10%, after it the target's 20%
+        assert_eq!(client.get_pending_rewards(&bob, &target), 100);
+        env.ledger().with_mut(|li| li.timestamp += 100);
+        assert_eq!(client.get_pending_rewards(&bob, &target), 300);
+    }
+
+    #[test]
+    fn test_split_pool_migrates_selected_stakers() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let source = create_client_pool(&env, &client, &admin, 0);
+
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let carol = Address::generate(&env);
+
+        client.stake(&alice, &source, &1_000);
+        client.stake(&bob, &source, &500);
+        client.stake(&carol, &source, &700);
+
+        let child = client.split_pool(
+            &admin,
+            &source,
+            &String::from_str(&env, "Child Pool"),
+            &Vec::from_array(&env, [bob.clone(), carol.clone()]),
+        );
+
+        assert_eq!(client.get_pool(&source).total_staked, 1_000);
+        assert_eq!(client.get_pool(&child).total_staked, 1_200);
+        assert_eq!(client.get_pool(&child).base_apy, client.get_pool(&source).base_apy);
+        assert_eq!(client.get_stake(&bob, &child).amount, 500);
+        assert_eq!(client.try_get_stake(&carol, &source), Err(Ok(Error::StakeNotFound)));
+        assert_eq!(client.get_stake(&alice, &source).amount, 1_000);
+    }
+
+    #[test]
+    fn test_split_pool_carries_allowlist_entries() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let source = create_client_pool(&env, &client, &admin, 0);
+        client.set_allowlist_mode(&admin, &source, &true);
+
+        let staker = Address::generate(&env);
+        client.set_staker_allowed(&admin, &source, &staker, &true);
+        client.stake(&staker, &source, &1_000);
+
+        let child = client.split_pool(
+            &admin,
+            &source,
+            &String::from_str(&env, "Child Pool"),
+            &Vec::from_array(&env, [staker.clone()]),
+        );
+
+        // The child is allowlist-only too, and the moved staker can top up
+        assert!(client.get_pool(&child).allowlist_only);
+        client.stake(&staker, &child, &500);
+        assert_eq!(client.get_stake(&staker, &child).amount, 1_500);
+
+        let outsider = Address::generate(&env);
+        assert_eq!(client.try_stake(&outsider, &child, &500), Err(Ok(Error::StakerNotAllowed)));
+    }
+
+    #[contract]
+    pub struct MockKyc;
+
+    #[contractimpl]
+    impl MockKyc {
+        pub fn set_level(env: Env, account: Address, level: u32) {
+            env.storage().persistent().set(&account, &level);
+        }
+
+        pub fn get_kyc_level(env: Env, account: Address) -> u32 {
+            env.storage().persistent().get(&account).unwrap_or(0)
+        }
+    }
+
+    #[test]
+    fn test_kyc_level_scales_rewards() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+
+        let kyc_contract = env.register_contract(None, MockKyc);
+        let kyc = MockKycClient::new(&env, &kyc_contract);
+        let basic = Address::generate(&env);
+        let enhanced = Address::generate(&env);
+        kyc.set_level(&basic, &1);
+        kyc.set_level(&enhanced, &3);
+
+        client.stake(&basic, &pool_id, &315_360_000);
+        client.stake(&enhanced, &pool_id, &315_360_000);
+
+        // No KYC contract configured: everyone earns 1x
+        env.ledger().with_mut(|li| li.timestamp += 100);
+        assert_eq!(client.get_pending_rewards(&basic, &pool_id), 100);
+        assert_eq!(client.get_pending_rewards(&enhanced, &pool_id), 100);
+
+        let mut multipliers = Map::new(&env);
+        multipliers.set(3, 40_000);
+        assert_eq!(
+            client.try_set_kyc_multipliers(&admin, &pool_id, &kyc_contract, &multipliers),
+            Err(Ok(Error::InvalidPerformanceMultiplier))
+        );
+        multipliers.set(1, 10_000);
+        multipliers.set(3, 15_000);
+        client.set_kyc_multipliers(&admin, &pool_id, &kyc_contract, &multipliers);
+
+        assert_eq!(client.get_pending_rewards(&basic, &pool_id), 100);
+        assert_eq!(client.get_pending_rewards(&enhanced, &pool_id), 150);
+
+        client.clear_kyc_multipliers(&admin, &pool_id);
+        assert_eq!(client.get_pending_rewards(&enhanced, &pool_id), 100);
+    }
+
+    #[test]
+    fn test_duration_bonus_favours_longer_held_stake() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        client.set_duration_bonus(&admin, &pool_id, &10_000, &1_000);
+
+        let early = Address::generate(&env);
+        let late = Address::generate(&env);
+        client.stake(&early, &pool_id, &315_360_000);
+        env.ledger().with_mut(|li| li.timestamp += 1_000);
+        client.stake(&late, &pool_id, &315_360_000);
+
+        // Ramping from 1x to 2x over the first 1,000s averages 1.5x
+        assert_eq!(client.get_pending_rewards(&early, &pool_id), 1_500);
+
+        // Past the ramp every further second earns the full 2x
+        env.ledger().with_mut(|li| li.timestamp += 100);
+        assert_eq!(client.get_pending_rewards(&early, &pool_id), 1_700);
+        assert_eq!(client.get_pending_rewards(&late, &pool_id), 105);
+
+        // A partial unstake settles what was earned, then restarts the ramp
+        client.unstake(&early, &pool_id, &157_680_000);
+        env.ledger().with_mut(|li| li.timestamp += 100);
+        assert_eq!(client.get_pending_rewards(&early, &pool_id), 1_752);
+    }
+
+    #[test]
+    fn test_reward_overflow_returns_error() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+
+        let whale = Address::generate(&env);
+        client.stake(&whale, &pool_id, &(i128::MAX - 1));
+        env.ledger().with_mut(|li| li.timestamp += 10 * 31_536_000);
+
+        assert_eq!(
+            client.try_get_pending_rewards(&whale, &pool_id),
+            Err(Ok(Error::ArithmeticOverflow))
+        );
+        assert_eq!(
+            client.try_unstake(&whale, &pool_id, &1),
+            Err(Ok(Error::ArithmeticOverflow))
+        );
+    }
+
+    #[test]
+    fn test_reward_cap_clamps_oversized_claim() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let reward_token = create_reward_token(&env, &client, &admin, pool_id);
+        let token_address = reward_token.address.clone();
+        client.set_reward_cap(&admin, &pool_id, &300, &1_000);
+
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &315_360_000);
+
+        env.ledger().with_mut(|li| li.timestamp += 500);
+        assert_eq!(client.claim_rewards(&staker, &staker, &pool_id, &token_address), 300);
+        let capped = env.events().all().iter().any(|(_, topics, _)| {
+            Symbol::try_from_val(&env, &topics.get(0).unwrap())
+                .is_ok_and(|t| t == Symbol::new(&env, "REWARD_CAPPED"))
+        });
+        assert!(capped);
+
+        // The clamped remainder is carried, not forfeited
+        assert_eq!(client.get_stake(&staker, &pool_id).accrued_rewards, 200);
+
+        env.ledger().with_mut(|li| li.timestamp += 100);
+        assert_eq!(
+            client.try_claim_rewards(&staker, &staker, &pool_id, &token_address),
+            Err(Ok(Error::NoRewardsAvailable))
+        );
+
+        // A new window opens once the period has elapsed
+        env.ledger().with_mut(|li| li.timestamp += 900);
+        assert_eq!(client.claim_rewards(&staker, &staker, &pool_id, &token_address), 300);
+        assert_eq!(reward_token.balance(&staker), 600);
+    }
+
+    #[test]
+    fn test_claim_all_rewards_carries_capped_remainder() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let reward_token = create_reward_token(&env, &client, &admin, pool_id);
+        client.set_reward_cap(&admin, &pool_id, &300, &1_000);
+
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &315_360_000);
+
+        env.ledger().with_mut(|li| li.timestamp += 500);
+        let claimed = client.claim_all_rewards(&staker, &pool_id);
+        assert_eq!(claimed.get(0).unwrap(), (reward_token.address.clone(), 300));
+        assert_eq!(client.get_stake(&staker, &pool_id).accrued_rewards, 200);
+
+        // The next window pays the remainder alongside what accrued since
+        env.ledger().with_mut(|li| li.timestamp += 1_000);
+        assert_eq!(client.get_pending_rewards(&staker, &pool_id), 1_200);
+    }
+
+    #[test]
+    fn test_emergency_unstake_keeps_earned_rewards() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 1_000);
+        let reward_token = create_reward_token(&env, &client, &admin, pool_id);
+
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &315_360_000);
+        env.ledger().with_mut(|li| li.timestamp += 100);
+        client.emergency_unstake(&staker, &pool_id);
+
+        // The principal is gone but the 100 earned before exiting is not
+        let stake = client.get_stake(&staker, &pool_id);
+        assert_eq!(stake.amount, 0);
+        assert_eq!(stake.accrued_rewards, 100);
+        assert_eq!(client.get_pool(&pool_id).total_staked, 0);
+        assert_eq!(client.claim_rewards(&staker, &staker, &pool_id, &reward_token.address), 100);
+    }
+
+    #[test]
+    fn test_emergency_unstake_cooldown_blocks_reentry() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 1_000);
+        client.set_emergency_cooldown(&admin, &pool_id, &3_600);
+
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &1_000_000);
+        client.emergency_unstake(&staker, &pool_id);
+
+        assert_eq!(
+            client.try_stake(&staker, &pool_id, &1_000_000),
+            Err(Ok(Error::EmergencyCooldownActive))
+        );
+
+        // Other stakers are unaffected
+        client.stake(&Address::generate(&env), &pool_id, &1_000_000);
+
+        env.ledger().with_mut(|li| li.timestamp += 3_600);
+        client.stake(&staker, &pool_id, &1_000_000);
+        assert_eq!(client.get_stake(&staker, &pool_id).amount, 1_000_000);
+    }
+
+    #[test]
+    fn test_revoke_vesting_claws_back_unvested() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let reward_token = create_reward_token(&env, &client, &admin, pool_id);
+        let token_address = reward_token.address.clone();
+
         let beneficiary = Address::generate(&env);
+        let schedule_id = client.create_vesting_schedule(
+            &admin,
+            &beneficiary,
+            &pool_id,
+            &1_000,
+            &0,
+            &1_000,
+            &VestingCurve::Linear,
+        );
 
-        RewardDistribution::initialize(env.clone(), admin.clone()).unwrap();
+        env.ledger().with_mut(|li| li.timestamp += 100);
+        assert_eq!(client.claim_vested(&beneficiary, &pool_id, &token_address), 100);
 
-        let pool_id = RewardDistribution::create_pool(
-            env.clone(),
-            admin.clone(),
-            String::from_str(&env, "Test Pool"),
-            1_000,
-            8_000,
-            100_0000000,
-            0,
-        ).unwrap();
+        // Only the named schedule can be revoked
+        env.ledger().with_mut(|li| li.timestamp += 300);
+        assert_eq!(
+            client.try_revoke_vesting(&admin, &beneficiary, &pool_id, &(schedule_id + 1)),
+            Err(Ok(Error::InvalidVestingSchedule))
+        );
 
-        // Create vesting schedule
-        RewardDistribution::create_vesting_schedule(
-            env.clone(),
-            admin,
-            beneficiary.clone(),
-            pool_id,
-            1000_0000000,
-            86400,  // 1 day cliff
-            2592000, // 30 day vesting
-            VestingCurve::Linear,
-        ).unwrap();
+        // 400 vested at revocation, 100 of it already claimed
+        assert_eq!(client.revoke_vesting(&admin, &beneficiary, &pool_id, &schedule_id), 600);
+        assert_eq!(client.get_treasury_balance(&pool_id), 600);
+        assert_eq!(
+            client.try_revoke_vesting(&admin, &beneficiary, &pool_id, &schedule_id),
+            Err(Ok(Error::InvalidVestingSchedule))
+        );
 
-        // Verify schedule
-        let schedule = RewardDistribution::get_vesting(
-            env.clone(),
-            beneficiary.clone(),
-            pool_id,
-        ).unwrap();
+        // Vesting stops: only the vested-to-date remainder is claimable
+        env.ledger().with_mut(|li| li.timestamp += 600);
+        assert_eq!(client.claim_vested(&beneficiary, &pool_id, &token_address), 300);
+        assert_eq!(
+            client.try_claim_vested(&beneficiary, &pool_id, &token_address),
+            Err(Ok(Error::VestingNotStarted))
+        );
+        assert_eq!(reward_token.balance(&beneficiary), 400);
+    }
 
-        assert_eq!(schedule.total_amount, 1000_0000000);
-        assert_eq!(schedule.cliff_duration, 86400);
+    #[test]
+    fn test_multisig_guards_every_emission_adjustment() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let reward_token = create_reward_token(&env, &client, &admin, pool_id);
+        let keeper = Address::generate(&env);
+        client.set_keeper(&admin, &keeper, &true);
+
+        let mut operations = Vec::new(&env);
+        operations.push_back(OP_ADJUST_EMISSION);
+        client.configure_multisig(
+            &admin,
+            &Vec::from_array(&env, [admin.clone(), keeper.clone()]),
+            &2,
+            &3_600,
+            &operations,
+        );
+
+        assert_eq!(
+            client.try_adjust_emission_rate(&admin, &pool_id, &reward_token.address, &1_000_000),
+            Err(Ok(Error::MultisigRequired))
+        );
+        assert_eq!(
+            client.try_set_emission_schedule(&admin, &pool_id, &reward_token.address, &10, &100),
+            Err(Ok(Error::MultisigRequired))
+        );
+        assert_eq!(
+            client.try_auto_adjust_all(&keeper, &pool_id, &1_000_000),
+            Err(Ok(Error::MultisigRequired))
+        );
+    }
+
+    #[test]
+    fn test_multisig_guards_upgrade() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let [alice, bob] = [0; 2].map(|_| Address::generate(&env));
+
+        let mut operations = Vec::new(&env);
+        operations.push_back(OP_UPGRADE);
+        client.configure_multisig(
+            &admin,
+            &Vec::from_array(&env, [alice.clone(), bob.clone()]),
+            &2,
+            &3_600,
+            &operations,
+        );
+
+        let wasm_hash = BytesN::from_array(&env, &[7; 32]);
+        assert_eq!(client.try_upgrade(&admin, &wasm_hash), Err(Ok(Error::MultisigRequired)));
+
+        // A proposal short of the threshold can't upgrade either
+        let action = multisig::action_hash(&env, &OP_UPGRADE, (wasm_hash.clone(),));
+        let proposal_id = client.propose_admin_action(&alice, &OP_UPGRADE, &action);
+        assert_eq!(
+            client.try_execute_upgrade(&proposal_id, &wasm_hash),
+            Err(Ok(Error::ProposalNotExecutable))
+        );
+    }
+
+    #[test]
+    fn test_multisig_pause_executes_after_threshold() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let [alice, bob, carol] = [0; 3].map(|_| Address::generate(&env));
+
+        let mut operations = Vec::new(&env);
+        operations.push_back(OP_SET_PAUSED);
+        client.configure_multisig(
+            &admin,
+            &Vec::from_array(&env, [alice.clone(), bob.clone(), carol.clone()]),
+            &3,
+            &3_600,
+            &operations,
+        );
+
+        // The single admin can no longer pause directly
+        assert_eq!(client.try_set_paused(&admin, &true), Err(Ok(Error::MultisigRequired)));
+
+        let action = multisig::action_hash(&env, &OP_SET_PAUSED, (true,));
+        let proposal_id = client.propose_admin_action(&alice, &OP_SET_PAUSED, &action);
+        assert_eq!(
+            client.try_execute_set_paused(&proposal_id, &true),
+            Err(Ok(Error::ProposalNotExecutable))
+        );
+
+        assert_eq!(client.approve_admin_action(&bob, &proposal_id), 2);
+        assert_eq!(
+            client.try_execute_set_paused(&proposal_id, &true),
+            Err(Ok(Error::ProposalNotExecutable))
+        );
+        assert!(!client.health().1);
+
+        // Approvals can't be reused for different arguments
+        assert_eq!(client.approve_admin_action(&carol, &proposal_id), 3);
+        assert_eq!(
+            client.try_execute_set_paused(&proposal_id, &false),
+            Err(Ok(Error::ProposalNotExecutable))
+        );
+
+        client.execute_set_paused(&proposal_id, &true);
+        assert!(client.health().1);
+        assert!(client.get_admin_proposal(&proposal_id).unwrap().executed);
+        assert_eq!(
+            client.try_execute_set_paused(&proposal_id, &true),
+            Err(Ok(Error::ProposalNotExecutable))
+        );
+    }
+
+    #[test]
+    fn test_accumulated_dust_is_paid_out() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let floor_pool = create_client_pool(&env, &client, &admin, 0);
+        let dust_pool = create_client_pool(&env, &client, &admin, 0);
+        client.set_rounding_mode(&admin, &dust_pool, &RoundingMode::AccumulateDust);
+        let floor_token = create_reward_token(&env, &client, &admin, floor_pool);
+        let dust_token = create_reward_token(&env, &client, &admin, dust_pool);
+
+        // Accrues one and a half tokens per second
+        let staker = Address::generate(&env);
+        client.stake(&staker, &floor_pool, &473_040_000);
+        client.stake(&staker, &dust_pool, &473_040_000);
+
+        let mut floor_paid = 0;
+        let mut dust_paid = 0;
+        for _ in 0..10 {
+            env.ledger().with_mut(|li| li.timestamp += 1);
+            floor_paid += client.claim_rewards(&staker, &staker, &floor_pool, &floor_token.address);
+            dust_paid += client.claim_rewards(&staker, &staker, &dust_pool, &dust_token.address);
+        }
+
+        // Truncation drops half a token per claim; carried dust is paid in full
+        assert_eq!(floor_paid, 10);
+        assert_eq!(dust_paid, 15);
+        assert_eq!(client.get_stake(&staker, &dust_pool).reward_dust, 0);
+    }
+
+    #[test]
+    fn test_closed_pool_rejects_stakes_but_lets_stakers_exit() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 1_000);
+        let reward_token = create_reward_token(&env, &client, &admin, pool_id);
+
+        // Accrues one token per second
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &315_360_000);
+        env.ledger().with_mut(|li| li.timestamp += 100);
+
+        client.update_pool_status(&admin, &pool_id, &RewardStatus::Closed);
+        assert_eq!(client.try_stake(&Address::generate(&env), &pool_id, &1_000), Err(Ok(Error::PoolClosed)));
+        assert_eq!(client.try_get_risk_adjusted_apy(&pool_id), Err(Ok(Error::PoolClosed)));
+        assert_eq!(
+            client.try_update_pool_status(&admin, &pool_id, &RewardStatus::Active),
+            Err(Ok(Error::PoolClosed))
+        );
+
+        // Nothing accrues after closure, and the lock period no longer applies
+        env.ledger().with_mut(|li| li.timestamp += 50);
+        assert_eq!(client.claim_rewards(&staker, &staker, &pool_id, &reward_token.address), 100);
+        client.unstake(&staker, &pool_id, &315_360_000);
+        assert_eq!(client.get_pool(&pool_id).total_staked, 0);
+        assert_eq!(client.get_staked_amount(&staker, &pool_id), 0);
+    }
+
+    #[test]
+    fn test_stake_rejected_above_max_per_address() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        client.set_max_stake_per_address(&admin, &pool_id, &1_000);
+
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &600);
+
+        // The cap applies to the whole position, not each stake
+        assert_eq!(client.try_stake(&staker, &pool_id, &500), Err(Ok(Error::AboveMaximumStake)));
+        client.stake(&staker, &pool_id, &400);
+        assert_eq!(client.get_stake(&staker, &pool_id).amount, 1_000);
+
+        // Lifting the cap restores unlimited staking
+        client.set_max_stake_per_address(&admin, &pool_id, &0);
+        client.stake(&staker, &pool_id, &5_000);
+        assert_eq!(client.get_stake(&staker, &pool_id).amount, 6_000);
+    }
+
+    #[test]
+    fn test_claim_and_migrate_restakes_rewards() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let from_pool = create_client_pool(&env, &client, &admin, 0);
+        let to_pool = create_client_pool(&env, &client, &admin, 0);
+        let reward_token = create_reward_token(&env, &client, &admin, from_pool);
+
+        // Accrues one token per second in from_pool
+        let staker = Address::generate(&env);
+        client.stake(&staker, &from_pool, &315_360_000);
+        env.ledger().with_mut(|li| li.timestamp += 50);
+
+        // to_pool isn't staked in the reward token yet
+        assert_eq!(
+            client.try_claim_and_migrate(&staker, &from_pool, &to_pool, &reward_token.address),
+            Err(Ok(Error::StakeTokenMismatch))
+        );
+        client.set_stake_token(&admin, &to_pool, &reward_token.address);
+
+        // 50 tokens fall short of to_pool's minimum stake, so the claim is rolled back
+        assert_eq!(
+            client.try_claim_and_migrate(&staker, &from_pool, &to_pool, &reward_token.address),
+            Err(Ok(Error::BelowMinimumStake))
+        );
+        assert!(client.get_claim_history(&staker, &from_pool).is_empty());
+
+        env.ledger().with_mut(|li| li.timestamp += 100);
+        let contract_balance = reward_token.balance(&client.address);
+        assert_eq!(client.claim_and_migrate(&staker, &from_pool, &to_pool, &reward_token.address), 150);
+
+        assert_eq!(client.get_stake(&staker, &to_pool).amount, 150);
+        assert_eq!(client.get_pool(&to_pool).total_staked, 150);
+        assert_eq!(client.get_reward_token(&from_pool, &reward_token.address).total_distributed, 150);
+        assert_eq!(reward_token.balance(&client.address), contract_balance);
+        assert_eq!(reward_token.balance(&staker), 0);
+    }
+
+    #[test]
+    fn test_payouts_scale_to_reward_token_decimals() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        let pool_id = create_client_pool(&env, &client, &admin, 0);
+        let six_dp = create_reward_token(&env, &client, &admin, pool_id);
+        let nine_dp = create_reward_token(&env, &client, &admin, pool_id);
+        assert_eq!(client.get_reward_token(&pool_id, &six_dp.address).decimals, 7);
+
+        client.set_reward_token_decimals(&admin, &pool_id, &six_dp.address, &6);
+        client.set_reward_token_decimals(&admin, &pool_id, &nine_dp.address, &9);
+
+        // Earns 1_000 value units (7 decimals) per 1_000 seconds
+        let staker = Address::generate(&env);
+        client.stake(&staker, &pool_id, &315_360_000);
+        env.ledger().with_mut(|li| li.timestamp += 1_000);
+
+        let claimed = client.claim_all_rewards(&staker, &pool_id);
+        assert_eq!(claimed.get(0).unwrap(), (six_dp.address.clone(), 100));
+        assert_eq!(six_dp.balance(&staker), 100);
+
+        env.ledger().with_mut(|li| li.timestamp += 1_000);
+        assert_eq!(client.claim_rewards(&staker, &staker, &pool_id, &nine_dp.address), 100_000);
+        assert_eq!(nine_dp.balance(&staker), 100_000);
+    }
+
+    #[test]
+    fn test_health_reflects_pause_state() {
+        let env = Env::default();
+        let (client, admin) = setup_client(&env);
+        assert_eq!(client.health(), (true, false, SCHEMA_VERSION));
+
+        client.set_paused(&admin, &true);
+        assert_eq!(client.health(), (true, true, SCHEMA_VERSION));
+
+        client.set_paused(&admin, &false);
+        assert_eq!(client.health(), (true, false, SCHEMA_VERSION));
     }
 }
@@ -0,0 +1,182 @@
+//! Storage schema migrations.
+//!
+//! Persisted structs are stored as maps of field name to value, so entries
+//! written before a struct gained a field can't be decoded by later builds.
+//! Each schema version has a step that upgrades the entries it changed in
+//! place, adding new fields with their defaults. Pools and their reward
+//! tokens are reached through the pool count and stake positions through
+//! each pool's staker index. Deployments that predate the staker index (or
+//! entries with no index at all, like vesting schedules) are upgraded per
+//! address with `upgrade_position`.
+
+use soroban_sdk::{symbol_short, Address, Env, IntoVal, Map, Symbol, TryFromVal, Val, Vec};
+use crate::errors::Error;
+use crate::storage;
+use crate::types::{ClaimRecord, RoundingMode};
+
+/// Run the step that upgrades storage from `version` to `version + 1`
+pub fn run_step(env: &Env, version: u32) -> Result<(), Error> {
+    match version {
+        // v1 -> v2: introduces the SCHEMA key itself, no data changes
+        1 => {}
+        // v2 -> v3: pools gain a reward warm-up; vesting schedules and claim
+        // history move off the stake key (see `upgrade_position`)
+        2 => upgrade_pools(env, |pool| {
+            set_default(env, pool, "reward_start_delay", 0u64);
+        }),
+        // v3 -> v4: pools gain an allowlist-only mode
+        3 => upgrade_pools(env, |pool| {
+            set_default(env, pool, "allowlist_only", false);
+        }),
+        // v4 -> v5: stakes keep rewards settled on a partial unstake
+        4 => upgrade_stakes(env),
+        // v5 -> v6: pools gain a claim fee
+        5 => upgrade_pools(env, |pool| {
+            set_default(env, pool, "claim_fee_bps", 0u32);
+        }),
+        // v6 -> v7: pools gain a duration bonus, ramped from each stake's
+        // original stake time
+        6 => {
+            upgrade_pools(env, |pool| {
+                set_default(env, pool, "duration_bonus_bps", 0u32);
+                set_default(env, pool, "duration_bonus_period", 0u64);
+            });
+            upgrade_stakes(env);
+        }
+        // v7 -> v8: pools gain a per-period reward cap
+        7 => upgrade_pools(env, |pool| {
+            set_default(env, pool, "max_rewards_per_period", 0i128);
+            set_default(env, pool, "reward_cap_period", 0u64);
+        }),
+        // v8 -> v9: pools gain an emergency unstake cooldown
+        8 => upgrade_pools(env, |pool| {
+            set_default(env, pool, "emergency_cooldown", 0u64);
+        }),
+        // v9 -> v10: vesting schedules gain revocation and an id. They have
+        // no index, so each is upgraded by `upgrade_position`.
+        9 => {}
+        // v10 -> v11: pools gain a rounding mode and stakes carry dust
+        10 => {
+            upgrade_pools(env, |pool| {
+                set_default(env, pool, "rounding_mode", RoundingMode::Floor);
+            });
+            upgrade_stakes(env);
+        }
+        // v11 -> v12: pools gain a per-address stake limit
+        11 => upgrade_pools(env, |pool| {
+            set_default(env, pool, "max_stake_per_address", 0i128);
+        }),
+        // v12 -> v13: reward tokens record their decimals. Earlier payouts
+        // were made 1:1 in value units, which 7 decimals keeps unchanged.
+        12 => upgrade_reward_tokens(env, |reward_token| {
+            set_default(env, reward_token, "decimals", 7u32);
+        }),
+        _ => return Err(Error::SchemaVersionMismatch),
+    }
+    Ok(())
+}
+
+/// Bring one address's entries in a pool up to the current schema, adding
+/// its stake position to the pool's staker index. Returns whether the
+/// address holds a position.
+pub fn upgrade_position(env: &Env, staker: &Address, pool_id: u32) -> bool {
+    move_legacy_entry(env, staker, pool_id);
+    upgrade_entry(env, &(staker, pool_id, "VESTING"), |vesting| {
+        // v10; schedules from before ids were issued are id 0
+        set_default(env, vesting, "revoked", false);
+        set_default(env, vesting, "schedule_id", 0u32);
+    });
+
+    if !env.storage().persistent().has(&(staker, pool_id)) {
+        return false;
+    }
+
+    upgrade_entry(env, &(staker, pool_id), |stake| upgrade_stake(env, stake));
+    storage::index_pool_staker(env, pool_id, staker);
+    true
+}
+
+/// Add every field stake positions have gained since v2. Each default is
+/// only set when missing, so this is safe to run once per stake version.
+fn upgrade_stake(env: &Env, stake: &mut Map<Symbol, Val>) {
+    // v5
+    set_default(env, stake, "accrued_rewards", 0i128);
+    // v7
+    if let Some(stake_time) = stake.get(Symbol::new(env, "stake_time")) {
+        set_default(env, stake, "bonus_start_time", stake_time);
+    }
+    // v11
+    set_default(env, stake, "reward_dust", 0i128);
+}
+
+/// Before v3, vesting schedules and claim history were written under the
+/// `(address, pool_id)` stake key; move whichever is there to its own key
+fn move_legacy_entry(env: &Env, staker: &Address, pool_id: u32) {
+    let storage = env.storage().persistent();
+    let legacy_key = (staker, pool_id);
+    let entry: Val = match storage.get(&legacy_key) {
+        Some(entry) => entry,
+        None => return,
+    };
+
+    if let Ok(mut history) = Vec::<ClaimRecord>::try_from_val(env, &entry) {
+        history.append(&storage::get_claim_history(env, staker, pool_id));
+        storage::set_claim_history(env, staker, pool_id, &history);
+        storage.remove(&legacy_key);
+    } else if let Ok(fields) = Map::<Symbol, Val>::try_from_val(env, &entry) {
+        if fields.contains_key(Symbol::new(env, "cliff_duration")) {
+            let vesting_key = (staker, pool_id, "VESTING");
+            if !storage.has(&vesting_key) {
+                storage.set(&vesting_key, &entry);
+            }
+            storage.remove(&legacy_key);
+        }
+    }
+}
+
+/// Apply `upgrade` to the field map of the struct stored under `key`, if any
+fn upgrade_entry<K: IntoVal<Env, Val>>(env: &Env, key: &K, upgrade: impl Fn(&mut Map<Symbol, Val>)) {
+    let storage = env.storage().persistent();
+    if let Some(mut entry) = storage.get::<K, Map<Symbol, Val>>(key) {
+        upgrade(&mut entry);
+        storage.set(key, &entry);
+    }
+}
+
+/// Add `field` to an entry written before it existed
+fn set_default<T: IntoVal<Env, Val>>(env: &Env, entry: &mut Map<Symbol, Val>, field: &str, default: T) {
+    let field = Symbol::new(env, field);
+    if !entry.contains_key(field.clone()) {
+        entry.set(field, default.into_val(env));
+    }
+}
+
+fn upgrade_pools(env: &Env, upgrade: impl Fn(&mut Map<Symbol, Val>)) {
+    let pool_count: u32 = env.storage().instance().get(&symbol_short!("POOL_CNT")).unwrap_or(0);
+    for pool_id in 1..=pool_count {
+        upgrade_entry(env, &(pool_id,), &upgrade);
+    }
+}
+
+fn upgrade_reward_tokens(env: &Env, upgrade: impl Fn(&mut Map<Symbol, Val>)) {
+    let pool_count: u32 = env.storage().instance().get(&symbol_short!("POOL_CNT")).unwrap_or(0);
+    for pool_id in 1..=pool_count {
+        let pool: Option<Map<Symbol, Val>> = env.storage().persistent().get(&(pool_id,));
+        let tokens = pool
+            .and_then(|pool| pool.get(Symbol::new(env, "reward_tokens")))
+            .and_then(|tokens| Vec::<Address>::try_from_val(env, &tokens).ok())
+            .unwrap_or(Vec::new(env));
+        for token in tokens.iter() {
+            upgrade_entry(env, &(pool_id, token), &upgrade);
+        }
+    }
+}
+
+fn upgrade_stakes(env: &Env) {
+    let pool_count: u32 = env.storage().instance().get(&symbol_short!("POOL_CNT")).unwrap_or(0);
+    for pool_id in 1..=pool_count {
+        for staker in storage::get_pool_stakers(env, pool_id).iter() {
+            upgrade_entry(env, &(staker, pool_id), |stake| upgrade_stake(env, stake));
+        }
+    }
+}
@@ -26,12 +26,75 @@ pub fn get_stake(env: &Env, staker: &Address, pool_id: u32) -> Option<StakePosit
 
 pub fn set_stake(env: &Env, stake: &StakePosition) {
     let key = (&stake.staker, stake.pool_id);
+    if !env.storage().persistent().has(&key) {
+        let mut stakers = get_pool_stakers(env, stake.pool_id);
+        stakers.push_back(stake.staker.clone());
+        env.storage().persistent().set(&(stake.pool_id, "STAKERS"), &stakers);
+    }
     env.storage().persistent().set(&key, stake);
 }
 
 pub fn remove_stake(env: &Env, staker: &Address, pool_id: u32) {
     let key = (staker, pool_id);
     env.storage().persistent().remove(&key);
+
+    let mut stakers = get_pool_stakers(env, pool_id);
+    if let Some(index) = stakers.first_index_of(staker) {
+        stakers.remove(index);
+        env.storage().persistent().set(&(pool_id, "STAKERS"), &stakers);
+    }
+}
+
+// Index of stakers holding a position in a pool
+pub fn get_pool_stakers(env: &Env, pool_id: u32) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&(pool_id, "STAKERS"))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn index_pool_staker(env: &Env, pool_id: u32, staker: &Address) {
+    let mut stakers = get_pool_stakers(env, pool_id);
+    if !stakers.contains(staker) {
+        stakers.push_back(staker.clone());
+        env.storage().persistent().set(&(pool_id, "STAKERS"), &stakers);
+    }
+}
+
+// Staker allowlist storage
+pub fn is_staker_allowed(env: &Env, pool_id: u32, staker: &Address) -> bool {
+    let key = (pool_id, staker, "ALLOWED");
+    env.storage().persistent().get(&key).unwrap_or(false)
+}
+
+pub fn set_staker_allowed(env: &Env, pool_id: u32, staker: &Address, allowed: bool) {
+    let key = (pool_id, staker, "ALLOWED");
+    env.storage().persistent().set(&key, &allowed);
+}
+
+// Claim delegate storage
+pub fn get_claim_delegate(env: &Env, staker: &Address, pool_id: u32) -> Option<Address> {
+    let key = (staker, pool_id, "CLM_DLG");
+    env.storage().persistent().get(&key)
+}
+
+pub fn set_claim_delegate(env: &Env, staker: &Address, pool_id: u32, delegate: &Address) {
+    let key = (staker, pool_id, "CLM_DLG");
+    env.storage().persistent().set(&key, delegate);
+}
+
+pub fn remove_claim_delegate(env: &Env, staker: &Address, pool_id: u32) {
+    let key = (staker, pool_id, "CLM_DLG");
+    env.storage().persistent().remove(&key);
+}
+
+// Rewards a scheduled token held back from a staker's claim, in its native units
+pub fn get_held_rewards(env: &Env, staker: &Address, pool_id: u32, token: &Address) -> i128 {
+    env.storage().persistent().get(&(staker, pool_id, token, "HELD")).unwrap_or(0)
+}
+
+pub fn set_held_rewards(env: &Env, staker: &Address, pool_id: u32, token: &Address, amount: i128) {
+    env.storage().persistent().set(&(staker, pool_id, token, "HELD"), &amount);
 }
 
 // Reward token storage
@@ -45,14 +108,74 @@ pub fn set_reward_token(env: &Env, pool_id: u32, token: &RewardToken) {
     env.storage().persistent().set(&key, token);
 }
 
+// Per-token emission adjustment timestamp
+pub fn get_emission_adjusted_at(env: &Env, pool_id: u32, token: &Address) -> Option<u64> {
+    let key = (pool_id, token, "EMIT_AT");
+    env.storage().persistent().get(&key)
+}
+
+pub fn set_emission_adjusted_at(env: &Env, pool_id: u32, token: &Address, timestamp: u64) {
+    let key = (pool_id, token, "EMIT_AT");
+    env.storage().persistent().set(&key, &timestamp);
+}
+
+// Emission schedule storage
+pub fn get_emission_schedule(env: &Env, pool_id: u32, token: &Address) -> Option<EmissionSchedule> {
+    let key = (pool_id, token, "SCHEDULE");
+    env.storage().persistent().get(&key)
+}
+
+pub fn set_emission_schedule(env: &Env, pool_id: u32, token: &Address, schedule: &EmissionSchedule) {
+    let key = (pool_id, token, "SCHEDULE");
+    env.storage().persistent().set(&key, schedule);
+}
+
+// KYC-level reward multiplier storage
+pub fn get_kyc_config(env: &Env, pool_id: u32) -> Option<KycRewardConfig> {
+    let key = (pool_id, "KYC_MULT");
+    env.storage().persistent().get(&key)
+}
+
+pub fn set_kyc_config(env: &Env, pool_id: u32, config: &KycRewardConfig) {
+    let key = (pool_id, "KYC_MULT");
+    env.storage().persistent().set(&key, config);
+}
+
+pub fn remove_kyc_config(env: &Env, pool_id: u32) {
+    let key = (pool_id, "KYC_MULT");
+    env.storage().persistent().remove(&key);
+}
+
+// Keeper registry
+pub fn is_keeper(env: &Env, keeper: &Address) -> bool {
+    let key = (keeper, "KEEPER");
+    env.storage().persistent().get(&key).unwrap_or(false)
+}
+
+pub fn set_keeper(env: &Env, keeper: &Address, enabled: bool) {
+    let key = (keeper, "KEEPER");
+    env.storage().persistent().set(&key, &enabled);
+}
+
+// Treasury balance accumulated from slashed stakes
+pub fn get_treasury_balance(env: &Env, pool_id: u32) -> i128 {
+    let key = (pool_id, "TREASURY");
+    env.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn set_treasury_balance(env: &Env, pool_id: u32, balance: i128) {
+    let key = (pool_id, "TREASURY");
+    env.storage().persistent().set(&key, &balance);
+}
+
 // Vesting schedule storage
 pub fn get_vesting(env: &Env, beneficiary: &Address, pool_id: u32) -> Option<VestingSchedule> {
-    let key = (beneficiary, pool_id);
+    let key = (beneficiary, pool_id, "VESTING");
     env.storage().persistent().get(&key)
 }
 
 pub fn set_vesting(env: &Env, beneficiary: &Address, pool_id: u32, schedule: &VestingSchedule) {
-    let key = (beneficiary, pool_id);
+    let key = (beneficiary, pool_id, "VESTING");
     env.storage().persistent().set(&key, schedule);
 }
 
@@ -67,20 +190,112 @@ pub fn set_metrics(env: &Env, metrics: &PerformanceMetrics) {
     env.storage().persistent().set(&key, metrics);
 }
 
+// APY history storage
+pub fn get_apy_history(env: &Env, pool_id: u32) -> Vec<(u64, u32)> {
+    let key = (pool_id, "APY_HIST");
+    env.storage().persistent().get(&key).unwrap_or(Vec::new(env))
+}
+
+pub fn add_apy_point(env: &Env, pool_id: u32, timestamp: u64, apy: u32, max_points: u32) {
+    let key = (pool_id, "APY_HIST");
+    let mut history = get_apy_history(env, pool_id);
+
+    history.push_back((timestamp, apy));
+    while history.len() > max_points {
+        history.pop_front();
+    }
+
+    env.storage().persistent().set(&key, &history);
+}
+
+// Emission adjustment history storage
+pub fn get_emission_history(env: &Env, pool_id: u32, token: &Address) -> Vec<EmissionAdjustment> {
+    env.storage()
+        .persistent()
+        .get(&(pool_id, token, "EMIT_HIST"))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn add_emission_adjustment(
+    env: &Env,
+    pool_id: u32,
+    token: &Address,
+    adjustment: &EmissionAdjustment,
+    max_entries: u32,
+) {
+    let mut history = get_emission_history(env, pool_id, token);
+
+    history.push_back(adjustment.clone());
+    while history.len() > max_entries {
+        history.pop_front();
+    }
+
+    env.storage().persistent().set(&(pool_id, token, "EMIT_HIST"), &history);
+}
+
+// Claim fees collected per pool and token, awaiting withdrawal
+pub fn get_accrued_fees(env: &Env, pool_id: u32, token: &Address) -> i128 {
+    env.storage().persistent().get(&(pool_id, token, "FEES")).unwrap_or(0)
+}
+
+pub fn set_accrued_fees(env: &Env, pool_id: u32, token: &Address, amount: i128) {
+    env.storage().persistent().set(&(pool_id, token, "FEES"), &amount);
+}
+
+// Reward cap window per pool and token: (window start, paid in window)
+pub fn get_reward_cap_window(env: &Env, pool_id: u32, token: &Address) -> (u64, i128) {
+    env.storage().persistent().get(&(pool_id, token, "CAP_WIN")).unwrap_or((0, 0))
+}
+
+pub fn set_reward_cap_window(env: &Env, pool_id: u32, token: &Address, window: &(u64, i128)) {
+    env.storage().persistent().set(&(pool_id, token, "CAP_WIN"), window);
+}
+
+// Time a pool was closed; rewards stop accruing from then on
+pub fn get_pool_closed_at(env: &Env, pool_id: u32) -> Option<u64> {
+    env.storage().persistent().get(&(pool_id, "CLOSED_AT"))
+}
+
+pub fn set_pool_closed_at(env: &Env, pool_id: u32, closed_at: u64) {
+    env.storage().persistent().set(&(pool_id, "CLOSED_AT"), &closed_at);
+}
+
+// End of a staker's re-entry cooldown after an emergency unstake
+pub fn get_emergency_cooldown_end(env: &Env, staker: &Address, pool_id: u32) -> u64 {
+    env.storage().persistent().get(&(staker, pool_id, "EMERG_CD")).unwrap_or(0)
+}
+
+pub fn set_emergency_cooldown_end(env: &Env, staker: &Address, pool_id: u32, until: u64) {
+    env.storage().persistent().set(&(staker, pool_id, "EMERG_CD"), &until);
+}
+
+// Token a pool's stake is denominated in, used to restake rewards into it
+pub fn get_stake_token(env: &Env, pool_id: u32) -> Option<Address> {
+    env.storage().persistent().get(&(pool_id, "STK_TKN"))
+}
+
+pub fn set_stake_token(env: &Env, pool_id: u32, token: &Address) {
+    env.storage().persistent().set(&(pool_id, "STK_TKN"), token);
+}
+
 // Claim history storage
 pub fn add_claim_record(env: &Env, record: &ClaimRecord) {
     let mut history: Vec<ClaimRecord> = env.storage()
         .persistent()
-        .get(&(&record.claimer, record.pool_id))
+        .get(&(&record.claimer, record.pool_id, "CLAIMS"))
         .unwrap_or(Vec::new(env));
     
     history.push_back(record.clone());
-    env.storage().persistent().set(&(&record.claimer, record.pool_id), &history);
+    env.storage().persistent().set(&(&record.claimer, record.pool_id, "CLAIMS"), &history);
 }
 
 pub fn get_claim_history(env: &Env, claimer: &Address, pool_id: u32) -> Vec<ClaimRecord> {
     env.storage()
         .persistent()
-        .get(&(claimer, pool_id))
+        .get(&(claimer, pool_id, "CLAIMS"))
         .unwrap_or(Vec::new(env))
 }
+
+pub fn set_claim_history(env: &Env, claimer: &Address, pool_id: u32, history: &Vec<ClaimRecord>) {
+    env.storage().persistent().set(&(claimer, pool_id, "CLAIMS"), history);
+}
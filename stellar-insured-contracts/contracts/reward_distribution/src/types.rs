@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, String, Vec};
+use soroban_sdk::{contracttype, Address, Map, String, Vec};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 #[contracttype]
@@ -14,6 +14,7 @@ pub enum RewardStatus {
     Active,
     Paused,
     Completed,
+    Closed,                           // Terminal: no new stakes, rewards stop accruing
 }
 
 #[contracttype]
@@ -24,6 +25,7 @@ pub struct RewardToken {
     pub total_allocated: i128,
     pub total_distributed: i128,
     pub active: bool,
+    pub decimals: u32,            // Native precision APY-based payouts are scaled to
 }
 
 #[contracttype]
@@ -35,10 +37,21 @@ pub struct VestingSchedule {
     pub start_time: u64,
     pub total_amount: i128,
     pub claimed_amount: i128,
+    pub revoked: bool,            // Frozen at the amount vested when revoked
+    pub schedule_id: u32,         // Grant identifier, named when revoking
 }
 
+/// How sub-unit reward remainders are handled when a payout is settled
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[contracttype]
-#[derive(Clone)]
+pub enum RoundingMode {
+    Floor,          // Truncate; remainders are lost
+    Round,          // Round half up; remainders are dropped or topped up
+    AccumulateDust, // Carry remainders on the position until they add up to a unit
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
 pub struct StakePosition {
     pub staker: Address,
     pub pool_id: u32,
@@ -46,6 +59,9 @@ pub struct StakePosition {
     pub stake_time: u64,
     pub last_claim_time: u64,
     pub performance_multiplier: u32,  // Basis points (10000 = 1x)
+    pub accrued_rewards: i128,        // Settled but unclaimed rewards
+    pub bonus_start_time: u64,        // Start of the holding period for the duration bonus
+    pub reward_dust: i128,            // Carried sub-unit remainder, in 1/REWARD_PRECISION units
 }
 
 #[contracttype]
@@ -60,6 +76,16 @@ pub struct RewardPool {
     pub status: RewardStatus,
     pub min_stake: i128,
     pub lock_period: u64,             // Minimum lock duration
+    pub reward_start_delay: u64,      // Warm-up before rewards accrue
+    pub allowlist_only: bool,         // Only allowlisted stakers may stake
+    pub claim_fee_bps: u32,           // Protocol fee taken from each claim
+    pub duration_bonus_bps: u32,      // Extra multiplier reached by long-held stakes
+    pub duration_bonus_period: u64,   // Holding time to reach the full duration bonus
+    pub max_rewards_per_period: i128, // Cap on rewards paid per token per period (0 = uncapped)
+    pub reward_cap_period: u64,       // Length of the reward cap window
+    pub emergency_cooldown: u64,      // Re-entry block after an emergency unstake
+    pub rounding_mode: RoundingMode,  // Treatment of sub-unit reward remainders
+    pub max_stake_per_address: i128,  // Cap on a single position's stake (0 = uncapped)
 }
 
 #[contracttype]
@@ -81,6 +107,40 @@ pub struct EmissionConfig {
     pub last_adjustment: u64,
 }
 
+#[contracttype]
+#[derive(Clone)]
+pub struct EmissionAdjustment {
+    pub timestamp: u64,
+    pub old_rate: i128,
+    pub new_rate: i128,
+    pub total_supply: i128,
+    pub time_elapsed: u64,            // Seconds since the previous adjustment
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct EmissionSchedule {
+    pub initial_rate: i128,           // Tokens per second before any halving
+    pub halving_interval: u64,        // Seconds between halvings
+    pub start_time: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct BonusWeights {
+    pub utilization: u32,             // Max bonus in basis points per metric
+    pub claim_ratio: u32,
+    pub volatility: u32,
+    pub counterparty: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct KycRewardConfig {
+    pub kyc_contract: Address,        // kyc_integration contract to query
+    pub multipliers: Map<u32, u32>,   // KYC level -> multiplier in basis points
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct PerformanceMetrics {
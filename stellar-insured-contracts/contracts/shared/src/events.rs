@@ -22,7 +22,7 @@ pub enum EventCategory {
 pub enum EventSeverity {
     Info,
     Warning,
-    Error,
+    Failure,
     Critical,
 }
 
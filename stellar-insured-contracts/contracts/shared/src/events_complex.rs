@@ -581,7 +581,7 @@ pub mod events {
             env,
             EventCategory::Authorization,
             "AuthorizationError",
-            EventSeverity::Error,
+            EventSeverity::Failure,
             actor,
             contract,
         )
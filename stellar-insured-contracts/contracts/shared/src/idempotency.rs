@@ -0,0 +1,66 @@
+//! # Idempotency Keys
+//!
+//! A relayer that retries a `create_*` transaction shouldn't create a second
+//! record. Callers may pass an optional idempotency key; the id created under
+//! it is remembered in temporary storage under
+//! `(IDEM_KEY, operation, caller, key)` and returned on a retry instead of
+//! creating a duplicate. Keys are scoped per caller and operation, so two
+//! callers (or two kinds of record) never collide.
+
+use soroban_sdk::{symbol_short, Address, BytesN, Env, Symbol};
+
+const IDEMPOTENCY_KEY: Symbol = symbol_short!("IDEM_KEY");
+
+/// Id previously created by `caller` under an idempotency key, if any
+pub fn idempotent_id(env: &Env, operation: Symbol, caller: &Address, key: &Option<BytesN<32>>) -> Option<u64> {
+    key.as_ref().and_then(|key| {
+        env.storage()
+            .temporary()
+            .get(&(IDEMPOTENCY_KEY, operation, caller.clone(), key.clone()))
+    })
+}
+
+/// Remember the id created by `caller` under an idempotency key
+pub fn remember_idempotent_id(env: &Env, operation: Symbol, caller: &Address, key: &Option<BytesN<32>>, id: u64) {
+    if let Some(key) = key {
+        env.storage()
+            .temporary()
+            .set(&(IDEMPOTENCY_KEY, operation, caller.clone(), key.clone()), &id);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, testutils::Address as _};
+
+    #[contract]
+    struct IdempotencyHost;
+
+    #[contractimpl]
+    impl IdempotencyHost {}
+
+    #[test]
+    fn test_keys_are_scoped_per_caller_and_operation() {
+        let env = Env::default();
+        let host = env.register_contract(None, IdempotencyHost);
+
+        env.as_contract(&host, || {
+            let alice = Address::generate(&env);
+            let bob = Address::generate(&env);
+            let key = Some(BytesN::from_array(&env, &[7u8; 32]));
+            let dash = symbol_short!("dash");
+
+            assert_eq!(idempotent_id(&env, dash.clone(), &alice, &key), None);
+            remember_idempotent_id(&env, dash.clone(), &alice, &key, 42);
+            assert_eq!(idempotent_id(&env, dash.clone(), &alice, &key), Some(42));
+
+            assert_eq!(idempotent_id(&env, dash.clone(), &bob, &key), None);
+            assert_eq!(idempotent_id(&env, symbol_short!("rule"), &alice, &key), None);
+
+            // Without a key nothing is remembered or returned
+            remember_idempotent_id(&env, dash.clone(), &alice, &None, 43);
+            assert_eq!(idempotent_id(&env, dash, &alice, &None), None);
+        });
+    }
+}
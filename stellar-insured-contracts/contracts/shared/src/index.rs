@@ -0,0 +1,158 @@
+//! # Append-Only Storage Indexes
+//!
+//! Soroban can't enumerate storage keys, so any "list all X for Y" view needs
+//! an index maintained alongside the records. [`AppendIndex`] keeps one as a
+//! persistent `Vec` under `(prefix, key)`, e.g. `(USER_DASH, owner)`, and
+//! serves it a page at a time.
+//!
+//! Every write and every non-empty read extends the index's TTL, so an index
+//! that is still being used doesn't expire before the records it points to.
+
+use core::marker::PhantomData;
+use soroban_sdk::{Env, IntoVal, Symbol, TryFromVal, Val, Vec};
+
+/// Remaining ledgers below which an index's TTL is extended (~1 day)
+const INDEX_TTL_THRESHOLD: u32 = 17_280;
+
+/// Ledgers an index's TTL is extended to (~30 days)
+const INDEX_TTL_EXTEND_TO: u32 = 518_400;
+
+/// Persistent list of `T` entries stored under `(prefix, key)`
+pub struct AppendIndex<T> {
+    storage_key: Val,
+    _entry: PhantomData<T>,
+}
+
+impl<T> AppendIndex<T>
+where
+    T: IntoVal<Env, Val> + TryFromVal<Env, Val> + Clone,
+{
+    pub fn new<K: IntoVal<Env, Val>>(env: &Env, prefix: Symbol, key: K) -> Self {
+        let key: Val = key.into_val(env);
+        Self {
+            storage_key: (prefix, key).into_val(env),
+            _entry: PhantomData,
+        }
+    }
+
+    fn load(&self, env: &Env) -> Vec<T> {
+        let storage_key = self.storage_key;
+        let storage = env.storage().persistent();
+        match storage.get::<Val, Vec<T>>(&storage_key) {
+            Some(entries) => {
+                storage.extend_ttl(&storage_key, INDEX_TTL_THRESHOLD, INDEX_TTL_EXTEND_TO);
+                entries
+            }
+            None => Vec::new(env),
+        }
+    }
+
+    fn store(&self, env: &Env, entries: &Vec<T>) {
+        let storage_key = self.storage_key;
+        let storage = env.storage().persistent();
+        if entries.is_empty() {
+            storage.remove(&storage_key);
+        } else {
+            storage.set(&storage_key, entries);
+            storage.extend_ttl(&storage_key, INDEX_TTL_THRESHOLD, INDEX_TTL_EXTEND_TO);
+        }
+    }
+
+    /// Append `entry` to the end of the index
+    pub fn push(&self, env: &Env, entry: &T) {
+        let mut entries = self.load(env);
+        entries.push_back(entry.clone());
+        self.store(env, &entries);
+    }
+
+    /// Remove the first occurrence of `entry`. Returns whether it was present.
+    pub fn remove(&self, env: &Env, entry: &T) -> bool {
+        let mut entries = self.load(env);
+        match entries.first_index_of(entry.clone()) {
+            Some(index) => {
+                entries.remove(index);
+                self.store(env, &entries);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn contains(&self, env: &Env, entry: &T) -> bool {
+        self.load(env).contains(entry.clone())
+    }
+
+    pub fn len(&self, env: &Env) -> u32 {
+        self.load(env).len()
+    }
+
+    pub fn is_empty(&self, env: &Env) -> bool {
+        self.len(env) == 0
+    }
+
+    /// Up to `limit` entries starting at `offset`, in insertion order. An
+    /// offset past the end yields an empty page.
+    pub fn page(&self, env: &Env, offset: u32, limit: u32) -> Vec<T> {
+        let entries = self.load(env);
+        let start = offset.min(entries.len());
+        let end = start.saturating_add(limit).min(entries.len());
+        entries.slice(start..end)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, symbol_short, testutils::Address as _, Address};
+
+    #[contract]
+    struct IndexHost;
+
+    #[contractimpl]
+    impl IndexHost {}
+
+    #[test]
+    fn test_page_boundaries() {
+        let env = Env::default();
+        let host = env.register_contract(None, IndexHost);
+
+        env.as_contract(&host, || {
+            let index = AppendIndex::<u64>::new(&env, symbol_short!("IDX"), Address::generate(&env));
+            assert!(index.page(&env, 0, 10).is_empty());
+
+            for id in 1..=5u64 {
+                index.push(&env, &id);
+            }
+
+            assert_eq!(index.page(&env, 0, 2), Vec::from_array(&env, [1u64, 2]));
+            assert_eq!(index.page(&env, 3, 10), Vec::from_array(&env, [4u64, 5]));
+            assert_eq!(index.page(&env, 4, 1), Vec::from_array(&env, [5u64]));
+            assert!(index.page(&env, 5, 10).is_empty());
+            assert!(index.page(&env, 2, 0).is_empty());
+            assert_eq!(index.len(&env), 5);
+        });
+    }
+
+    #[test]
+    fn test_remove_keeps_order_and_isolates_keys() {
+        let env = Env::default();
+        let host = env.register_contract(None, IndexHost);
+
+        env.as_contract(&host, || {
+            let alice = AppendIndex::<u64>::new(&env, symbol_short!("IDX"), Address::generate(&env));
+            let bob = AppendIndex::<u64>::new(&env, symbol_short!("IDX"), Address::generate(&env));
+            for id in [10u64, 20, 30] {
+                alice.push(&env, &id);
+            }
+            bob.push(&env, &20);
+
+            assert!(alice.remove(&env, &20));
+            assert!(!alice.remove(&env, &20));
+            assert_eq!(alice.page(&env, 0, 10), Vec::from_array(&env, [10u64, 30]));
+            assert!(bob.contains(&env, &20));
+
+            assert!(bob.remove(&env, &20));
+            assert!(bob.is_empty(&env));
+        });
+    }
+}
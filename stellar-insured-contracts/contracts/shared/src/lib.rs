@@ -10,6 +10,11 @@
 //! - `types`      – Shared data types and enums (PolicyStatus, ClaimStatus, etc.)
 //! - `constants`  – Configuration constants for validation and limits
 //! - `validation` – Centralized, domain-specific validation helper functions
+//! - `rng`        – Ledger-seeded, non-replayable 32-byte values
+//! - `multisig`   – M-of-N propose/approve/execute for admin operations
+//! - `index`      – Paginated persistent indexes for list views
+//! - `idempotency` – Retry-safe ids for `create_*` operations
+//! - `metrics`    – Registry of metric names monitoring contracts accept
 //!
 //! ## Usage
 //!
@@ -38,6 +43,11 @@ pub mod upgradeable;
 // pub mod gas_optimization;
 // pub mod emergency_pause;
 pub mod events;
+pub mod rng;
+pub mod multisig;
+pub mod index;
+pub mod idempotency;
+pub mod metrics;
 // pub mod audit_events;
 // pub mod event_verification;
 
@@ -56,7 +66,7 @@ pub use types::{
     // DID (Decentralized Identity) types
     DidDocument, VerificationMethod, PublicKeyJwk, DidService, ServiceProperty,
     IdentityVerification, KycRecord, ZkIdentityProof, DidResolutionResult,
-    MetadataProperty,
+    MetadataProperty, RevocationInfo,
 };
 
 pub use constants::{
@@ -0,0 +1,97 @@
+//! # Metric Registry
+//!
+//! Monitoring contracts only accept data for metric names an admin has
+//! registered, so a typo such as `gas_usd` is rejected instead of silently
+//! starting an orphan series. Definitions live under `(MET_REG, name)` with
+//! the registered names listed under `MET_NAMES`.
+//!
+//! The helpers don't check authorization; callers gate [`register_metric`]
+//! behind their own admin check.
+
+use soroban_sdk::{contracttype, symbol_short, Env, Symbol, Vec};
+
+const METRIC_REGISTRY: Symbol = symbol_short!("MET_REG");
+const METRIC_NAMES: Symbol = symbol_short!("MET_NAMES");
+
+/// Registered metric definition
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MetricDefinition {
+    /// Metric name
+    pub name: Symbol,
+    /// Metric unit (e.g., "gas", "ms", "count")
+    pub unit: Symbol,
+    /// Registered at timestamp
+    pub registered_at: u64,
+}
+
+/// Whether `name` has been registered
+pub fn is_registered(env: &Env, name: &Symbol) -> bool {
+    env.storage().persistent().has(&(METRIC_REGISTRY, name.clone()))
+}
+
+/// Register `name` with `unit`. Returns false, changing nothing, if the
+/// name is already registered.
+pub fn register_metric(env: &Env, name: &Symbol, unit: &Symbol) -> bool {
+    if is_registered(env, name) {
+        return false;
+    }
+
+    let definition = MetricDefinition {
+        name: name.clone(),
+        unit: unit.clone(),
+        registered_at: env.ledger().timestamp(),
+    };
+    env.storage().persistent().set(&(METRIC_REGISTRY, name.clone()), &definition);
+
+    let mut names: Vec<Symbol> = env.storage().persistent().get(&METRIC_NAMES).unwrap_or(Vec::new(env));
+    names.push_back(name.clone());
+    env.storage().persistent().set(&METRIC_NAMES, &names);
+
+    true
+}
+
+/// All registered metric definitions, in registration order
+pub fn list_registered_metrics(env: &Env) -> Vec<MetricDefinition> {
+    let names: Vec<Symbol> = env.storage().persistent().get(&METRIC_NAMES).unwrap_or(Vec::new(env));
+    let mut definitions = Vec::new(env);
+    for name in names.iter() {
+        if let Some(definition) = env.storage().persistent().get(&(METRIC_REGISTRY, name)) {
+            definitions.push_back(definition);
+        }
+    }
+    definitions
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl};
+
+    #[contract]
+    struct RegistryHost;
+
+    #[contractimpl]
+    impl RegistryHost {}
+
+    #[test]
+    fn test_register_rejects_duplicates() {
+        let env = Env::default();
+        let host = env.register_contract(None, RegistryHost);
+
+        env.as_contract(&host, || {
+            let gas_used = Symbol::new(&env, "gas_used");
+            assert!(!is_registered(&env, &gas_used));
+
+            assert!(register_metric(&env, &gas_used, &symbol_short!("gas")));
+            assert!(!register_metric(&env, &gas_used, &symbol_short!("ms")));
+            assert!(register_metric(&env, &symbol_short!("error"), &symbol_short!("count")));
+
+            assert!(is_registered(&env, &gas_used));
+            let definitions = list_registered_metrics(&env);
+            assert_eq!(definitions.len(), 2);
+            assert_eq!(definitions.get(0).unwrap().unit, symbol_short!("gas"));
+            assert_eq!(definitions.get(1).unwrap().name, symbol_short!("error"));
+        });
+    }
+}
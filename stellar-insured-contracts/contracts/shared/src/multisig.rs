@@ -0,0 +1,215 @@
+//! # Multi-Signature Admin Operations
+//!
+//! M-of-N approval for high-privilege operations. A signer proposes an
+//! action, other signers approve it, and once `threshold` approvals are in
+//! the contract executes it, all within `window_secs` of the proposal.
+//!
+//! Each proposal is bound to an operation symbol and an action hash of the
+//! operation's arguments (see [`action_hash`]), so approvals collected for one
+//! set of arguments can't be used to execute another.
+//!
+//! ## Storage Safety
+//!
+//! This module reserves the following storage keys in the calling contract:
+//! - `MS_CFG` and `MS_NEXT` (instance)
+//! - `(MS_PROP, proposal_id)` (persistent)
+
+use soroban_sdk::{
+    contracterror, contracttype, symbol_short, xdr::ToXdr, Address, BytesN, Env, IntoVal,
+    Symbol, Val, Vec,
+};
+
+const MULTISIG_CONFIG: Symbol = symbol_short!("MS_CFG");
+const NEXT_PROPOSAL_ID: Symbol = symbol_short!("MS_NEXT");
+const PROPOSAL: Symbol = symbol_short!("MS_PROP");
+
+/// Multisig-related errors
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum MultisigError {
+    /// Threshold is zero or exceeds the signer count, or the window is zero
+    InvalidConfig = 1,
+
+    /// Multisig has not been configured for this contract
+    NotConfigured = 2,
+
+    /// Caller is not one of the configured signers
+    NotSigner = 3,
+
+    /// No proposal exists with the given id
+    ProposalNotFound = 4,
+
+    /// Signer has already approved this proposal
+    AlreadyApproved = 5,
+
+    /// Approval window has elapsed
+    ProposalExpired = 6,
+
+    /// Fewer than `threshold` signers have approved
+    ThresholdNotMet = 7,
+
+    /// Proposal has already been executed
+    AlreadyExecuted = 8,
+
+    /// Operation or arguments differ from what was proposed
+    ActionMismatch = 9,
+}
+
+/// Signer set and the operations that require their approval
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MultisigConfig {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+    /// Seconds after proposal during which approvals and execution are accepted
+    pub window_secs: u64,
+    pub operations: Vec<Symbol>,
+}
+
+/// A pending or executed admin action
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MultisigProposal {
+    pub id: u64,
+    pub operation: Symbol,
+    pub action: BytesN<32>,
+    pub approvals: Vec<Address>,
+    pub created_at: u64,
+    pub executed: bool,
+}
+
+/// Store the signer set, validating the threshold and window
+pub fn configure(env: &Env, config: &MultisigConfig) -> Result<(), MultisigError> {
+    if config.threshold == 0
+        || config.threshold > config.signers.len()
+        || config.window_secs == 0
+    {
+        return Err(MultisigError::InvalidConfig);
+    }
+    env.storage().instance().set(&MULTISIG_CONFIG, config);
+    Ok(())
+}
+
+pub fn get_config(env: &Env) -> Option<MultisigConfig> {
+    env.storage().instance().get(&MULTISIG_CONFIG)
+}
+
+/// Whether `operation` must go through propose/approve/execute
+pub fn is_guarded(env: &Env, operation: &Symbol) -> bool {
+    get_config(env).is_some_and(|config| config.operations.contains(operation))
+}
+
+/// Hash identifying an operation invoked with specific arguments
+pub fn action_hash<T: IntoVal<Env, Val>>(env: &Env, operation: &Symbol, args: T) -> BytesN<32> {
+    let payload: Val = args.into_val(env);
+    env.crypto()
+        .sha256(&(operation.clone(), payload).to_xdr(env))
+        .into()
+}
+
+pub fn get_proposal(env: &Env, proposal_id: u64) -> Option<MultisigProposal> {
+    env.storage().persistent().get(&(PROPOSAL, proposal_id))
+}
+
+fn require_signer(env: &Env, signer: &Address) -> Result<MultisigConfig, MultisigError> {
+    let config = get_config(env).ok_or(MultisigError::NotConfigured)?;
+    if !config.signers.contains(signer) {
+        return Err(MultisigError::NotSigner);
+    }
+    Ok(config)
+}
+
+fn require_open(env: &Env, config: &MultisigConfig, proposal: &MultisigProposal) -> Result<(), MultisigError> {
+    if proposal.executed {
+        return Err(MultisigError::AlreadyExecuted);
+    }
+    if env.ledger().timestamp() > proposal.created_at.saturating_add(config.window_secs) {
+        return Err(MultisigError::ProposalExpired);
+    }
+    Ok(())
+}
+
+/// Open a proposal for `operation` with the given action hash. The proposer's
+/// approval is recorded immediately. Returns the proposal id.
+pub fn propose(
+    env: &Env,
+    proposer: &Address,
+    operation: Symbol,
+    action: BytesN<32>,
+) -> Result<u64, MultisigError> {
+    proposer.require_auth();
+    require_signer(env, proposer)?;
+
+    let id: u64 = env.storage().instance().get(&NEXT_PROPOSAL_ID).unwrap_or(1);
+    env.storage().instance().set(&NEXT_PROPOSAL_ID, &(id + 1));
+
+    let mut approvals = Vec::new(env);
+    approvals.push_back(proposer.clone());
+    let proposal = MultisigProposal {
+        id,
+        operation: operation.clone(),
+        action,
+        approvals,
+        created_at: env.ledger().timestamp(),
+        executed: false,
+    };
+    env.storage().persistent().set(&(PROPOSAL, id), &proposal);
+
+    env.events().publish((symbol_short!("ms_prop"), id), (operation, proposer.clone()));
+
+    Ok(id)
+}
+
+/// Add `signer`'s approval to an open proposal. Returns the approval count.
+pub fn approve(env: &Env, signer: &Address, proposal_id: u64) -> Result<u32, MultisigError> {
+    signer.require_auth();
+    let config = require_signer(env, signer)?;
+
+    let mut proposal = get_proposal(env, proposal_id).ok_or(MultisigError::ProposalNotFound)?;
+    require_open(env, &config, &proposal)?;
+    if proposal.approvals.contains(signer) {
+        return Err(MultisigError::AlreadyApproved);
+    }
+
+    proposal.approvals.push_back(signer.clone());
+    env.storage().persistent().set(&(PROPOSAL, proposal_id), &proposal);
+
+    env.events().publish((symbol_short!("ms_appr"), proposal_id), signer.clone());
+
+    Ok(proposal.approvals.len())
+}
+
+/// Consume an approved proposal for `operation` with the given action hash,
+/// marking it executed. The caller performs the operation itself on `Ok`.
+pub fn execute(
+    env: &Env,
+    proposal_id: u64,
+    operation: &Symbol,
+    action: &BytesN<32>,
+) -> Result<(), MultisigError> {
+    let config = get_config(env).ok_or(MultisigError::NotConfigured)?;
+    let mut proposal = get_proposal(env, proposal_id).ok_or(MultisigError::ProposalNotFound)?;
+    require_open(env, &config, &proposal)?;
+
+    if proposal.operation != *operation || proposal.action != *action {
+        return Err(MultisigError::ActionMismatch);
+    }
+
+    // Signers removed since approving no longer count
+    let mut approvals = 0u32;
+    for signer in proposal.approvals.iter() {
+        if config.signers.contains(&signer) {
+            approvals += 1;
+        }
+    }
+    if approvals < config.threshold {
+        return Err(MultisigError::ThresholdNotMet);
+    }
+
+    proposal.executed = true;
+    env.storage().persistent().set(&(PROPOSAL, proposal_id), &proposal);
+
+    env.events().publish((symbol_short!("ms_exec"), proposal_id), operation.clone());
+
+    Ok(())
+}
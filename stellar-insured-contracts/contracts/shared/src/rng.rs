@@ -0,0 +1,57 @@
+//! # Ledger-Seeded Randomness
+//!
+//! Non-replayable 32-byte values for share tokens, API keys and identifiers.
+//! Each value is the SHA-256 of the ledger sequence and timestamp, the caller
+//! address and a per-contract entropy counter, so two calls in the same ledger
+//! never produce the same output.
+//!
+//! The inputs are public, so values are unique but not secret from validators;
+//! don't use them where a party could profit from predicting the outcome.
+
+use soroban_sdk::{symbol_short, xdr::ToXdr, Address, BytesN, Env, Symbol};
+
+/// Instance storage key of the calling contract's entropy counter
+const ENTROPY_COUNTER: Symbol = symbol_short!("RNG_CTR");
+
+/// Draw a fresh 32-byte value for `caller`, advancing the contract's entropy counter
+pub fn random_bytes(env: &Env, caller: &Address) -> BytesN<32> {
+    let counter: u64 = env.storage().instance().get(&ENTROPY_COUNTER).unwrap_or(0);
+    env.storage().instance().set(&ENTROPY_COUNTER, &(counter + 1));
+
+    let seed = (
+        env.ledger().sequence(),
+        env.ledger().timestamp(),
+        caller.clone(),
+        counter,
+    )
+        .to_xdr(env);
+    env.crypto().sha256(&seed).into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, testutils::Address as _};
+
+    #[contract]
+    struct RngHost;
+
+    #[contractimpl]
+    impl RngHost {}
+
+    #[test]
+    fn test_same_ledger_calls_are_distinct() {
+        let env = Env::default();
+        let host = env.register_contract(None, RngHost);
+        let caller = Address::generate(&env);
+
+        env.as_contract(&host, || {
+            let first = random_bytes(&env, &caller);
+            let second = random_bytes(&env, &caller);
+            let other_caller = random_bytes(&env, &Address::generate(&env));
+
+            assert_ne!(first, second);
+            assert_ne!(second, other_caller);
+        });
+    }
+}
@@ -30,13 +30,13 @@ fn test_event_severity() {
     // Test that all event severity levels can be created
     let info_severity = EventSeverity::Info;
     let warning_severity = EventSeverity::Warning;
-    let error_severity = EventSeverity::Error;
+    let error_severity = EventSeverity::Failure;
     let critical_severity = EventSeverity::Critical;
     
     // Test severity equality
     assert_eq!(info_severity, EventSeverity::Info);
     assert_eq!(warning_severity, EventSeverity::Warning);
-    assert_eq!(error_severity, EventSeverity::Error);
+    assert_eq!(error_severity, EventSeverity::Failure);
     assert_eq!(critical_severity, EventSeverity::Critical);
     
     // Test that severities are different
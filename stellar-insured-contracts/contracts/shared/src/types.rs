@@ -3,7 +3,7 @@
 //! This module defines shared enums and structs that represent core concepts
 //! used across all insurance contracts (policies, claims, governance, etc.).
 
-use soroban_sdk::{contracttype, Address, BytesN, Symbol, Vec};
+use soroban_sdk::{contracttype, Address, Bytes, BytesN, Symbol, Vec};
 
 // ===== Asset Types =====
 
@@ -389,8 +389,8 @@ pub struct ClaimMetadata {
     /// Timestamp of last update
     pub updated_at: u64,
 
-    /// Optional evidence hash (SHA-256)
-    pub evidence_hash: Option<BytesN<32>>,
+    /// Optional evidence hash (32-byte SHA-256)
+    pub evidence_hash: Option<Bytes>,
 }
 
 /// Treasury allocation record
@@ -906,8 +906,8 @@ pub struct PrivacySettings {
     pub privacy_enabled: bool,
     /// Preferred privacy level (1-3)
     pub privacy_level: u32,
-    /// Encryption public key (for confidential data)
-    pub encryption_key: Option<BytesN<32>>,
+    /// Encryption public key, 32 bytes (for confidential data)
+    pub encryption_key: Option<Bytes>,
     /// Data retention period in days
     pub retention_days: u32,
     /// Whether data can be shared with regulators
@@ -1011,7 +1011,7 @@ pub struct ComplianceRecord {
     /// Whether compliant
     pub is_compliant: bool,
     /// Encrypted compliance data (for regulators)
-    pub encrypted_data: Option<BytesN<32>>,
+    pub encrypted_data: Option<Bytes>,
     /// Timestamp
     pub checked_at: u64,
     /// Auditor address (if applicable)
@@ -1062,8 +1062,8 @@ pub struct VerificationMethod {
     pub controller: soroban_sdk::String,
     /// Public key in base58 format
     pub public_key_base58: Option<soroban_sdk::String>,
-    /// Public key in JWK format
-    pub public_key_jwk: Option<PublicKeyJwk>,
+    /// Public key in JWK format (empty if none)
+    pub public_key_jwk: Vec<PublicKeyJwk>,
 }
 
 /// JSON Web Key representation
@@ -1184,6 +1184,18 @@ pub struct ZkIdentityProof {
     pub is_revoked: bool,
 }
 
+/// Audit record kept alongside a revoked identity entity
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RevocationInfo {
+    /// Address that performed the revocation
+    pub by: Address,
+    /// Reason code (e.g. "compromised", "superseded")
+    pub reason: Symbol,
+    /// Revocation timestamp
+    pub at: u64,
+}
+
 /// DID resolution result
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -1,16 +1,21 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, symbol_short, Address, BytesN, Env, Symbol, Vec, String,
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env,
+    Symbol, Vec, String,
 };
 use shared::{
-    ZkIdentityProof, ZkProof, ZkVerificationResult, DidDocument,
+    ZkIdentityProof, ZkProof, ZkVerificationResult, DidDocument, RevocationInfo,
     authorization::{require_admin, require_role, Role},
+    rng,
 };
 
 #[contract]
 pub struct ZkIdentityContract;
 
+/// Storage schema version written by this build of the contract
+const SCHEMA_VERSION: u32 = 1;
+
 // Storage keys
 const ADMIN: Symbol = symbol_short!("ADMIN");
 const PAUSED: Symbol = symbol_short!("PAUSED");
@@ -23,6 +28,15 @@ const CIRCUIT_DEFINITION: Symbol = symbol_short!("CIR_DEF");
 const VERIFICATION_KEY: Symbol = symbol_short!("VER_KEY");
 const PROOF_BATCH: Symbol = symbol_short!("PROOF_BATCH");
 const ZK_IDENTITY_STATE: Symbol = symbol_short!("ZK_STATE");
+const DID_PROOFS: Symbol = symbol_short!("DID_PRF");
+const CIRCUIT_GOVERNANCE: Symbol = symbol_short!("CIR_GOV");
+const CIRCUIT_APPROVALS: Symbol = symbol_short!("CIR_APPR");
+const PROOF_REVOCATION: Symbol = symbol_short!("PRF_REV");
+const CIRCUIT_PROOFS: Symbol = symbol_short!("CIR_PRF");
+
+// Proof status buckets for the per-circuit index
+const STATUS_VALID: Symbol = symbol_short!("valid");
+const STATUS_REVOKED: Symbol = symbol_short!("revoked");
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -44,6 +58,8 @@ pub enum ContractError {
     BatchInvalid = 15,
     IdentityNotCommitted = 16,
     CommitmentInvalid = 17,
+    ProofAlreadyRevoked = 18,
+    CircuitInactive = 19,
 }
 
 /// Circuit definition for ZK proofs
@@ -60,6 +76,28 @@ pub struct CircuitDefinition {
     pub created_at: u64,
     pub is_active: bool,
     pub verification_required: bool,
+    pub max_validity_days: u32, // Longest expiry a proof under this circuit may request
+}
+
+/// Circuit registration input used by batched registration
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CircuitInput {
+    pub circuit_id: Symbol,
+    pub circuit_name: String,
+    pub circuit_type: Symbol,
+    pub description: String,
+    pub num_public_inputs: u32,
+    pub num_private_inputs: u32,
+    pub verification_required: bool,
+}
+
+/// Verifier set that must approve new circuits before they activate
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CircuitGovernance {
+    pub approvers: Vec<Address>,
+    pub threshold: u32,
 }
 
 /// Verification key for a circuit
@@ -87,6 +125,7 @@ pub struct ProofBatch {
     pub created_at: u64,
     pub verification_result: Symbol, // "pending", "valid", "invalid"
     pub verified_at: Option<u64>,
+    pub verified_count: u32, // Leading proofs already verified by chunks
 }
 
 /// ZK identity state commitment
@@ -94,6 +133,7 @@ pub struct ProofBatch {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ZkIdentityState {
     pub did: String,
+    pub controller: Address, // Address that committed the identity and may revoke its proofs
     pub identity_nullifier: BytesN<32>,
     pub identity_commitment: BytesN<32>,
     pub latest_proof_id: Option<BytesN<32>>,
@@ -123,25 +163,161 @@ fn set_paused(env: &Env, paused: bool) {
     env.storage().persistent().set(&PAUSED, &paused);
 }
 
+/// New circuits start pending when circuit governance is configured
+fn circuits_require_approval(env: &Env) -> bool {
+    env.storage().persistent().has(&CIRCUIT_GOVERNANCE)
+}
+
+const MAX_CIRCUIT_BATCH_SIZE: u32 = 20;
+
+/// Proof validity cap for circuits that don't configure their own
+const DEFAULT_MAX_PROOF_VALIDITY_DAYS: u32 = 365;
+
 fn get_next_proof_id(env: &Env) -> u64 {
     let current: u64 = env.storage().persistent().get(&PROOF_COUNTER).unwrap_or(0);
     env.storage().persistent().set(&PROOF_COUNTER, &(current + 1));
     current + 1
 }
 
-/// Generate unique proof ID
-fn generate_proof_id(env: &Env, did: &String, circuit_id: &Symbol) -> BytesN<32> {
-    let timestamp = env.ledger().timestamp();
-    let combined = format!("{}:{}:{}", did, circuit_id, timestamp);
-    // In production, use proper hash function
-    BytesN::from_array(env, &[
-        (timestamp >> 24) as u8,
-        (timestamp >> 16) as u8,
-        (timestamp >> 8) as u8,
-        timestamp as u8,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    ])
+fn get_circuit_proofs(env: &Env, circuit_id: &Symbol, status: &Symbol) -> Vec<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&(CIRCUIT_PROOFS, circuit_id.clone(), status.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+fn set_circuit_proofs(env: &Env, circuit_id: &Symbol, status: &Symbol, proof_ids: &Vec<BytesN<32>>) {
+    env.storage()
+        .persistent()
+        .set(&(CIRCUIT_PROOFS, circuit_id.clone(), status.clone()), proof_ids);
+}
+
+/// Mark a proof revoked and record who revoked it and why
+fn mark_proof_revoked(env: &Env, proof: ZkIdentityProof, revoker: Address, reason: Symbol) {
+    let circuit_id = proof.circuit_id.clone();
+    let proof_id = record_revocation(env, proof, revoker, reason);
+
+    // Move the proof from the circuit's valid bucket to its revoked bucket
+    let mut valid = get_circuit_proofs(env, &circuit_id, &STATUS_VALID);
+    if let Some(index) = valid.first_index_of(&proof_id) {
+        valid.remove(index);
+        set_circuit_proofs(env, &circuit_id, &STATUS_VALID, &valid);
+    }
+    let mut revoked = get_circuit_proofs(env, &circuit_id, &STATUS_REVOKED);
+    revoked.push_back(proof_id);
+    set_circuit_proofs(env, &circuit_id, &STATUS_REVOKED, &revoked);
+}
+
+/// Flag a proof revoked and record who revoked it, leaving the circuit's
+/// status buckets to the caller. Returns the proof id.
+fn record_revocation(env: &Env, mut proof: ZkIdentityProof, revoker: Address, reason: Symbol) -> BytesN<32> {
+    proof.is_revoked = true;
+    env.storage()
+        .persistent()
+        .set(&(ZK_PROOF, proof.proof_id.clone()), &proof);
+
+    let info = RevocationInfo {
+        by: revoker,
+        reason: reason.clone(),
+        at: env.ledger().timestamp(),
+    };
+    env.storage()
+        .persistent()
+        .set(&(PROOF_REVOCATION, proof.proof_id.clone()), &info);
+
+    env.events().publish(
+        (Symbol::new(env, "proof_revoked"), proof.did),
+        (proof.proof_id.clone(), reason),
+    );
+
+    proof.proof_id
+}
+
+/// Lowercase hex encoding of an identity commitment, the form in which a
+/// proof's public inputs carry it
+fn commitment_hex(env: &Env, commitment: &BytesN<32>) -> String {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let mut buf = [0u8; 64];
+    for (i, byte) in commitment.to_array().iter().enumerate() {
+        buf[i * 2] = HEX[(byte >> 4) as usize];
+        buf[i * 2 + 1] = HEX[(byte & 0x0f) as usize];
+    }
+    String::from_bytes(env, &buf)
+}
+
+/// Load a batch owned by `verifier` with its circuit and verification key
+fn load_batch(
+    env: &Env,
+    verifier: &Address,
+    batch_id: u64,
+) -> Result<(ProofBatch, CircuitDefinition, VerificationKey), ContractError> {
+    let batch: ProofBatch = env
+        .storage()
+        .persistent()
+        .get(&(PROOF_BATCH, batch_id))
+        .ok_or(ContractError::NotFound)?;
+
+    if batch.verifier != *verifier {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let circuit: CircuitDefinition = env
+        .storage()
+        .persistent()
+        .get(&(CIRCUIT_DEFINITION, batch.circuit_id.clone()))
+        .ok_or(ContractError::CircuitNotRegistered)?;
+
+    let verification_key: VerificationKey = env
+        .storage()
+        .persistent()
+        .get(&(VERIFICATION_KEY, batch.circuit_id.clone()))
+        .ok_or(ContractError::VerificationFailed)?;
+
+    Ok((batch, circuit, verification_key))
+}
+
+/// Whether every proof in `batch.proof_ids[start..end]` verifies
+fn proofs_valid(
+    env: &Env,
+    batch: &ProofBatch,
+    start: u32,
+    end: u32,
+    circuit: &CircuitDefinition,
+    verification_key: &VerificationKey,
+) -> bool {
+    for i in start..end {
+        let proof_id = batch.proof_ids.get(i).unwrap();
+        match env.storage().persistent().get(&(ZK_PROOF, proof_id)) {
+            Some(zk_proof) => {
+                if verify_zk_proof_with_circuit(env, &zk_proof, circuit, verification_key)
+                    != Ok(ZkVerificationResult::Valid)
+                {
+                    return false;
+                }
+            }
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Record the batch's final result
+fn finalize_batch(env: &Env, batch: &mut ProofBatch, all_valid: bool) {
+    batch.verification_result = if all_valid {
+        Symbol::new(env, "valid")
+    } else {
+        Symbol::new(env, "invalid")
+    };
+    batch.verified_at = Some(env.ledger().timestamp());
+
+    env.storage()
+        .persistent()
+        .set(&(PROOF_BATCH, batch.batch_id), batch);
+
+    env.events().publish(
+        (Symbol::new(env, "batch_verified"), batch.verifier.clone()),
+        (batch.batch_id, all_valid),
+    );
 }
 
 /// Verify ZK proof with circuit-specific validation
@@ -239,8 +415,9 @@ impl ZkIdentityContract {
             num_private_inputs,
             creator: creator.clone(),
             created_at: env.ledger().timestamp(),
-            is_active: true,
+            is_active: !circuits_require_approval(&env),
             verification_required,
+            max_validity_days: DEFAULT_MAX_PROOF_VALIDITY_DAYS,
         };
 
         env.storage()
@@ -255,6 +432,193 @@ impl ZkIdentityContract {
         Ok(())
     }
 
+    /// Register several circuits in one transaction. The whole batch is
+    /// rejected if any circuit id already exists or has invalid input counts.
+    pub fn register_circuits_batch(
+        env: Env,
+        creator: Address,
+        circuits: Vec<CircuitInput>,
+    ) -> Result<u32, ContractError> {
+        creator.require_auth();
+
+        if is_paused(&env) {
+            return Err(ContractError::Paused);
+        }
+
+        if circuits.is_empty() || circuits.len() > MAX_CIRCUIT_BATCH_SIZE {
+            return Err(ContractError::InvalidInput);
+        }
+
+        // Validate everything before writing anything
+        let mut seen: Vec<Symbol> = Vec::new(&env);
+        for input in circuits.iter() {
+            if input.num_public_inputs == 0 || input.num_private_inputs == 0 {
+                return Err(ContractError::InvalidInput);
+            }
+            if seen.contains(&input.circuit_id)
+                || env
+                    .storage()
+                    .persistent()
+                    .has(&(CIRCUIT_DEFINITION, input.circuit_id.clone()))
+            {
+                return Err(ContractError::AlreadyExists);
+            }
+            seen.push_back(input.circuit_id.clone());
+        }
+
+        let now = env.ledger().timestamp();
+        let is_active = !circuits_require_approval(&env);
+        for input in circuits.iter() {
+            let circuit = CircuitDefinition {
+                circuit_id: input.circuit_id.clone(),
+                circuit_name: input.circuit_name,
+                circuit_type: input.circuit_type,
+                description: input.description,
+                num_public_inputs: input.num_public_inputs,
+                num_private_inputs: input.num_private_inputs,
+                creator: creator.clone(),
+                created_at: now,
+                is_active,
+                verification_required: input.verification_required,
+                max_validity_days: DEFAULT_MAX_PROOF_VALIDITY_DAYS,
+            };
+
+            env.storage()
+                .persistent()
+                .set(&(CIRCUIT_DEFINITION, input.circuit_id), &circuit);
+        }
+
+        let count = circuits.len();
+        env.events().publish(
+            (symbol_short!("cir_batch"), creator),
+            count,
+        );
+
+        Ok(count)
+    }
+
+    /// Require M-of-N verifier approvals before new circuits activate.
+    /// An empty approver list turns circuit governance off again; circuits
+    /// still pending then are activated by the admin via `approve_circuit`.
+    pub fn set_circuit_governance(
+        env: Env,
+        admin: Address,
+        approvers: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        require_admin(&env, &admin)?;
+
+        if approvers.is_empty() {
+            env.storage().persistent().remove(&CIRCUIT_GOVERNANCE);
+        } else {
+            if threshold == 0 || threshold > approvers.len() {
+                return Err(ContractError::InvalidInput);
+            }
+            env.storage()
+                .persistent()
+                .set(&CIRCUIT_GOVERNANCE, &CircuitGovernance { approvers, threshold });
+        }
+
+        env.events().publish((symbol_short!("cir_gov"), admin), threshold);
+
+        Ok(())
+    }
+
+    /// Approve a pending circuit. The circuit activates once the approval
+    /// threshold is reached. With circuit governance turned off, a circuit
+    /// left pending is activated by the admin's approval alone. Returns the
+    /// number of approvals so far.
+    pub fn approve_circuit(
+        env: Env,
+        approver: Address,
+        circuit_id: Symbol,
+    ) -> Result<u32, ContractError> {
+        approver.require_auth();
+
+        let governance: Option<CircuitGovernance> = env.storage().persistent().get(&CIRCUIT_GOVERNANCE);
+        let threshold = match &governance {
+            Some(governance) if governance.approvers.contains(&approver) => governance.threshold,
+            Some(_) => return Err(ContractError::Unauthorized),
+            None => {
+                require_admin(&env, &approver)?;
+                1
+            }
+        };
+
+        let mut circuit: CircuitDefinition = env
+            .storage()
+            .persistent()
+            .get(&(CIRCUIT_DEFINITION, circuit_id.clone()))
+            .ok_or(ContractError::CircuitNotRegistered)?;
+
+        if circuit.is_active {
+            return Err(ContractError::InvalidState);
+        }
+
+        let mut approvals: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&(CIRCUIT_APPROVALS, circuit_id.clone()))
+            .unwrap_or(Vec::new(&env));
+
+        if approvals.contains(&approver) {
+            return Err(ContractError::AlreadyExists);
+        }
+
+        approvals.push_back(approver.clone());
+        env.storage()
+            .persistent()
+            .set(&(CIRCUIT_APPROVALS, circuit_id.clone()), &approvals);
+
+        if approvals.len() >= threshold {
+            circuit.is_active = true;
+            env.storage()
+                .persistent()
+                .set(&(CIRCUIT_DEFINITION, circuit_id.clone()), &circuit);
+
+            env.events().publish((symbol_short!("cir_actv"), circuit_id.clone()), approvals.len());
+        }
+
+        env.events().publish((symbol_short!("cir_appr"), approver), circuit_id);
+
+        Ok(approvals.len())
+    }
+
+    /// Set the longest validity a proof under a circuit may request.
+    /// Callable by the admin or the circuit creator.
+    pub fn set_circuit_max_validity(
+        env: Env,
+        caller: Address,
+        circuit_id: Symbol,
+        max_validity_days: u32,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+
+        if max_validity_days == 0 {
+            return Err(ContractError::InvalidInput);
+        }
+
+        let mut circuit: CircuitDefinition = env
+            .storage()
+            .persistent()
+            .get(&(CIRCUIT_DEFINITION, circuit_id.clone()))
+            .ok_or(ContractError::CircuitNotRegistered)?;
+
+        if caller != circuit.creator {
+            require_admin(&env, &caller)?;
+        }
+
+        circuit.max_validity_days = max_validity_days;
+        env.storage()
+            .persistent()
+            .set(&(CIRCUIT_DEFINITION, circuit_id.clone()), &circuit);
+
+        env.events().publish((symbol_short!("cir_valid"), circuit_id), max_validity_days);
+
+        Ok(())
+    }
+
     /// Register verification key for a circuit
     pub fn register_verification_key(
         env: Env,
@@ -303,22 +667,34 @@ impl ZkIdentityContract {
         Ok(())
     }
 
-    /// Create identity commitment
+    /// Create identity commitment. `controller` becomes the DID's
+    /// controller; only it may replace the commitment later.
     pub fn create_identity_commitment(
         env: Env,
+        controller: Address,
         did: String,
         identity_nullifier: BytesN<32>,
         identity_commitment: BytesN<32>,
     ) -> Result<(), ContractError> {
-        // This would typically require proof of ownership of the DID
-        // For now, we'll allow anyone to create a commitment
+        controller.require_auth();
 
         if is_paused(&env) {
             return Err(ContractError::Paused);
         }
 
+        if let Some(existing) = env
+            .storage()
+            .persistent()
+            .get::<_, ZkIdentityState>(&(ZK_IDENTITY_STATE, did.clone()))
+        {
+            if existing.controller != controller {
+                return Err(ContractError::Unauthorized);
+            }
+        }
+
         let identity_state = ZkIdentityState {
             did: did.clone(),
+            controller,
             identity_nullifier,
             identity_commitment,
             latest_proof_id: None,
@@ -362,6 +738,14 @@ impl ZkIdentityContract {
             .get(&(CIRCUIT_DEFINITION, circuit_id))
             .ok_or(ContractError::CircuitNotRegistered)?;
 
+        if !circuit.is_active {
+            return Err(ContractError::CircuitInactive);
+        }
+
+        if expires_in_days > circuit.max_validity_days {
+            return Err(ContractError::InvalidInput);
+        }
+
         let verification_key: VerificationKey = env
             .storage()
             .persistent()
@@ -369,13 +753,18 @@ impl ZkIdentityContract {
             .ok_or(ContractError::VerificationFailed)?;
 
         // Check if identity commitment exists
-        let _identity_state: ZkIdentityState = env
+        let identity_state: ZkIdentityState = env
             .storage()
             .persistent()
             .get(&(ZK_IDENTITY_STATE, did.clone()))
             .ok_or(ContractError::IdentityNotCommitted)?;
 
-        let proof_id = generate_proof_id(&env, &did, &circuit_id);
+        // The proof must be bound to the DID's committed identity
+        if !public_inputs.contains(&commitment_hex(&env, &identity_state.identity_commitment)) {
+            return Err(ContractError::CommitmentInvalid);
+        }
+
+        let proof_id = rng::random_bytes(&env, &submitter);
         let expires_at = env.ledger().timestamp() + (expires_in_days as u64 * 86400);
 
         let zk_proof = ZkIdentityProof {
@@ -402,6 +791,22 @@ impl ZkIdentityContract {
             .persistent()
             .set(&(ZK_PROOF, proof_id.clone()), &zk_proof);
 
+        // Index the proof under its DID
+        let mut did_proofs: Vec<BytesN<32>> = env
+            .storage()
+            .persistent()
+            .get(&(DID_PROOFS, did.clone()))
+            .unwrap_or(Vec::new(&env));
+        did_proofs.push_back(proof_id.clone());
+        env.storage()
+            .persistent()
+            .set(&(DID_PROOFS, did.clone()), &did_proofs);
+
+        // Index the proof under its circuit as valid
+        let mut circuit_proofs = get_circuit_proofs(&env, &circuit_id, &STATUS_VALID);
+        circuit_proofs.push_back(proof_id.clone());
+        set_circuit_proofs(&env, &circuit_id, &STATUS_VALID, &circuit_proofs);
+
         // Update identity state
         let mut identity_state: ZkIdentityState = env
             .storage()
@@ -447,14 +852,7 @@ impl ZkIdentityContract {
             .ok_or(ContractError::CircuitNotRegistered)?;
 
         let batch_id = get_next_proof_id(&env);
-        let batch_hash = BytesN::from_array(&env, &[
-            (batch_id >> 24) as u8,
-            (batch_id >> 16) as u8,
-            (batch_id >> 8) as u8,
-            batch_id as u8,
-            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        ]);
+        let batch_hash = rng::random_bytes(&env, &verifier);
 
         let batch = ProofBatch {
             batch_id,
@@ -465,6 +863,7 @@ impl ZkIdentityContract {
             created_at: env.ledger().timestamp(),
             verification_result: Symbol::new(&env, "pending"),
             verified_at: None,
+            verified_count: 0,
         };
 
         env.storage()
@@ -487,81 +886,81 @@ impl ZkIdentityContract {
     ) -> Result<bool, ContractError> {
         verifier.require_auth();
 
-        let mut batch: ProofBatch = env
-            .storage()
-            .persistent()
-            .get(&(PROOF_BATCH, batch_id))
-            .ok_or(ContractError::NotFound)?;
+        let (mut batch, circuit, verification_key) = load_batch(&env, &verifier, batch_id)?;
 
-        if batch.verifier != verifier {
-            return Err(ContractError::Unauthorized);
+        let len = batch.proof_ids.len();
+        let all_valid = proofs_valid(&env, &batch, 0, len, &circuit, &verification_key);
+        if all_valid {
+            batch.verified_count = len;
         }
+        finalize_batch(&env, &mut batch, all_valid);
 
-        // Get circuit and verification key
-        let circuit: CircuitDefinition = env
-            .storage()
-            .persistent()
-            .get(&(CIRCUIT_DEFINITION, batch.circuit_id))
-            .ok_or(ContractError::CircuitNotRegistered)?;
+        Ok(all_valid)
+    }
 
-        let verification_key: VerificationKey = env
-            .storage()
-            .persistent()
-            .get(&(VERIFICATION_KEY, batch.circuit_id))
-            .ok_or(ContractError::VerificationFailed)?;
+    /// Verify `count` proofs of a batch starting at `start`, so large
+    /// batches can be verified across several transactions. Chunks must be
+    /// verified in order, each starting where the last one stopped. Returns
+    /// `None` while proofs remain, or the final result once the last chunk
+    /// is verified or any proof fails.
+    pub fn verify_batch_chunk(
+        env: Env,
+        verifier: Address,
+        batch_id: u64,
+        start: u32,
+        count: u32,
+    ) -> Result<Option<bool>, ContractError> {
+        verifier.require_auth();
 
-        let mut all_valid = true;
-
-        // Verify each proof in the batch
-        for proof_id in batch.proof_ids.iter() {
-            if let Some(zk_proof) = env.storage().persistent().get(&(ZK_PROOF, proof_id)) {
-                match verify_zk_proof_with_circuit(&env, &zk_proof, &circuit, &verification_key) {
-                    Ok(ZkVerificationResult::Valid) => continue,
-                    _ => {
-                        all_valid = false;
-                        break;
-                    }
-                }
-            } else {
-                all_valid = false;
-                break;
-            }
+        let (mut batch, circuit, verification_key) = load_batch(&env, &verifier, batch_id)?;
+
+        if batch.verification_result != Symbol::new(&env, "pending") {
+            return Err(ContractError::InvalidState);
+        }
+        if count == 0 || start != batch.verified_count {
+            return Err(ContractError::InvalidInput);
         }
 
-        // Update batch verification result
-        batch.verification_result = if all_valid {
-            Symbol::new(&env, "valid")
-        } else {
-            Symbol::new(&env, "invalid")
-        };
-        batch.verified_at = Some(env.ledger().timestamp());
+        let end = start.saturating_add(count).min(batch.proof_ids.len());
+        if !proofs_valid(&env, &batch, start, end, &circuit, &verification_key) {
+            finalize_batch(&env, &mut batch, false);
+            return Ok(Some(false));
+        }
+
+        batch.verified_count = end;
+        if end == batch.proof_ids.len() {
+            finalize_batch(&env, &mut batch, true);
+            return Ok(Some(true));
+        }
 
         env.storage()
             .persistent()
             .set(&(PROOF_BATCH, batch_id), &batch);
 
         env.events().publish(
-            (symbol_short!("batch_verified"), verifier),
-            (batch_id, all_valid),
+            (symbol_short!("batch_chk"), verifier),
+            (batch_id, end),
         );
 
-        Ok(all_valid)
+        Ok(None)
     }
 
-    /// Revoke ZK proof
+    /// Revoke ZK proof, recording who revoked it and why. Callable by the
+    /// DID's controller or the admin.
     pub fn revoke_proof(
         env: Env,
+        revoker: Address,
         did: String,
         proof_id: BytesN<32>,
+        reason: Symbol,
     ) -> Result<(), ContractError> {
-        // This would typically require proof of ownership of the DID
-        // For now, we'll allow the DID owner to revoke their own proofs
+        revoker.require_auth();
 
         if is_paused(&env) {
             return Err(ContractError::Paused);
         }
 
-        let mut proof: ZkIdentityProof = env
+        let proof: ZkIdentityProof = env
             .storage()
             .persistent()
             .get(&(ZK_PROOF, proof_id))
@@ -571,15 +970,111 @@ impl ZkIdentityContract {
             return Err(ContractError::Unauthorized);
         }
 
-        proof.is_revoked = true;
-        env.storage()
+        let identity_state: ZkIdentityState = env
+            .storage()
             .persistent()
-            .set(&(ZK_PROOF, proof_id), &proof);
+            .get(&(ZK_IDENTITY_STATE, did))
+            .ok_or(ContractError::IdentityNotCommitted)?;
+        let admin: Address = env.storage().persistent().get(&ADMIN).ok_or(ContractError::NotInitialized)?;
+        if revoker != identity_state.controller && revoker != admin {
+            return Err(ContractError::Unauthorized);
+        }
 
-        env.events().publish(
-            (symbol_short!("proof_revoked"), did),
-            proof_id,
-        );
+        if proof.is_revoked {
+            return Err(ContractError::ProofAlreadyRevoked);
+        }
+
+        mark_proof_revoked(&env, proof, revoker, reason);
+
+        Ok(())
+    }
+
+    /// Revoke every outstanding proof issued under a circuit, e.g. after a
+    /// flaw is found in it. Callable by the admin or the circuit creator.
+    /// Returns the number of proofs revoked.
+    pub fn revoke_proofs_for_circuit(
+        env: Env,
+        caller: Address,
+        circuit_id: Symbol,
+    ) -> Result<u32, ContractError> {
+        caller.require_auth();
+
+        if is_paused(&env) {
+            return Err(ContractError::Paused);
+        }
+
+        let circuit: CircuitDefinition = env
+            .storage()
+            .persistent()
+            .get(&(CIRCUIT_DEFINITION, circuit_id.clone()))
+            .ok_or(ContractError::CircuitNotRegistered)?;
+
+        let admin: Address = env.storage().persistent().get(&ADMIN).ok_or(ContractError::NotInitialized)?;
+        if caller != admin && caller != circuit.creator {
+            return Err(ContractError::Unauthorized);
+        }
+
+        // Every proof in the valid bucket is revoked, so the buckets are
+        // rewritten once rather than per proof
+        let proof_ids = get_circuit_proofs(&env, &circuit_id, &STATUS_VALID);
+        let mut revoked_ids = get_circuit_proofs(&env, &circuit_id, &STATUS_REVOKED);
+
+        let mut revoked = 0u32;
+        for proof_id in proof_ids.iter() {
+            if let Some(proof) = env.storage().persistent().get::<_, ZkIdentityProof>(&(ZK_PROOF, proof_id)) {
+                if !proof.is_revoked {
+                    revoked_ids.push_back(record_revocation(&env, proof, caller.clone(), symbol_short!("circuit")));
+                    revoked += 1;
+                }
+            }
+        }
+
+        set_circuit_proofs(&env, &circuit_id, &STATUS_VALID, &Vec::new(&env));
+        set_circuit_proofs(&env, &circuit_id, &STATUS_REVOKED, &revoked_ids);
+
+        Ok(revoked)
+    }
+
+    /// Keep a DID's identity state, proof index and proofs alive for at
+    /// least `ledgers` more ledgers. Entries that already live longer are
+    /// left as they are.
+    pub fn bump_did_ttl(env: Env, did: String, ledgers: u32) -> Result<(), ContractError> {
+        if ledgers == 0 {
+            return Err(ContractError::InvalidInput);
+        }
+
+        let storage = env.storage().persistent();
+        let state_key = (ZK_IDENTITY_STATE, did.clone());
+        if !storage.has(&state_key) {
+            return Err(ContractError::IdentityNotCommitted);
+        }
+        storage.extend_ttl(&state_key, ledgers, ledgers);
+
+        let index_key = (DID_PROOFS, did);
+        if let Some(proof_ids) = storage.get::<_, Vec<BytesN<32>>>(&index_key) {
+            storage.extend_ttl(&index_key, ledgers, ledgers);
+            for proof_id in proof_ids.iter() {
+                let proof_key = (ZK_PROOF, proof_id);
+                if storage.has(&proof_key) {
+                    storage.extend_ttl(&proof_key, ledgers, ledgers);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Keep a single proof alive for at least `ledgers` more ledgers
+    pub fn bump_proof_ttl(env: Env, proof_id: BytesN<32>, ledgers: u32) -> Result<(), ContractError> {
+        if ledgers == 0 {
+            return Err(ContractError::InvalidInput);
+        }
+
+        let key = (ZK_PROOF, proof_id);
+        if !env.storage().persistent().has(&key) {
+            return Err(ContractError::NotFound);
+        }
+        env.storage().persistent().extend_ttl(&key, ledgers, ledgers);
 
         Ok(())
     }
@@ -605,16 +1100,34 @@ impl ZkIdentityContract {
 
     // ===== View Functions =====
 
+    /// Health check for monitoring: (initialized, paused, schema version)
+    pub fn health(env: Env) -> (bool, bool, u32) {
+        (env.storage().persistent().has(&ADMIN), is_paused(&env), SCHEMA_VERSION)
+    }
+
     /// Get ZK proof
     pub fn get_zk_proof(env: Env, proof_id: BytesN<32>) -> Option<ZkIdentityProof> {
         env.storage().persistent().get(&(ZK_PROOF, proof_id))
     }
 
+    /// Get who revoked a proof, why and when
+    pub fn get_proof_revocation(env: Env, proof_id: BytesN<32>) -> Option<RevocationInfo> {
+        env.storage().persistent().get(&(PROOF_REVOCATION, proof_id))
+    }
+
     /// Get circuit definition
     pub fn get_circuit_definition(env: Env, circuit_id: Symbol) -> Option<CircuitDefinition> {
         env.storage().persistent().get(&(CIRCUIT_DEFINITION, circuit_id))
     }
 
+    /// Get the verifiers who have approved a circuit
+    pub fn get_circuit_approvals(env: Env, circuit_id: Symbol) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&(CIRCUIT_APPROVALS, circuit_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
     /// Get verification key
     pub fn get_verification_key(env: Env, circuit_id: Symbol) -> Option<VerificationKey> {
         env.storage().persistent().get(&(VERIFICATION_KEY, circuit_id))
@@ -654,26 +1167,151 @@ impl ZkIdentityContract {
         verify_zk_proof_with_circuit(&env, &proof, &circuit, &verification_key)
     }
 
+    /// Check whether a proof would verify against a circuit without
+    /// submitting it. Nothing is stored and no identity commitment is needed;
+    /// proofs the circuit rejects as malformed report `Invalid`.
+    pub fn dry_run_verify(
+        env: Env,
+        circuit_id: Symbol,
+        public_inputs: Vec<String>,
+        proof_data: BytesN<32>,
+    ) -> ZkVerificationResult {
+        let circuit: CircuitDefinition = match env
+            .storage()
+            .persistent()
+            .get(&(CIRCUIT_DEFINITION, circuit_id.clone()))
+        {
+            Some(circuit) => circuit,
+            None => return ZkVerificationResult::Invalid,
+        };
+        let verification_key: VerificationKey = match env
+            .storage()
+            .persistent()
+            .get(&(VERIFICATION_KEY, circuit_id.clone()))
+        {
+            Some(verification_key) => verification_key,
+            None => return ZkVerificationResult::Invalid,
+        };
+
+        // Transient proof shaped as submit_zk_proof would build it
+        let now = env.ledger().timestamp();
+        let proof = ZkIdentityProof {
+            proof_id: BytesN::from_array(&env, &[0u8; 32]),
+            did: String::from_str(&env, ""),
+            circuit_id,
+            public_inputs,
+            proof_data,
+            verification_key_hash: verification_key.key_hash.clone(),
+            created_at: now,
+            expires_at: now + (circuit.max_validity_days as u64 * 86400),
+            is_revoked: false,
+        };
+
+        verify_zk_proof_with_circuit(&env, &proof, &circuit, &verification_key)
+            .unwrap_or(ZkVerificationResult::Invalid)
+    }
+
     /// Get all proofs for a DID
     pub fn get_proofs_for_did(env: Env, did: String) -> Vec<ZkIdentityProof> {
-        // In production, maintain an index for efficient querying
-        // For now, return empty vector
-        Vec::new(&env)
+        let proof_ids: Vec<BytesN<32>> = env
+            .storage()
+            .persistent()
+            .get(&(DID_PROOFS, did))
+            .unwrap_or(Vec::new(&env));
+
+        let mut proofs = Vec::new(&env);
+        for proof_id in proof_ids.iter() {
+            if let Some(proof) = env.storage().persistent().get(&(ZK_PROOF, proof_id)) {
+                proofs.push_back(proof);
+            }
+        }
+        proofs
     }
 
-    /// Check if DID has valid proof for circuit
+    /// Page through the proofs issued under a circuit. With `only_valid`,
+    /// revoked and expired proofs are skipped; otherwise valid proofs are
+    /// listed first, followed by revoked ones.
+    pub fn list_proofs_by_circuit(
+        env: Env,
+        circuit_id: Symbol,
+        only_valid: bool,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<ZkIdentityProof> {
+        let now = env.ledger().timestamp();
+        let mut proof_ids = get_circuit_proofs(&env, &circuit_id, &STATUS_VALID);
+        if !only_valid {
+            proof_ids.append(&get_circuit_proofs(&env, &circuit_id, &STATUS_REVOKED));
+        }
+
+        let mut proofs = Vec::new(&env);
+        let mut skipped = 0u32;
+        for proof_id in proof_ids.iter() {
+            if proofs.len() >= limit {
+                break;
+            }
+            let proof: ZkIdentityProof = match env.storage().persistent().get(&(ZK_PROOF, proof_id)) {
+                Some(proof) => proof,
+                None => continue,
+            };
+            if only_valid && now > proof.expires_at {
+                continue;
+            }
+            if skipped < offset {
+                skipped += 1;
+                continue;
+            }
+            proofs.push_back(proof);
+        }
+        proofs
+    }
+
+    /// Report unrevoked proofs for a DID that expire within `within_secs`,
+    /// emitting a `proof_exp` event per proof so off-chain keepers can renew them
+    pub fn poll_expiring_proofs(env: Env, did: String, within_secs: u64) -> Vec<ZkIdentityProof> {
+        let now = env.ledger().timestamp();
+        let horizon = now.saturating_add(within_secs);
+
+        let mut expiring = Vec::new(&env);
+        for proof in Self::get_proofs_for_did(env.clone(), did.clone()).iter() {
+            if proof.is_revoked || proof.expires_at < now || proof.expires_at > horizon {
+                continue;
+            }
+
+            env.events().publish(
+                (symbol_short!("proof_exp"), did.clone()),
+                (proof.proof_id.clone(), proof.expires_at),
+            );
+            expiring.push_back(proof);
+        }
+        expiring
+    }
+
+    /// Check if DID has a valid proof for circuit created within the last `max_age_secs`
     pub fn has_valid_proof_for_circuit(
         env: Env,
         did: String,
         circuit_id: Symbol,
+        max_age_secs: u64,
     ) -> bool {
-        if let Some(identity_state) = Self::get_identity_state(env, did) {
-            if let Some(latest_proof_id) = identity_state.latest_proof_id {
-                if let Ok(result) = Self::verify_proof(env, latest_proof_id) {
-                    return result == ZkVerificationResult::Valid;
-                }
-            }
+        let latest_proof_id = match Self::get_identity_state(env.clone(), did).and_then(|state| state.latest_proof_id) {
+            Some(proof_id) => proof_id,
+            None => return false,
+        };
+        let proof = match Self::get_zk_proof(env.clone(), latest_proof_id.clone()) {
+            Some(proof) => proof,
+            None => return false,
+        };
+
+        if proof.circuit_id != circuit_id
+            || env.ledger().timestamp().saturating_sub(proof.created_at) > max_age_secs
+        {
+            return false;
         }
-        false
+
+        Self::verify_proof(env, latest_proof_id) == Ok(ZkVerificationResult::Valid)
     }
 }
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,529 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::testutils::{storage::Persistent as _, Address as _, Ledger};
+use soroban_sdk::vec;
+
+fn setup(env: &Env) -> (ZkIdentityContractClient<'_>, Address) {
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, ZkIdentityContract);
+    let client = ZkIdentityContractClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+
+    client.initialize(&admin);
+
+    (client, admin)
+}
+
+fn circuit_input(env: &Env, id: &str, num_public_inputs: u32) -> CircuitInput {
+    CircuitInput {
+        circuit_id: Symbol::new(env, id),
+        circuit_name: String::from_str(env, id),
+        circuit_type: Symbol::new(env, "identity"),
+        description: String::from_str(env, "test circuit"),
+        num_public_inputs,
+        num_private_inputs: 1,
+        verification_required: false,
+    }
+}
+
+/// Register an active one-input circuit with a verification key and commit `did`
+fn setup_circuit(env: &Env, client: &ZkIdentityContractClient, id: &str, did: &String) -> Symbol {
+    let creator = Address::generate(env);
+    let circuit_id = Symbol::new(env, id);
+
+    client.register_circuit(
+        &creator,
+        &circuit_id,
+        &String::from_str(env, id),
+        &Symbol::new(env, "identity"),
+        &String::from_str(env, "test circuit"),
+        &1,
+        &1,
+        &false,
+    );
+    client.register_verification_key(
+        &creator,
+        &circuit_id,
+        &BytesN::from_array(env, &[1u8; 32]),
+        &BytesN::from_array(env, &[2u8; 32]),
+        &1,
+    );
+    if client.get_identity_state(did).is_none() {
+        client.create_identity_commitment(
+            &Address::generate(env),
+            did,
+            &BytesN::from_array(env, &[3u8; 32]),
+            &BytesN::from_array(env, &[4u8; 32]),
+        );
+    }
+
+    circuit_id
+}
+
+/// Hex form of the `[4u8; 32]` commitment `setup_circuit` creates
+fn commitment_input(env: &Env) -> String {
+    String::from_str(env, "0404040404040404040404040404040404040404040404040404040404040404")
+}
+
+/// Submit a one-input proof bound to the `setup_circuit` commitment
+fn submit_proof(
+    env: &Env,
+    client: &ZkIdentityContractClient,
+    did: &String,
+    circuit_id: &Symbol,
+    expires_in_days: u32,
+) -> BytesN<32> {
+    client.submit_zk_proof(
+        &Address::generate(env),
+        did,
+        circuit_id,
+        &vec![env, commitment_input(env)],
+        &BytesN::from_array(env, &[5u8; 32]),
+        &expires_in_days,
+    )
+}
+
+#[test]
+fn test_register_circuits_batch() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let creator = Address::generate(&env);
+
+    let circuits = vec![
+        &env,
+        circuit_input(&env, "age", 1),
+        circuit_input(&env, "income", 2),
+        circuit_input(&env, "residency", 1),
+    ];
+
+    assert_eq!(client.register_circuits_batch(&creator, &circuits), 3);
+
+    for id in ["age", "income", "residency"] {
+        let circuit = client.get_circuit_definition(&Symbol::new(&env, id)).unwrap();
+        assert_eq!(circuit.creator, creator);
+        assert!(circuit.is_active);
+    }
+}
+
+#[test]
+fn test_register_circuits_batch_duplicate_aborts() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let creator = Address::generate(&env);
+
+    client.register_circuits_batch(&creator, &vec![&env, circuit_input(&env, "age", 1)]);
+
+    let circuits = vec![
+        &env,
+        circuit_input(&env, "income", 2),
+        circuit_input(&env, "age", 1),
+    ];
+    let result = client.try_register_circuits_batch(&creator, &circuits);
+    assert_eq!(result, Err(Ok(ContractError::AlreadyExists)));
+
+    // Nothing from the failed batch was registered
+    assert!(client.get_circuit_definition(&Symbol::new(&env, "income")).is_none());
+}
+
+#[test]
+fn test_poll_expiring_proofs() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let did = String::from_str(&env, "did:stellar:alice");
+    let circuit_id = setup_circuit(&env, &client, "age", &did);
+
+    let soon = submit_proof(&env, &client, &did, &circuit_id, 1);
+    let _long_lived = submit_proof(&env, &client, &did, &circuit_id, 365);
+
+    let expiring = client.poll_expiring_proofs(&did, &(7 * 86400));
+    assert_eq!(expiring.len(), 1);
+    assert_eq!(expiring.get(0).unwrap().proof_id, soon);
+}
+
+#[test]
+fn test_bump_did_ttl_extends_entries() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let did = String::from_str(&env, "did:stellar:alice");
+    let circuit_id = setup_circuit(&env, &client, "age", &did);
+    let proof_id = submit_proof(&env, &client, &did, &circuit_id, 30);
+
+    let ttls = || {
+        env.as_contract(&client.address, || {
+            (
+                env.storage().persistent().get_ttl(&(ZK_IDENTITY_STATE, did.clone())),
+                env.storage().persistent().get_ttl(&(ZK_PROOF, proof_id.clone())),
+            )
+        })
+    };
+
+    let (state_before, proof_before) = ttls();
+    client.bump_did_ttl(&did, &100_000);
+    let (state_after, proof_after) = ttls();
+
+    assert!(state_after > state_before);
+    assert!(proof_after > proof_before);
+    // The TTL is raised to the requested ledgers, not by them
+    assert_eq!(state_after, 100_000);
+    assert_eq!(proof_after, 100_000);
+}
+
+#[test]
+fn test_circuit_requires_verifier_approvals() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let creator = Address::generate(&env);
+    let approvers = vec![&env, Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+    client.set_circuit_governance(&admin, &approvers, &2);
+
+    let circuit_id = Symbol::new(&env, "age");
+    client.register_circuit(
+        &creator,
+        &circuit_id,
+        &String::from_str(&env, "age"),
+        &Symbol::new(&env, "identity"),
+        &String::from_str(&env, "test circuit"),
+        &1,
+        &1,
+        &false,
+    );
+    assert!(!client.get_circuit_definition(&circuit_id).unwrap().is_active);
+
+    // Pending circuits can't take a verification key, so no proofs either
+    let register_key = || {
+        client.try_register_verification_key(
+            &creator,
+            &circuit_id,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &BytesN::from_array(&env, &[2u8; 32]),
+            &1,
+        )
+    };
+    assert_eq!(register_key(), Err(Ok(ContractError::CircuitNotRegistered)));
+
+    let outsider = Address::generate(&env);
+    assert_eq!(client.try_approve_circuit(&outsider, &circuit_id), Err(Ok(ContractError::Unauthorized)));
+
+    assert_eq!(client.approve_circuit(&approvers.get(0).unwrap(), &circuit_id), 1);
+    assert_eq!(
+        client.try_approve_circuit(&approvers.get(0).unwrap(), &circuit_id),
+        Err(Ok(ContractError::AlreadyExists))
+    );
+    assert!(!client.get_circuit_definition(&circuit_id).unwrap().is_active);
+
+    assert_eq!(client.approve_circuit(&approvers.get(1).unwrap(), &circuit_id), 2);
+    assert!(client.get_circuit_definition(&circuit_id).unwrap().is_active);
+    assert_eq!(client.get_circuit_approvals(&circuit_id).len(), 2);
+    assert!(register_key().is_ok());
+}
+
+#[test]
+fn test_pending_circuit_rejects_proofs_until_activated() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let approvers = vec![&env, Address::generate(&env), Address::generate(&env)];
+    client.set_circuit_governance(&admin, &approvers, &2);
+
+    let circuit_id = Symbol::new(&env, "age");
+    client.register_circuit(
+        &Address::generate(&env),
+        &circuit_id,
+        &String::from_str(&env, "age"),
+        &Symbol::new(&env, "identity"),
+        &String::from_str(&env, "test circuit"),
+        &1,
+        &1,
+        &false,
+    );
+
+    let did = String::from_str(&env, "did:stellar:alice");
+    let result = client.try_submit_zk_proof(
+        &Address::generate(&env),
+        &did,
+        &circuit_id,
+        &vec![&env, commitment_input(&env)],
+        &BytesN::from_array(&env, &[5u8; 32]),
+        &30,
+    );
+    assert_eq!(result, Err(Ok(ContractError::CircuitInactive)));
+
+    // Turning governance off leaves the circuit for the admin to activate
+    client.set_circuit_governance(&admin, &vec![&env], &0);
+    assert_eq!(
+        client.try_approve_circuit(&approvers.get(0).unwrap(), &circuit_id),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(client.approve_circuit(&admin, &circuit_id), 1);
+    assert!(client.get_circuit_definition(&circuit_id).unwrap().is_active);
+}
+
+#[test]
+fn test_revoke_proof_records_reason() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let did = String::from_str(&env, "did:stellar:alice");
+    let circuit_id = setup_circuit(&env, &client, "age", &did);
+    let proof_id = submit_proof(&env, &client, &did, &circuit_id, 30);
+    let owner = client.get_identity_state(&did).unwrap().controller;
+    let reason = Symbol::new(&env, "key_leaked");
+
+    assert_eq!(
+        client.try_revoke_proof(&Address::generate(&env), &did, &proof_id, &reason),
+        Err(Ok(ContractError::Unauthorized))
+    );
+
+    client.revoke_proof(&owner, &did, &proof_id, &reason);
+
+    assert!(client.get_zk_proof(&proof_id).unwrap().is_revoked);
+    let info = client.get_proof_revocation(&proof_id).unwrap();
+    assert_eq!(info.by, owner);
+    assert_eq!(info.reason, reason);
+    assert_eq!(info.at, env.ledger().timestamp());
+
+    // A second revocation neither overwrites the record nor re-indexes the proof
+    assert_eq!(
+        client.try_revoke_proof(&owner, &did, &proof_id, &Symbol::new(&env, "again")),
+        Err(Ok(ContractError::ProofAlreadyRevoked))
+    );
+    assert_eq!(client.get_proof_revocation(&proof_id).unwrap().reason, reason);
+    assert_eq!(client.list_proofs_by_circuit(&circuit_id, &false, &0, &10).len(), 1);
+}
+
+#[test]
+fn test_revoke_proofs_for_circuit() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let alice = String::from_str(&env, "did:stellar:alice");
+    let bob = String::from_str(&env, "did:stellar:bob");
+    let circuit_id = setup_circuit(&env, &client, "age", &alice);
+    client.create_identity_commitment(
+        &Address::generate(&env),
+        &bob,
+        &BytesN::from_array(&env, &[3u8; 32]),
+        &BytesN::from_array(&env, &[4u8; 32]),
+    );
+
+    let first = submit_proof(&env, &client, &alice, &circuit_id, 30);
+    let second = submit_proof(&env, &client, &bob, &circuit_id, 30);
+    assert_eq!(client.verify_proof(&first), ZkVerificationResult::Valid);
+
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_revoke_proofs_for_circuit(&outsider, &circuit_id),
+        Err(Ok(ContractError::Unauthorized))
+    );
+
+    assert_eq!(client.revoke_proofs_for_circuit(&admin, &circuit_id), 2);
+    assert_eq!(client.verify_proof(&first), ZkVerificationResult::Invalid);
+    assert_eq!(client.verify_proof(&second), ZkVerificationResult::Invalid);
+    assert_eq!(client.get_proof_revocation(&second).unwrap().by, admin);
+
+    // Already-revoked proofs aren't counted again
+    assert_eq!(client.revoke_proofs_for_circuit(&admin, &circuit_id), 0);
+}
+
+#[test]
+fn test_proof_validity_capped_per_circuit() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let did = String::from_str(&env, "did:stellar:alice");
+    let circuit_id = setup_circuit(&env, &client, "age", &did);
+    assert_eq!(
+        client.get_circuit_definition(&circuit_id).unwrap().max_validity_days,
+        DEFAULT_MAX_PROOF_VALIDITY_DAYS
+    );
+
+    client.set_circuit_max_validity(&admin, &circuit_id, &90);
+
+    submit_proof(&env, &client, &did, &circuit_id, 90);
+
+    let result = client.try_submit_zk_proof(
+        &Address::generate(&env),
+        &did,
+        &circuit_id,
+        &vec![&env, commitment_input(&env)],
+        &BytesN::from_array(&env, &[5u8; 32]),
+        &91,
+    );
+    assert_eq!(result, Err(Ok(ContractError::InvalidInput)));
+}
+
+#[test]
+fn test_proof_must_match_identity_commitment() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let did = String::from_str(&env, "did:stellar:alice");
+    let circuit_id = setup_circuit(&env, &client, "age", &did);
+
+    // Inputs committed to a different identity can't be attached to this DID
+    let result = client.try_submit_zk_proof(
+        &Address::generate(&env),
+        &did,
+        &circuit_id,
+        &vec![&env, String::from_str(&env, "0505050505050505050505050505050505050505050505050505050505050505")],
+        &BytesN::from_array(&env, &[5u8; 32]),
+        &30,
+    );
+    assert_eq!(result, Err(Ok(ContractError::CommitmentInvalid)));
+
+    let proof_id = submit_proof(&env, &client, &did, &circuit_id, 30);
+    assert_eq!(client.verify_proof(&proof_id), ZkVerificationResult::Valid);
+}
+
+#[test]
+fn test_same_ledger_proofs_get_distinct_ids() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let did = String::from_str(&env, "did:stellar:alice");
+    let circuit_id = setup_circuit(&env, &client, "age", &did);
+
+    let first = submit_proof(&env, &client, &did, &circuit_id, 30);
+    let second = submit_proof(&env, &client, &did, &circuit_id, 30);
+
+    assert_ne!(first, second);
+    assert!(client.get_zk_proof(&first).is_some());
+    assert!(client.get_zk_proof(&second).is_some());
+}
+
+#[test]
+fn test_has_valid_proof_requires_recent_proof() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let did = String::from_str(&env, "did:stellar:alice");
+    let circuit_id = setup_circuit(&env, &client, "age", &did);
+    let day = 86_400u64;
+
+    submit_proof(&env, &client, &did, &circuit_id, 365);
+    env.ledger().with_mut(|li| li.timestamp += 10 * day);
+
+    // Unexpired, but older than a one-day recency window
+    assert!(!client.has_valid_proof_for_circuit(&did, &circuit_id, &day));
+    assert!(client.has_valid_proof_for_circuit(&did, &circuit_id, &(30 * day)));
+    assert!(!client.has_valid_proof_for_circuit(&did, &Symbol::new(&env, "income"), &(30 * day)));
+
+    submit_proof(&env, &client, &did, &circuit_id, 365);
+    assert!(client.has_valid_proof_for_circuit(&did, &circuit_id, &day));
+}
+
+#[test]
+fn test_list_proofs_by_circuit() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let did = String::from_str(&env, "did:stellar:alice");
+    let age = setup_circuit(&env, &client, "age", &did);
+    let income = setup_circuit(&env, &client, "income", &did);
+
+    let first = submit_proof(&env, &client, &did, &age, 30);
+    let second = submit_proof(&env, &client, &did, &age, 30);
+    let third = submit_proof(&env, &client, &did, &age, 30);
+    let other = submit_proof(&env, &client, &did, &income, 30);
+
+    client.revoke_proof(&admin, &did, &second, &Symbol::new(&env, "leaked"));
+
+    let valid = client.list_proofs_by_circuit(&age, &true, &0, &10);
+    assert_eq!(valid.len(), 2);
+    assert_eq!(valid.get(0).unwrap().proof_id, first);
+    assert_eq!(valid.get(1).unwrap().proof_id, third);
+
+    // Revoked proofs follow the valid ones when included
+    let all = client.list_proofs_by_circuit(&age, &false, &0, &10);
+    assert_eq!(all.len(), 3);
+    assert_eq!(all.get(2).unwrap().proof_id, second);
+
+    let page = client.list_proofs_by_circuit(&age, &false, &1, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().proof_id, third);
+
+    let income_proofs = client.list_proofs_by_circuit(&income, &true, &0, &10);
+    assert_eq!(income_proofs.len(), 1);
+    assert_eq!(income_proofs.get(0).unwrap().proof_id, other);
+
+    // Expired proofs drop out of the valid listing
+    env.ledger().with_mut(|li| li.timestamp += 31 * 86_400);
+    assert_eq!(client.list_proofs_by_circuit(&age, &true, &0, &10).len(), 0);
+    assert_eq!(client.list_proofs_by_circuit(&age, &false, &0, &10).len(), 3);
+}
+
+#[test]
+fn test_verify_batch_in_chunks() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let did = String::from_str(&env, "did:stellar:alice");
+    let circuit_id = setup_circuit(&env, &client, "age", &did);
+
+    let mut proof_ids = Vec::new(&env);
+    for _ in 0..10 {
+        proof_ids.push_back(submit_proof(&env, &client, &did, &circuit_id, 30));
+    }
+    let verifier = Address::generate(&env);
+    let batch_id = client.create_batch_verification(&verifier, &circuit_id, &proof_ids);
+
+    assert_eq!(client.verify_batch_chunk(&verifier, &batch_id, &0, &6), None);
+    let batch = client.get_proof_batch(&batch_id).unwrap();
+    assert_eq!(batch.verified_count, 6);
+    assert_eq!(batch.verification_result, Symbol::new(&env, "pending"));
+    assert!(batch.verified_at.is_none());
+
+    // Chunks must pick up where the last one stopped
+    assert_eq!(
+        client.try_verify_batch_chunk(&verifier, &batch_id, &0, &4),
+        Err(Ok(ContractError::InvalidInput))
+    );
+
+    assert_eq!(client.verify_batch_chunk(&verifier, &batch_id, &6, &4), Some(true));
+    let batch = client.get_proof_batch(&batch_id).unwrap();
+    assert_eq!(batch.verified_count, 10);
+    assert_eq!(batch.verification_result, Symbol::new(&env, "valid"));
+    assert!(batch.verified_at.is_some());
+
+    assert_eq!(
+        client.try_verify_batch_chunk(&verifier, &batch_id, &10, &1),
+        Err(Ok(ContractError::InvalidState))
+    );
+}
+
+#[test]
+fn test_dry_run_verify_stores_nothing() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    let did = String::from_str(&env, "did:stellar:alice");
+    let circuit_id = setup_circuit(&env, &client, "age", &did);
+    let proof_data = BytesN::from_array(&env, &[5u8; 32]);
+
+    // No commitment binding is required of a dry run
+    let well_formed = vec![&env, String::from_str(&env, "over_18")];
+    assert_eq!(
+        client.dry_run_verify(&circuit_id, &well_formed, &proof_data),
+        ZkVerificationResult::Valid
+    );
+
+    // The circuit takes one public input
+    let malformed = vec![&env, commitment_input(&env), String::from_str(&env, "extra")];
+    assert_eq!(
+        client.dry_run_verify(&circuit_id, &malformed, &proof_data),
+        ZkVerificationResult::Invalid
+    );
+    assert_eq!(
+        client.dry_run_verify(&Symbol::new(&env, "unknown"), &well_formed, &proof_data),
+        ZkVerificationResult::Invalid
+    );
+
+    assert!(client.list_proofs_by_circuit(&circuit_id, &false, &0, &10).is_empty());
+    assert!(client.get_proofs_for_did(&did).is_empty());
+    assert!(client.get_identity_state(&did).unwrap().latest_proof_id.is_none());
+}
+
+#[test]
+fn test_health_reflects_pause_state() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    assert_eq!(client.health(), (true, false, 1));
+
+    client.set_paused(&admin, &true);
+    assert_eq!(client.health(), (true, true, 1));
+
+    client.set_paused(&admin, &false);
+    assert_eq!(client.health(), (true, false, 1));
+}